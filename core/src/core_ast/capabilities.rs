@@ -266,6 +266,28 @@ pub trait Scope: IsScope {
     }
 }
 
+/// Where a comment has to be found relative to a symbol to be attached to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CommentPosition {
+    /// Comment on the lines right above the symbol
+    #[default]
+    Leading,
+    /// Comment at the end of the line where the symbol ends
+    Trailing,
+    /// Either leading or trailing, a trailing comment takes precedence
+    Both,
+}
+
+impl CommentPosition {
+    pub fn accepts_leading(&self) -> bool {
+        matches!(self, CommentPosition::Leading | CommentPosition::Both)
+    }
+
+    pub fn accepts_trailing(&self) -> bool {
+        matches!(self, CommentPosition::Trailing | CommentPosition::Both)
+    }
+}
+
 /// Trait implemented by all [AstSymbol]
 pub trait IsComment {
     /// Tell this symbol is a comment
@@ -276,6 +298,13 @@ pub trait IsComment {
     fn is_comment(&self) -> bool {
         false
     }
+
+    /// Tell where comments of this symbol are found
+    ///
+    /// By default, [`CommentPosition::Leading`]
+    fn comment_position(&self) -> CommentPosition {
+        CommentPosition::Leading
+    }
 }
 
 /// Trait implemented by all [AstSymbol]
@@ -1,7 +1,9 @@
 use super::capabilities::*;
 use super::data::*;
+use super::serialize::*;
 use super::symbol::*;
 use super::update::*;
+use super::visit::*;
 use crate::workspace::Document;
 use downcast_rs::{impl_downcast, DowncastSync};
 use lsp_types::Position;
@@ -20,6 +22,7 @@ pub trait AstSymbol:
     DowncastSync
     + Send
     + Sync
+    + std::fmt::Debug
     // capabilities.rs
     + BuildDocumentSymbols
     + BuildSemanticTokens
@@ -40,6 +43,11 @@ pub trait AstSymbol:
     + UpdateDynamic
     + UpdateRange
     + CollectReferences
+    + CollectSymbols
+    // visit.rs
+    + CollectChildren
+    // serialize.rs
+    + SerializeFields
 {
     /// Retrieves the data of the symbol.
     fn get_data(&self) -> &SymbolData;
@@ -47,6 +55,19 @@ pub trait AstSymbol:
     /// Retrieves the mutable data of the symbol.
     fn get_mut_data(&mut self) -> &mut SymbolData;
 
+    /// Returns the name of the symbol's type, without its module path.
+    fn get_type_name(&self) -> &'static str {
+        let name = std::any::type_name::<Self>();
+        name.rsplit("::").next().unwrap_or(name)
+    }
+
+    /// Returns the name of the struct holding the symbol's fields, without its module path.
+    ///
+    /// This is the type name of the symbol, except for choices which return the struct of their variant.
+    fn get_struct_type_name(&self) -> &'static str {
+        self.get_type_name()
+    }
+
     /// Retrieves the text of the symbol based on its range within the provided source code.
     fn get_text<'a>(&self, source_code: &'a [u8]) -> Option<&'a str> {
         let range = self.get_data().get_range();
@@ -58,6 +79,13 @@ pub trait AstSymbol:
         }
     }
 
+    /// Returns the direct children of the symbol, in the order of its fields.
+    fn children(&self) -> std::vec::IntoIter<DynSymbol> {
+        let mut children = vec![];
+        self.collect_children(&mut children);
+        children.into_iter()
+    }
+
     /// Get the symbol's scope.
     ///
     /// The scope defines the search area for references and completion items.
@@ -83,61 +111,42 @@ pub trait AstSymbol:
         range.start <= offset && offset <= range.end
     }
 
+    /// Checks if the symbol contains the given byte range.
+    fn contains_range(&self, range: &std::ops::Range<usize>) -> bool {
+        let symbol = self.get_data().get_range();
+        symbol.start <= range.start && range.end <= symbol.end
+    }
+
     /// Returns the LSP start position of the symbol.
     fn get_start_position(&self, workspace: &Document) -> Position {
         let range = self.get_data().get_range();
-        let node = workspace
-            .cst
-            .root_node()
-            .descendant_for_byte_range(range.start, range.start)
-            .unwrap();
-
-        Position {
-            line: node.start_position().row as u32,
-            character: node.start_position().column as u32,
-        }
+        workspace.position_at(range.start).unwrap()
     }
 
     /// Returns the LSP end position of the symbol.
     fn get_end_position(&self, workspace: &Document) -> Position {
         let range = self.get_data().get_range();
-        let node = workspace
-            .cst
-            .root_node()
-            .descendant_for_byte_range(range.end, range.end)
-            .unwrap();
-
-        Position {
-            line: node.start_position().row as u32,
-            character: node.start_position().column as u32,
-        }
+        workspace.position_at(range.end).unwrap()
     }
 
     /// Returns the LSP range (start and end position) of the symbol.
     fn get_lsp_range(&self, workspace: &Document) -> Range {
         let range = self.get_data().get_range();
-        let node = workspace
-            .cst
-            .root_node()
-            .descendant_for_byte_range(range.start, range.end)
-            .unwrap();
-
-        lsp_types::Range {
-            start: Position {
-                line: node.start_position().row as u32,
-                character: node.start_position().column as u32,
-            },
-            end: Position {
-                line: node.end_position().row as u32,
-                character: node.end_position().column as u32,
-            },
-        }
+        workspace.range_at(range).unwrap()
     }
 }
 
 impl_downcast!(AstSymbol);
 
 impl<T: AstSymbol + ?Sized> GetSymbolData for T {
+    fn get_id(&self) -> SymbolId {
+        self.get_data().get_id()
+    }
+
+    fn set_id(&mut self, id: SymbolId) {
+        self.get_mut_data().set_id(id)
+    }
+
     fn get_url(&self) -> Arc<Url> {
         self.get_data().get_url()
     }
@@ -174,6 +183,14 @@ impl<T: AstSymbol + ?Sized> GetSymbolData for T {
         self.get_mut_data().reset_target_reference_reference();
     }
 
+    fn is_partial(&self) -> bool {
+        self.get_data().is_partial()
+    }
+
+    fn set_partial(&mut self, partial: bool) {
+        self.get_mut_data().set_partial(partial)
+    }
+
     fn get_referrers(&self) -> &Option<Referrers> {
         self.get_data().get_referrers()
     }
@@ -1,3 +1,4 @@
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 use crate::core_build::main_builder::MainBuilder;
@@ -6,9 +7,26 @@ use super::core::AstSymbol;
 use super::symbol::*;
 use lsp_types::Url;
 
+/// Identifier of an ast symbol
+///
+/// Ids are unique within the process and survive incremental updates:
+/// when a symbol is rebuilt, the new symbol and its descendants keep the ids of the old symbols with the same type and range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SymbolId(pub u64);
+
+impl SymbolId {
+    /// Generate a new unique id
+    pub fn generate() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        Self(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
 /// Core data of any ast symbol
 #[derive(Clone)]
 pub struct SymbolData {
+    /// The stable id of the symbol
+    pub id: SymbolId,
     /// The workspace url of the symbol
     pub url: Arc<Url>,
     /// The parent of the symbol
@@ -21,23 +39,46 @@ pub struct SymbolData {
     pub target: Option<WeakSymbol>,
     /// The byte range of the symbol in the source code
     pub range: std::ops::Range<usize>,
+    /// Whether the symbol was built from a region containing syntax errors
+    pub partial: bool,
+}
+
+/// Parent, target and referrers are printed as ids, they would otherwise print the whole tree
+impl std::fmt::Debug for SymbolData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SymbolData")
+            .field("id", &self.id)
+            .field("range", &self.range)
+            .field("comment", &self.comment)
+            .field("partial", &self.partial)
+            .field("parent", &self.parent)
+            .field("target", &self.target)
+            .field("referrers", &self.referrers)
+            .finish()
+    }
 }
 
 impl SymbolData {
     pub fn new(url: Arc<Url>, range: std::ops::Range<usize>) -> Self {
         Self {
+            id: SymbolId::generate(),
             url,
             parent: None,
             comment: None,
             referrers: None,
             target: None,
             range,
+            partial: false,
         }
     }
 }
 
 /// Trait to read or mutate the core data of an ast symbol
 pub trait GetSymbolData {
+    /// Get the stable id of the symbol
+    fn get_id(&self) -> SymbolId;
+    /// Set the id of the symbol
+    fn set_id(&mut self, id: SymbolId);
     /// Get the workspace url of the symbol
     fn get_url(&self) -> Arc<Url>;
     /// Get the range of the symbol in the source code
@@ -64,6 +105,13 @@ pub trait GetSymbolData {
     fn set_target_reference(&mut self, target: WeakSymbol);
     /// Reset the target of the symbol
     fn reset_target_reference_reference(&mut self);
+    /// Check if the symbol was built from a region containing syntax errors
+    ///
+    /// Some children of a partial symbol may be missing or outdated,
+    /// the rest of the ast is not affected.
+    fn is_partial(&self) -> bool;
+    /// Set whether the symbol was built from a region containing syntax errors
+    fn set_partial(&mut self, partial: bool);
     /// Get the referrers of the symbol
     ///
     /// Referrers are symbols that refer to this symbol
@@ -75,6 +123,14 @@ pub trait GetSymbolData {
 }
 
 impl GetSymbolData for SymbolData {
+    fn get_id(&self) -> SymbolId {
+        self.id
+    }
+
+    fn set_id(&mut self, id: SymbolId) {
+        self.id = id;
+    }
+
     fn get_url(&self) -> Arc<Url> {
         self.url.clone()
     }
@@ -121,6 +177,14 @@ impl GetSymbolData for SymbolData {
         self.target = None;
     }
 
+    fn is_partial(&self) -> bool {
+        self.partial
+    }
+
+    fn set_partial(&mut self, partial: bool) {
+        self.partial = partial;
+    }
+
     fn get_referrers(&self) -> &Option<Referrers> {
         &self.referrers
     }
@@ -131,7 +195,7 @@ impl GetSymbolData for SymbolData {
 }
 
 /// List of weak symbols that refer to this symbol
-#[derive(Default, Clone)]
+#[derive(Default, Clone, Debug)]
 pub struct Referrers(Vec<WeakSymbol>);
 
 /// Trait for managing [`Referrers`]
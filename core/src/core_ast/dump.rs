@@ -0,0 +1,86 @@
+//! This module provides a textual dump of the AST, for snapshot tests and bug reports.
+
+use std::fmt::Write;
+
+use super::symbol::DynSymbol;
+use crate::workspace::Workspace;
+
+/// Layout of a dump, see [`dump_ast`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DumpFormat {
+    /// One symbol per line, children are indented by two spaces.
+    ///
+    /// ```text
+    /// Module 0..18
+    ///   Function 0..18
+    ///     Name 4..7 "foo"
+    /// ```
+    Tree,
+    /// The whole AST on a single line.
+    ///
+    /// ```text
+    /// (Module 0..18 (Function 0..18 (Name 4..7 "foo")))
+    /// ```
+    SExpression,
+}
+
+/// Dump the AST of a workspace, or an empty string if the workspace has no AST.
+///
+/// Each symbol is printed with its type name and byte range, choices are followed by the struct of their variant
+/// such as `Parameter(TypedParameter)`.
+/// Symbols without children are followed by their text and partial symbols are marked with `partial`.
+pub fn dump_ast(workspace: &Workspace, format: DumpFormat) -> String {
+    match &workspace.ast {
+        Some(ast) => dump_symbol(ast, workspace.document.document.text.as_bytes(), format),
+        None => String::new(),
+    }
+}
+
+/// Dump a symbol and its descendants, see [`dump_ast`].
+pub fn dump_symbol(symbol: &DynSymbol, source_code: &[u8], format: DumpFormat) -> String {
+    let mut dump = String::new();
+    write_symbol(&mut dump, symbol, source_code, format, 0);
+    dump
+}
+
+fn write_symbol(
+    dump: &mut String,
+    symbol: &DynSymbol,
+    source_code: &[u8],
+    format: DumpFormat,
+    depth: usize,
+) {
+    let read = symbol.read();
+    let children = read.children().collect::<Vec<_>>();
+
+    match format {
+        DumpFormat::Tree => dump.push_str(&"  ".repeat(depth)),
+        DumpFormat::SExpression if depth > 0 => dump.push_str(" ("),
+        DumpFormat::SExpression => dump.push('('),
+    }
+
+    write!(dump, "{}", read.get_type_name()).unwrap();
+    if read.get_struct_type_name() != read.get_type_name() {
+        write!(dump, "({})", read.get_struct_type_name()).unwrap();
+    }
+    write!(dump, " {:?}", read.get_range()).unwrap();
+    if read.is_partial() {
+        dump.push_str(" partial");
+    }
+    if children.is_empty() {
+        if let Some(text) = read.get_text(source_code) {
+            write!(dump, " {:?}", text).unwrap();
+        }
+    }
+    if format == DumpFormat::Tree {
+        dump.push('\n');
+    }
+
+    for child in &children {
+        write_symbol(dump, child, source_code, format, depth + 1);
+    }
+
+    if format == DumpFormat::SExpression {
+        dump.push(')');
+    }
+}
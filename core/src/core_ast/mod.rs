@@ -1,5 +1,8 @@
 pub mod capabilities;
 pub mod core;
 pub mod data;
+pub mod dump;
+pub mod serialize;
 pub mod symbol;
 pub mod update;
+pub mod visit;
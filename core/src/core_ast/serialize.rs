@@ -0,0 +1,171 @@
+//! This module provides the serialization of the AST to JSON (enabled with feature `serde`).
+//!
+//! - [`SerializeFields`] Serializing the fields of a symbol, implemented by the `seq` and `choice` macros.
+//! - [`SymbolJson`] Serializing a symbol and its descendants.
+//!
+//! With the `serde` feature, [`Workspace`] implements [`serde::Serialize`] as well.
+//!
+//! Each symbol is serialized as an object with:
+//! - `type`: The name of the symbol's type.
+//! - `variant`: The struct of the variant for a `choice` (see [`get_struct_type_name`](super::core::AstSymbol::get_struct_type_name)), `null` otherwise.
+//! - `range`: The byte range of the symbol.
+//! - `lsp_range`: The LSP range of the symbol.
+//! - `comment`: The text of the symbol's comment, if any.
+//! - `target`: The location of the symbol's target (`uri`, `range` and `lsp_range` when in the same document), if any.
+//! - `fields`: The children of the symbol by field, symbol fields are serialized as symbols and value fields with their `Serialize` impl.
+//!
+//! Symbols don't implement [`serde::Serialize`] themselves: ranges, comments and targets are only known
+//! with the [`Document`] of the symbol, and symbols are shared behind locks that must be read one at a time.
+//! The tree is therefore converted to a [`serde_json::Value`] first, which [`SymbolJson`] then serializes with any serializer.
+//! Errors of value fields are propagated to the serializer.
+
+#[cfg(feature = "serde")]
+use super::core::AstSymbol;
+#[cfg(feature = "serde")]
+use super::symbol::*;
+#[cfg(feature = "serde")]
+use crate::workspace::Document;
+#[cfg(feature = "serde")]
+use crate::workspace::Workspace;
+#[cfg(feature = "serde")]
+use serde_json::{json, Map, Value};
+
+/// A trait for serializing the fields of an AST symbol.
+///
+/// Value fields must implement [`serde::Serialize`] when the `serde` feature is enabled.
+pub trait SerializeFields {
+    /// Add the fields of the symbol to `fields`, by field name.
+    #[cfg(feature = "serde")]
+    fn serialize_fields(
+        &self,
+        _document: &Document,
+        _fields: &mut Map<String, Value>,
+    ) -> Result<(), serde_json::Error> {
+        Ok(())
+    }
+}
+
+/// Serialize a symbol field, see [`SerializeFields`].
+#[cfg(feature = "serde")]
+pub trait ToJson {
+    fn to_json(&self, document: &Document) -> Result<Value, serde_json::Error>;
+}
+
+#[cfg(feature = "serde")]
+impl<T: AstSymbol> ToJson for Symbol<T> {
+    fn to_json(&self, document: &Document) -> Result<Value, serde_json::Error> {
+        symbol_to_json(&*self.read(), document)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: AstSymbol> ToJson for Option<Symbol<T>> {
+    fn to_json(&self, document: &Document) -> Result<Value, serde_json::Error> {
+        match self {
+            Some(symbol) => symbol.to_json(document),
+            None => Ok(Value::Null),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: AstSymbol> ToJson for Vec<Symbol<T>> {
+    fn to_json(&self, document: &Document) -> Result<Value, serde_json::Error> {
+        self.iter()
+            .map(|symbol| symbol.to_json(document))
+            .collect::<Result<_, _>>()
+            .map(Value::Array)
+    }
+}
+
+/// Serialize a symbol and its descendants, see the [module](self) documentation for the layout.
+///
+/// ```ignore
+/// let json = serde_json::to_string(&SymbolJson::new(&symbol, &workspace.document))?;
+/// ```
+#[cfg(feature = "serde")]
+pub struct SymbolJson<'a> {
+    symbol: &'a DynSymbol,
+    document: &'a Document,
+}
+
+#[cfg(feature = "serde")]
+impl<'a> SymbolJson<'a> {
+    pub fn new(symbol: &'a DynSymbol, document: &'a Document) -> Self {
+        Self { symbol, document }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for SymbolJson<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        symbol_to_json(&*self.symbol.read(), self.document)
+            .map_err(serde::ser::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+/// Serialize the AST of the workspace with its diagnostics.
+///
+/// ```json
+/// { "ast": { "type": "Module", ... }, "errors": [] }
+/// ```
+#[cfg(feature = "serde")]
+impl serde::Serialize for Workspace {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut workspace = serializer.serialize_struct("Workspace", 2)?;
+        workspace.serialize_field(
+            "ast",
+            &self
+                .ast
+                .as_ref()
+                .map(|ast| SymbolJson::new(ast, &self.document)),
+        )?;
+        workspace.serialize_field("errors", &self.errors)?;
+        workspace.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+fn symbol_to_json(symbol: &dyn AstSymbol, document: &Document) -> Result<Value, serde_json::Error> {
+    let source_code = document.document.text.as_bytes();
+    let range = symbol.get_range();
+
+    let target = symbol
+        .get_target()
+        .and_then(|target| target.to_dyn())
+        .map(|target| {
+            let target = target.read();
+            let url = target.get_url();
+            let range = target.get_range();
+            let lsp_range = match url == symbol.get_url() {
+                true => document.range_at(range.clone()),
+                false => None,
+            };
+            json!({
+                "uri": url.as_str(),
+                "range": { "start": range.start, "end": range.end },
+                "lsp_range": lsp_range,
+            })
+        });
+
+    let mut fields = Map::new();
+    symbol.serialize_fields(document, &mut fields)?;
+
+    let variant = match symbol.get_struct_type_name() {
+        name if name != symbol.get_type_name() => Some(name),
+        _ => None,
+    };
+
+    Ok(json!({
+        "type": symbol.get_type_name(),
+        "variant": variant,
+        "range": { "start": range.start, "end": range.end },
+        "lsp_range": document.range_at(range),
+        "comment": symbol.get_comment(source_code),
+        "target": target,
+        "fields": fields,
+    }))
+}
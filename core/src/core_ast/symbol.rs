@@ -39,7 +39,10 @@ impl<T: AstSymbol> Symbol<T> {
     /// If the symbol is a reference ([`super::capabilities::Reference`]), add it to the unsolved references list
     ///
     /// If the symbol requires checking ([`super::capabilities::Check`]), add it to the unsolved checks list
-    pub fn new_and_check(symbol: T, params: &mut MainBuilder) -> Self {
+    ///
+    /// If the symbol was built from a region containing syntax errors, mark it as partial
+    pub fn new_and_check(mut symbol: T, params: &mut MainBuilder) -> Self {
+        symbol.set_partial(params.has_syntax_error(&symbol.get_range()));
         let arc = Symbol::new(symbol);
         let read = arc.read();
         if read.is_reference() {
@@ -53,6 +56,17 @@ impl<T: AstSymbol> Symbol<T> {
         arc
     }
 
+    /// Create a new [Symbol] from a placeholder, see [`crate::build::Placeholder`]
+    ///
+    /// Inject itself as the parent of the symbol
+    ///
+    /// Placeholders are neither checked nor resolved as references
+    pub fn new_placeholder(symbol: T) -> Self {
+        let arc = Symbol::new(symbol);
+        arc.write().inject_parent(arc.to_weak());
+        arc
+    }
+
     pub(crate) fn new(symbol: T) -> Self {
         Self(Arc::new(RwLock::new(symbol)))
     }
@@ -62,6 +76,28 @@ impl<T: AstSymbol> Symbol<T> {
     }
 }
 
+/// A symbol locked for writing is printed as `<locked>`
+impl<T: AstSymbol> std::fmt::Debug for Symbol<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0.try_read() {
+            Some(symbol) => symbol.fmt(f),
+            None => f.write_str("<locked>"),
+        }
+    }
+}
+
+/// Prints the type name and the byte range of the symbol, such as `Function 4..22`
+///
+/// A symbol locked for writing is printed as `<locked>`
+impl<T: AstSymbol> std::fmt::Display for Symbol<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0.try_read() {
+            Some(symbol) => write!(f, "{} {:?}", symbol.get_type_name(), symbol.get_range()),
+            None => f.write_str("<locked>"),
+        }
+    }
+}
+
 /// Generic Thread-safe wrapper around an [AstSymbol] trait object using [Arc] and [parking_lot::RwLock]
 #[derive(Clone)]
 pub struct DynSymbol(Arc<RwLock<dyn AstSymbol>>);
@@ -84,6 +120,11 @@ impl DynSymbol {
         self.0.write()
     }
 
+    /// Returns `true` if both [DynSymbol] point to the same symbol
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+
     /// Downgrade a [DynSymbol] to a [WeakSymbol]
     pub(crate) fn to_weak(&self) -> WeakSymbol {
         WeakSymbol::new(self)
@@ -94,6 +135,28 @@ impl DynSymbol {
     }
 }
 
+/// A symbol locked for writing is printed as `<locked>`
+impl std::fmt::Debug for DynSymbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0.try_read() {
+            Some(symbol) => symbol.fmt(f),
+            None => f.write_str("<locked>"),
+        }
+    }
+}
+
+/// Prints the type name and the byte range of the symbol, such as `Function 4..22`
+///
+/// A symbol locked for writing is printed as `<locked>`
+impl std::fmt::Display for DynSymbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0.try_read() {
+            Some(symbol) => write!(f, "{} {:?}", symbol.get_type_name(), symbol.get_range()),
+            None => f.write_str("<locked>"),
+        }
+    }
+}
+
 /// Generic Thread-safe wrapper around a [Weak] reference to an [AstSymbol] using [Weak] and [parking_lot::RwLock]
 ///
 /// Must be upgraded to a [DynSymbol] before use
@@ -118,3 +181,16 @@ impl WeakSymbol {
         &self.0
     }
 }
+
+/// Only the id of the symbol is printed, parents would otherwise print the whole tree
+impl std::fmt::Debug for WeakSymbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0.upgrade() {
+            Some(symbol) => match symbol.try_read() {
+                Some(symbol) => f.debug_tuple("WeakSymbol").field(&symbol.get_id()).finish(),
+                None => f.write_str("WeakSymbol(<locked>)"),
+            },
+            None => f.write_str("WeakSymbol(<dropped>)"),
+        }
+    }
+}
@@ -4,10 +4,14 @@
 //! - [`CollectReferences`] Collecting references to AST symbols when a section will be dropped.
 //! - [`Parent`] Injecting parent relationships into symbols.
 //! - [`UpdateRange`] Modifying the range of symbols in response to edits.
-//! - [`UpdateStatic`] and [`UpdateDynamic`] Performing incremental updates on AST nodes based on offset changes.
+//! - [`CollectSymbols`] Collecting the descendants of a symbol, used to keep [`SymbolId`]s stable.
+//! - [`UpdateStatic`] and [`UpdateDynamic`] Performing incremental updates on AST nodes based on edited ranges.
+//! - [`UpdateChildren`] Passing incremental updates down to symbols that can't be rebuilt on their own.
 //!
 //! Note: Still under development.
 
+use std::any::TypeId;
+use std::collections::{HashMap, VecDeque};
 use std::ops::ControlFlow;
 
 use lsp_types::Diagnostic;
@@ -16,7 +20,7 @@ use crate::core_build::buildable::Buildable;
 use crate::core_build::buildable::Queryable;
 use crate::core_build::buildable::StaticBuildable;
 use crate::core_build::downcast::TryFromBuilder;
-use crate::core_build::main_builder::MainBuilder;
+use crate::core_build::main_builder::{AstChange, MainBuilder};
 
 use super::core::AstSymbol;
 use super::data::*;
@@ -102,13 +106,23 @@ pub trait UpdateRange {
 /// - `offset`: The amount by which the symbol's range should be adjusted. Positive values
 ///   extend the range, while negative values shrink it.
 fn edit(data: &mut SymbolData, start: usize, offset: isize) {
-    if data.range.start >= start {
+    edit_byte_range(&mut data.range, start, offset);
+    // Comments are usually outside the symbol's range, so they have to be moved as well
+    if let Some(comment) = data.comment.as_mut() {
+        edit_byte_range(comment, start, offset);
+    }
+}
+
+fn edit_byte_range(range: &mut std::ops::Range<usize>, start: usize, offset: isize) {
+    // Offsets located in deleted text are moved to the start of the edit
+    let shift = |position: usize| position.saturating_add_signed(offset).max(start);
+    if range.start >= start {
         // Entire range is after the offset; shift both start and end
-        data.range.start = (data.range.start as isize + offset) as usize;
-        data.range.end = (data.range.end as isize + offset) as usize;
-    } else if data.range.end >= start {
+        range.start = shift(range.start);
+        range.end = shift(range.end);
+    } else if range.end >= start {
         // The offset occurs within the range; adjust only the end
-        data.range.end = (data.range.end as isize + offset) as usize;
+        range.end = shift(range.end);
     }
 }
 
@@ -152,12 +166,100 @@ impl<T: AstSymbol> UpdateRange for Vec<Symbol<T>> {
     }
 }
 
+/// A trait for collecting all the descendants of an AST symbol.
+///
+/// Symbols are collected in depth-first order, each symbol coming before its children.
+pub trait CollectSymbols {
+    fn collect_symbols(&self, acc: &mut Vec<DynSymbol>);
+}
+
+impl CollectSymbols for DynSymbol {
+    fn collect_symbols(&self, acc: &mut Vec<DynSymbol>) {
+        acc.push(self.clone());
+        self.read().collect_symbols(acc);
+    }
+}
+
+impl<T: AstSymbol> CollectSymbols for Symbol<T> {
+    fn collect_symbols(&self, acc: &mut Vec<DynSymbol>) {
+        acc.push(self.to_dyn());
+        self.read().collect_symbols(acc);
+    }
+}
+
+impl<T: AstSymbol> CollectSymbols for Option<Symbol<T>> {
+    fn collect_symbols(&self, acc: &mut Vec<DynSymbol>) {
+        if let Some(symbol) = self.as_ref() {
+            symbol.collect_symbols(acc);
+        }
+    }
+}
+
+impl<T: AstSymbol> CollectSymbols for Vec<Symbol<T>> {
+    fn collect_symbols(&self, acc: &mut Vec<DynSymbol>) {
+        for symbol in self.iter() {
+            symbol.collect_symbols(acc);
+        }
+    }
+}
+
+/// Give the symbols of a rebuilt tree the [`SymbolId`]s of the old tree.
+///
+/// A new symbol takes the id of an old symbol with the same type (and thus the same query name) and the same range,
+/// ranges of the old tree must already be shifted by the edits.
+///
+/// Returns the [`AstChange`] describing the replacement, with the old range still shifted by the edits.
+pub(crate) fn preserve_ids(old: &DynSymbol, new: &DynSymbol) -> AstChange {
+    let key = |symbol: &DynSymbol| {
+        let read = symbol.read();
+        (
+            read.as_any().type_id(),
+            read.get_range().start,
+            read.get_range().end,
+        )
+    };
+
+    let mut old_symbols = vec![];
+    old.collect_symbols(&mut old_symbols);
+    let mut ids: HashMap<(TypeId, usize, usize), VecDeque<SymbolId>> = HashMap::new();
+    for symbol in &old_symbols {
+        let id = symbol.read().get_id();
+        ids.entry(key(symbol)).or_default().push_back(id);
+    }
+
+    let mut new_symbols = vec![];
+    new.collect_symbols(&mut new_symbols);
+    let mut updated = vec![];
+    let mut added = vec![];
+    for symbol in &new_symbols {
+        match ids.get_mut(&key(symbol)).and_then(|ids| ids.pop_front()) {
+            Some(id) => {
+                symbol.write().set_id(id);
+                updated.push(id);
+            }
+            None => added.push(symbol.read().get_id()),
+        }
+    }
+
+    let mut removed = ids.into_values().flatten().collect::<Vec<_>>();
+    removed.sort();
+
+    AstChange {
+        old_range: old.read().get_range(),
+        new_range: new.read().get_range(),
+        symbol: new.clone(),
+        updated,
+        added,
+        removed,
+    }
+}
+
 /// Trait to update an ast symbol incrementally
 ///
 /// This trait is implemented on all symbols.
 ///
 /// To update a symbol, the following conditions must be met:
-/// - The symbol is inside the range of the update
+/// - The symbol contains the edited range
 /// - No lower level symbols have been updated
 pub trait UpdateStatic<T, Y>
 where
@@ -165,7 +267,7 @@ where
     Y: AstSymbol,
 {
     /// ### Conditions for Symbol Updates
-    /// - The symbol must be within the range of the edit.
+    /// - The symbol must contain the edited range, given in the coordinates of the edited document.
     /// - No lower-level symbols (children) have already been updated.
     ///
     /// ### Return Value
@@ -174,8 +276,7 @@ where
     /// - [ControlFlow::Continue]: The symbol did not require updating.
     fn update<'a>(
         &mut self,
-        start: usize,
-        offset: isize,
+        range: &std::ops::Range<usize>,
         parent_check: Option<WeakSymbol>,
         builder_params: &'a mut MainBuilder,
     ) -> ControlFlow<Result<(), Diagnostic>, ()>;
@@ -191,13 +292,12 @@ where
 {
     fn update<'a>(
         &mut self,
-        start: usize,
-        offset: isize,
+        range: &std::ops::Range<usize>,
         parent_check: Option<WeakSymbol>,
         builder_params: &'a mut MainBuilder,
     ) -> ControlFlow<Result<(), Diagnostic>, ()> {
         let read = self.read();
-        match read.is_inside_offset(start) {
+        match read.contains_range(range) {
             true => {
                 let check = match read.must_check() {
                     true => Some(self.to_weak()),
@@ -206,8 +306,7 @@ where
                 drop(read);
                 // Check if no lower level symbols could be updated.
 
-                self.write()
-                    .dyn_update(start, offset, check, builder_params)?;
+                self.write().dyn_update(range, check, builder_params)?;
                 let parent = self.read().get_parent();
                 let range = self.read().get_range();
                 log::info!("");
@@ -234,6 +333,10 @@ where
                     symbol.write().set_parent(parent);
                 }
 
+                // Keep the ids of the symbols that did not change
+                let change = preserve_ids(&self.to_dyn(), &symbol.to_dyn());
+                builder_params.changes.push(change);
+
                 // Update the symbol
                 *self = symbol;
                 ControlFlow::Break(Ok(()))
@@ -253,13 +356,12 @@ where
 {
     fn update<'a>(
         &mut self,
-        start: usize,
-        offset: isize,
+        range: &std::ops::Range<usize>,
         parent_check: Option<WeakSymbol>,
         builder_params: &'a mut MainBuilder,
     ) -> ControlFlow<Result<(), Diagnostic>, ()> {
         match self {
-            Some(symbol) => symbol.update(start, offset, parent_check, builder_params),
+            Some(symbol) => symbol.update(range, parent_check, builder_params),
             None => ControlFlow::Continue(()),
         }
     }
@@ -275,13 +377,12 @@ where
 {
     fn update<'a>(
         &mut self,
-        start: usize,
-        offset: isize,
+        range: &std::ops::Range<usize>,
         parent_check: Option<WeakSymbol>,
         builder_params: &'a mut MainBuilder,
     ) -> ControlFlow<Result<(), Diagnostic>, ()> {
         for symbol in self.iter_mut() {
-            match symbol.update(start, offset, parent_check.clone(), builder_params) {
+            match symbol.update(range, parent_check.clone(), builder_params) {
                 ControlFlow::Break(result) => return ControlFlow::Break(result),
                 ControlFlow::Continue(()) => continue,
             }
@@ -290,12 +391,75 @@ where
     }
 }
 
+/// Trait to update the children of symbols that cannot be rebuilt on their own.
+///
+/// Symbols of fields mapped to another capture (`#[ast(capture = "...")]`) are not built from their query name,
+/// so they can't be rebuilt with [`StaticBuildable`].
+///
+/// The edit is passed down to their children, and if none of them is updated,
+/// [`ControlFlow::Continue`] is returned so that the parent is rebuilt instead.
+pub trait UpdateChildren {
+    fn update_children(
+        &mut self,
+        range: &std::ops::Range<usize>,
+        parent_check: Option<WeakSymbol>,
+        builder_params: &mut MainBuilder,
+    ) -> ControlFlow<Result<(), Diagnostic>, ()>;
+}
+
+impl<T: AstSymbol> UpdateChildren for Symbol<T> {
+    fn update_children(
+        &mut self,
+        range: &std::ops::Range<usize>,
+        parent_check: Option<WeakSymbol>,
+        builder_params: &mut MainBuilder,
+    ) -> ControlFlow<Result<(), Diagnostic>, ()> {
+        let read = self.read();
+        if !read.contains_range(range) {
+            return ControlFlow::Continue(());
+        }
+        let check = match read.must_check() {
+            true => Some(self.to_weak()),
+            false => parent_check,
+        };
+        drop(read);
+        self.write().dyn_update(range, check, builder_params)
+    }
+}
+
+impl<T: AstSymbol> UpdateChildren for Option<Symbol<T>> {
+    fn update_children(
+        &mut self,
+        range: &std::ops::Range<usize>,
+        parent_check: Option<WeakSymbol>,
+        builder_params: &mut MainBuilder,
+    ) -> ControlFlow<Result<(), Diagnostic>, ()> {
+        match self {
+            Some(symbol) => symbol.update_children(range, parent_check, builder_params),
+            None => ControlFlow::Continue(()),
+        }
+    }
+}
+
+impl<T: AstSymbol> UpdateChildren for Vec<Symbol<T>> {
+    fn update_children(
+        &mut self,
+        range: &std::ops::Range<usize>,
+        parent_check: Option<WeakSymbol>,
+        builder_params: &mut MainBuilder,
+    ) -> ControlFlow<Result<(), Diagnostic>, ()> {
+        for symbol in self.iter_mut() {
+            symbol.update_children(range, parent_check.clone(), builder_params)?;
+        }
+        ControlFlow::Continue(())
+    }
+}
+
 /// This trait is similar to [UpdateStatic], but is used for trait objects ([DynSymbol])
 pub trait UpdateDynamic {
     fn dyn_update<'a>(
         &mut self,
-        start: usize,
-        offset: isize,
+        range: &std::ops::Range<usize>,
         parent_check: Option<WeakSymbol>,
         builder_params: &'a mut MainBuilder,
     ) -> ControlFlow<Result<(), Diagnostic>, ()>;
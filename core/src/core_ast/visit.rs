@@ -0,0 +1,126 @@
+//! This module provides traits for traversing the AST.
+//!
+//! - [`CollectChildren`] Collecting the direct children of a symbol, see [`AstSymbol::children`].
+//! - [`Walk`] and [`WalkMut`] Visiting the children of a symbol with a visitor.
+//! - [`Visit`] and [`VisitMut`] Calling the hook of a visitor for a symbol.
+//!
+//! `Walk` and `CollectChildren` are implemented by the `seq` and `choice` macros,
+//! `Visit` is implemented by the `visitor` macro along with the `Visitor` and `VisitorMut` traits.
+
+use super::core::AstSymbol;
+use super::symbol::*;
+
+/// A trait for collecting the direct children of an AST symbol.
+///
+/// Value fields are not symbols and are skipped.
+pub trait CollectChildren {
+    fn collect_children(&self, acc: &mut Vec<DynSymbol>);
+}
+
+impl<T: AstSymbol> CollectChildren for Symbol<T> {
+    fn collect_children(&self, acc: &mut Vec<DynSymbol>) {
+        acc.push(self.to_dyn());
+    }
+}
+
+impl<T: AstSymbol> CollectChildren for Option<Symbol<T>> {
+    fn collect_children(&self, acc: &mut Vec<DynSymbol>) {
+        if let Some(symbol) = self.as_ref() {
+            symbol.collect_children(acc);
+        }
+    }
+}
+
+impl<T: AstSymbol> CollectChildren for Vec<Symbol<T>> {
+    fn collect_children(&self, acc: &mut Vec<DynSymbol>) {
+        for symbol in self.iter() {
+            symbol.collect_children(acc);
+        }
+    }
+}
+
+/// Call the hook of the visitor `V` for this symbol.
+///
+/// Implemented by the `visitor` macro for every listed symbol,
+/// and forwarded by [`Symbol`], [`Option`] and [`Vec`].
+///
+/// A symbol reachable from a visited symbol but missing from the `visitor` macro fails to compile with this message.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` has no hook in the visitor",
+    label = "`{Self}` is not listed in `visitor!`",
+    note = "add `{Self}` to the `visitor!` macro, every symbol reachable from a visited symbol needs a hook"
+)]
+pub trait Visit<V: ?Sized> {
+    fn accept(&self, visitor: &mut V);
+}
+
+/// Call the hook of the mutable visitor `V` for this symbol.
+///
+/// See [`Visit`].
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` has no hook in the mutable visitor",
+    label = "`{Self}` is not listed in `visitor!`",
+    note = "add `{Self}` to the `visitor!` macro, every symbol reachable from a visited symbol needs a hook"
+)]
+pub trait VisitMut<V: ?Sized> {
+    fn accept_mut(&mut self, visitor: &mut V);
+}
+
+/// Visit the children of a symbol, in the order of its fields.
+///
+/// This is the default recursion of the hooks of a visitor.
+pub trait Walk<V: ?Sized> {
+    fn walk(&self, visitor: &mut V);
+}
+
+/// Visit the children of a symbol mutably, in the order of its fields.
+///
+/// Each child is locked for writing while it is visited,
+/// hooks must not read the parents of the symbol they are given.
+pub trait WalkMut<V: ?Sized> {
+    fn walk_mut(&mut self, visitor: &mut V);
+}
+
+impl<V: ?Sized, T: AstSymbol + Visit<V>> Visit<V> for Symbol<T> {
+    fn accept(&self, visitor: &mut V) {
+        self.read().accept(visitor);
+    }
+}
+
+impl<V: ?Sized, T: Visit<V>> Visit<V> for Option<T> {
+    fn accept(&self, visitor: &mut V) {
+        if let Some(symbol) = self.as_ref() {
+            symbol.accept(visitor);
+        }
+    }
+}
+
+impl<V: ?Sized, T: Visit<V>> Visit<V> for Vec<T> {
+    fn accept(&self, visitor: &mut V) {
+        for symbol in self.iter() {
+            symbol.accept(visitor);
+        }
+    }
+}
+
+impl<V: ?Sized, T: AstSymbol + VisitMut<V>> VisitMut<V> for Symbol<T> {
+    fn accept_mut(&mut self, visitor: &mut V) {
+        self.write().accept_mut(visitor);
+    }
+}
+
+impl<V: ?Sized, T: VisitMut<V>> VisitMut<V> for Option<T> {
+    fn accept_mut(&mut self, visitor: &mut V) {
+        if let Some(symbol) = self.as_mut() {
+            symbol.accept_mut(visitor);
+        }
+    }
+}
+
+impl<V: ?Sized, T: VisitMut<V>> VisitMut<V> for Vec<T> {
+    fn accept_mut(&mut self, visitor: &mut V) {
+        for symbol in self.iter_mut() {
+            symbol.accept_mut(visitor);
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use downcast_rs::{impl_downcast, Downcast};
-use lsp_types::{Diagnostic, Position, Url};
+use lsp_types::{Diagnostic, Url};
 
 use crate::{
     core_ast::{core::AstSymbol, symbol::Symbol},
@@ -89,29 +89,27 @@ pub trait Buildable: Downcast {
         params: &mut MainBuilder,
     ) -> Result<Option<PendingSymbol>, Diagnostic>;
 
+    /// Store the node of a capture that belongs to a value field of the builder.
+    ///
+    /// Value fields are not symbols, the text of their capture is parsed when the symbol is built.
+    ///
+    /// Returns `true` if the capture was consumed.
+    #[allow(clippy::result_large_err)]
+    fn add_value(
+        &mut self,
+        _capture: &tree_sitter::QueryCapture,
+        _params: &mut MainBuilder,
+    ) -> Result<bool, Diagnostic> {
+        Ok(false)
+    }
+
     fn get_url(&self) -> Arc<Url>;
 
     fn get_range(&self) -> std::ops::Range<usize>;
 
     fn get_query_index(&self) -> usize;
     fn get_lsp_range(&self, workspace: &Document) -> lsp_types::Range {
-        let range = self.get_range();
-        let node = workspace
-            .cst
-            .root_node()
-            .descendant_for_byte_range(range.start, range.end)
-            .unwrap();
-
-        lsp_types::Range {
-            start: Position {
-                line: node.start_position().row as u32,
-                character: node.start_position().column as u32,
-            },
-            end: Position {
-                line: node.end_position().row as u32,
-                character: node.end_position().column as u32,
-            },
-        }
+        workspace.range_at(self.get_range()).unwrap()
     }
 
     fn get_text<'a>(&self, source_code: &'a [u8]) -> &'a str {
@@ -195,6 +193,80 @@ pub const fn check_conflicts(
     }
 }
 
+/// Build a symbol standing for a field that is missing from the source code.
+///
+/// Implemented by the `seq` and `choice` macros:
+/// - A `seq` placeholder has empty `Vec` and `Option` fields, its value fields take their [`Default`] value
+///   and its mandatory symbol fields are placeholders as well.
+/// - A `choice` placeholder is the placeholder of its first variant that has one.
+///
+/// Placeholders are used for symbol fields marked with `#[ast(default)]`,
+/// and for missing mandatory symbol fields when building in lenient mode (see [`MainBuilder::lenient`](crate::build::MainBuilder::lenient)).
+pub trait Placeholder: Sized + 'static {
+    /// Build a placeholder spanning `range`.
+    ///
+    /// Returns `None` if the symbol can't be built without source code,
+    /// when it has a mandatory value field without `default` or when its mandatory fields are recursive.
+    ///
+    /// `visited` holds the types whose placeholder is being built.
+    fn placeholder(
+        url: Arc<Url>,
+        range: std::ops::Range<usize>,
+        visited: &mut std::collections::HashSet<std::any::TypeId>,
+    ) -> Option<Self>;
+}
+
+/// Collect the captures consumed by the fields of a struct or the variants of an enum
+///
+/// Symbols are visited once, so recursive symbols are supported.
+#[cfg(feature = "assertions")]
+pub trait CollectQueryNames: Queryable + 'static {
+    /// Push the captures consumed by the symbols contained in this symbol, recursively.
+    ///
+    /// The query names of the symbol itself are pushed by its parent.
+    fn collect_query_names(
+        names: &mut Vec<&'static str>,
+        visited: &mut std::collections::HashSet<std::any::TypeId>,
+    );
+}
+
+/// Compare the captures of the core query with the query names reachable from the root symbol
+///
+/// Returns an error listing the query names missing from the query and the captures no symbol consumes.
+///
+/// Captures starting with `_` are private to the query (e.g. `@_name` used by an `#eq?` predicate) and are never reported as unused.
+#[cfg(feature = "assertions")]
+pub fn check_query_names<T: CollectQueryNames>(query: &tree_sitter::Query) -> Result<(), String> {
+    let mut names = T::QUERY_NAMES.to_vec();
+    T::collect_query_names(&mut names, &mut std::collections::HashSet::new());
+
+    let captures = query.capture_names();
+
+    let mut missing = names
+        .iter()
+        .filter(|name| !captures.contains(name))
+        .collect::<Vec<_>>();
+    missing.sort();
+    missing.dedup();
+
+    let unused = captures
+        .iter()
+        .filter(|capture| !capture.starts_with('_') && !names.contains(capture))
+        .collect::<Vec<_>>();
+
+    let mut errors = vec![];
+    if !missing.is_empty() {
+        errors.push(format!("Query names missing from the query: {:?}", missing));
+    }
+    if !unused.is_empty() {
+        errors.push(format!("Captures not consumed by any symbol: {:?}", unused));
+    }
+    match errors.is_empty() {
+        true => Ok(()),
+        false => Err(errors.join("\n")),
+    }
+}
+
 pub trait Constructor<T: Buildable + Queryable> {
     fn new(
         url: Arc<Url>,
@@ -210,26 +282,46 @@ pub trait Constructor<T: Buildable + Queryable> {
     }
 }
 
+/// Add a capture to a field of a builder.
+///
+/// `query_names` are the captures accepted by the field, usually [`Queryable::QUERY_NAMES`] of `Y`
+/// unless the field maps to another capture.
 pub trait AddSymbol {
     fn add<Y: Buildable + Queryable>(
         &mut self,
         capture: &tree_sitter::QueryCapture,
         params: &mut MainBuilder,
+        query_names: &[&str],
         parent_name: &str,
         field_name: &str,
     ) -> Result<Option<PendingSymbol>, Diagnostic>;
 }
 
+/// Returns `true` if the node of the capture is in the Tree-sitter `field` of its parent node.
+///
+/// Used by fields with `#[ast(field = "...")]`, so that fields sharing query names are told apart.
+pub fn is_in_field(capture: &tree_sitter::QueryCapture, field: &str) -> bool {
+    let Some(parent) = capture.node.parent() else {
+        return false;
+    };
+    let mut cursor = parent.walk();
+    let in_field = parent
+        .children_by_field_name(field, &mut cursor)
+        .any(|child| child.id() == capture.node.id());
+    in_field
+}
+
 impl AddSymbol for PendingSymbol {
     fn add<Y: Buildable + Queryable>(
         &mut self,
         capture: &tree_sitter::QueryCapture,
         params: &mut MainBuilder,
+        query_names: &[&str],
         parent_name: &str,
         field_name: &str,
     ) -> Result<Option<PendingSymbol>, Diagnostic> {
         let name = params.query.capture_names()[capture.index as usize];
-        if Y::QUERY_NAMES.contains(&name) {
+        if query_names.contains(&name) {
             match Y::new(params.url.clone(), params.query, capture) {
                 Some(node) => {
                     let node = PendingSymbol::new(node);
@@ -238,13 +330,10 @@ impl AddSymbol for PendingSymbol {
                 }
                 None => {
                     return Err(builder_error!(
-                        tree_sitter_range_to_lsp_range(&capture.node.range()),
+                        tree_sitter_range_to_lsp_range(params.document, &capture.node.range()),
                         format!(
                             "Invalid {:?} for {:?}, expected: {:?}, received: {:?}",
-                            field_name,
-                            parent_name,
-                            name,
-                            Y::QUERY_NAMES
+                            field_name, parent_name, name, query_names
                         )
                     ))
                 }
@@ -259,15 +348,16 @@ impl AddSymbol for MaybePendingSymbol {
         &mut self,
         capture: &tree_sitter::QueryCapture,
         params: &mut MainBuilder,
+        query_names: &[&str],
         parent_name: &str,
         field_name: &str,
     ) -> Result<Option<PendingSymbol>, Diagnostic> {
         let name = params.query.capture_names()[capture.index as usize];
-        if Y::QUERY_NAMES.contains(&name) {
+        if query_names.contains(&name) {
             match self.as_ref() {
                 Some(_) => {
                     return Err(builder_error!(
-                        tree_sitter_range_to_lsp_range(&capture.node.range()),
+                        tree_sitter_range_to_lsp_range(params.document, &capture.node.range()),
                         format!("{:?} already set in {:?}", field_name, parent_name)
                     ));
                 }
@@ -278,13 +368,10 @@ impl AddSymbol for MaybePendingSymbol {
                     }
                     None => {
                         return Err(builder_error!(
-                            tree_sitter_range_to_lsp_range(&capture.node.range()),
+                            tree_sitter_range_to_lsp_range(params.document, &capture.node.range()),
                             format!(
                                 "Invalid {:?} for {:?}, expected: {:?}, received: {:?}",
-                                field_name,
-                                parent_name,
-                                name,
-                                Y::QUERY_NAMES
+                                field_name, parent_name, name, query_names
                             )
                         ))
                     }
@@ -300,12 +387,13 @@ impl AddSymbol for Vec<PendingSymbol> {
         &mut self,
         capture: &tree_sitter::QueryCapture,
         params: &mut MainBuilder,
+        query_names: &[&str],
         parent_name: &str,
         field_name: &str,
     ) -> Result<Option<PendingSymbol>, Diagnostic> {
         let name = params.query.capture_names()[capture.index as usize];
 
-        if Y::QUERY_NAMES.contains(&name) {
+        if query_names.contains(&name) {
             match Y::new(params.url.clone(), params.query, capture) {
                 Some(node) => {
                     let node = PendingSymbol::new(node);
@@ -314,13 +402,10 @@ impl AddSymbol for Vec<PendingSymbol> {
                 }
                 None => {
                     return Err(builder_error!(
-                        tree_sitter_range_to_lsp_range(&capture.node.range()),
+                        tree_sitter_range_to_lsp_range(params.document, &capture.node.range()),
                         format!(
                             "Invalid {:?} for {:?}, expected: {:?}, received: {:?}",
-                            field_name,
-                            parent_name,
-                            name,
-                            Y::QUERY_NAMES
+                            field_name, parent_name, name, query_names
                         )
                     ))
                 }
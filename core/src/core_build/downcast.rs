@@ -88,11 +88,18 @@ where
         field_range: lsp_types::Range,
         input_name: &str,
     ) -> Result<Self::Output, Diagnostic> {
-        self.as_ref().map_or(Ok(None), |pending| {
+        let result = self.as_ref().map_or(Ok(None), |pending| {
             pending
                 .try_downcast(check, field_name, field_range, input_name)
                 .map(Some)
-        })
+        });
+        match result {
+            Err(err) if check.lenient => {
+                check.diagnostics.push(err);
+                Ok(None)
+            }
+            result => result,
+        }
     }
 }
 
@@ -111,8 +118,20 @@ where
         field_range: lsp_types::Range,
         input_name: &str,
     ) -> Result<Self::Output, Diagnostic> {
-        self.iter()
-            .map(|item| item.try_downcast(check, field_name, field_range, input_name))
-            .collect::<Result<Vec<_>, lsp_types::Diagnostic>>()
+        if !check.lenient {
+            return self
+                .iter()
+                .map(|item| item.try_downcast(check, field_name, field_range, input_name))
+                .collect::<Result<Vec<_>, lsp_types::Diagnostic>>();
+        }
+
+        let mut result = vec![];
+        for item in self.iter() {
+            match item.try_downcast(check, field_name, field_range, input_name) {
+                Ok(item) => result.push(item),
+                Err(err) => check.diagnostics.push(err),
+            }
+        }
+        Ok(result)
     }
 }
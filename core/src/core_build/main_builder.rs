@@ -1,8 +1,14 @@
-use crate::core_ast::data::ReferrersTrait;
-use crate::core_ast::{symbol::*, update::UpdateRange};
+use crate::core_ast::data::{ReferrersTrait, SymbolId};
+use crate::core_ast::{
+    symbol::*,
+    update::{preserve_ids, UpdateRange},
+};
 use crate::workspace::Document;
 use lsp_types::{Diagnostic, Url};
-use std::{ops::ControlFlow, sync::Arc};
+use std::{
+    ops::{ControlFlow, Range},
+    sync::Arc,
+};
 use tree_sitter::InputEdit;
 
 pub struct MainBuilder<'a> {
@@ -12,6 +18,34 @@ pub struct MainBuilder<'a> {
     pub diagnostics: &'a mut Vec<Diagnostic>,
     pub unsolved_checks: &'a mut Vec<WeakSymbol>,
     pub unsolved_references: &'a mut Vec<WeakSymbol>,
+    /// Subtrees replaced by [`MainBuilder::swap_ast`]
+    pub changes: Vec<AstChange>,
+    /// Keep building a symbol when some of its children are invalid.
+    ///
+    /// Invalid items of `Vec` fields are skipped and invalid `Option` fields are left empty,
+    /// the error is added to the diagnostics instead.
+    /// Missing or invalid mandatory fields are replaced by a [`Placeholder`](crate::build::Placeholder) when their type has one,
+    /// otherwise they fail the symbol, which is then skipped by the nearest `Vec` or `Option` parent.
+    pub lenient: bool,
+}
+
+/// A subtree replaced during an incremental update
+///
+/// Symbols are identified by their [`SymbolId`], which is kept by the symbols that still exist after the update.
+#[derive(Clone)]
+pub struct AstChange {
+    /// Byte range of the replaced symbol in the previous version of the document
+    pub old_range: Range<usize>,
+    /// Byte range of the new symbol
+    pub new_range: Range<usize>,
+    /// The new symbol
+    pub symbol: DynSymbol,
+    /// Symbols found in both the old and the new subtree
+    pub updated: Vec<SymbolId>,
+    /// Symbols only found in the new subtree
+    pub added: Vec<SymbolId>,
+    /// Symbols only found in the old subtree
+    pub removed: Vec<SymbolId>,
 }
 
 impl<'a> MainBuilder<'a> {
@@ -117,6 +151,13 @@ impl<'a> MainBuilder<'a> {
         self
     }
 
+    /// Update the ast after the document was edited.
+    ///
+    /// `edit_ranges` are the edits in the order they were applied to the document,
+    /// each one being expressed in the coordinates of the document produced by the previous edits.
+    ///
+    /// The ranges of all symbols are shifted first, the smallest symbols containing the edited ranges are then rebuilt.
+    /// If no symbol contains an edited range, the whole ast is rebuilt.
     pub fn swap_ast(
         &'a mut self,
         root: &mut DynSymbol,
@@ -126,6 +167,8 @@ impl<'a> MainBuilder<'a> {
             Option<std::ops::Range<usize>>,
         ) -> Result<DynSymbol, lsp_types::Diagnostic>,
     ) -> &'a mut MainBuilder<'a> {
+        let first_change = self.changes.len();
+
         // All ranges have to be updated
         for (edit, _) in edit_ranges {
             let start_byte = edit.start_byte;
@@ -137,45 +180,41 @@ impl<'a> MainBuilder<'a> {
                 continue;
             }
 
-            root.edit_range(start_byte, (new_end_byte - old_end_byte) as isize);
+            root.edit_range(start_byte, new_end_byte as isize - old_end_byte as isize);
         }
 
-        // Filter out overlapping edits for ast edit
-        // Since the containing node is already uodated, chil dnodes do not need to be built twice.
-        for (edit, is_ws) in filter_intersecting_edits(edit_ranges).iter() {
-            let start_byte = edit.start_byte;
-            let old_end_byte = edit.old_end_byte;
-            let new_end_byte = edit.new_end_byte;
-
-            let is_noop = old_end_byte == start_byte && new_end_byte == start_byte;
-            if is_noop {
-                continue;
-            }
-
+        // Edited ranges are now expressed in the coordinates of the edited document,
+        // overlapping ranges are merged so the symbol containing them is only built once.
+        for (range, is_ws) in normalize_edits(edit_ranges) {
             let node = self
                 .document
                 .cst
                 .root_node()
-                .descendant_for_byte_range(edit.start_byte, edit.new_end_byte);
+                .descendant_for_byte_range(range.start, range.end);
 
+            // Symbols containing an invalid syntax are rebuilt as a whole
+            let mut range = range;
+            let mut recovering = false;
             if let Some(node) = node {
-                if let Some(node) = node.parent() {
-                    if node.is_error() {
-                        log::warn!("");
-                        log::warn!("Node has an invalid syntax, aborting incremental update");
-                        continue;
-                    }
-                }
-                if node.is_extra() {
+                let error = std::iter::successors(Some(node), |node| node.parent())
+                    .find(|node| node.is_error());
+                if let Some(error) = error {
+                    log::warn!("");
+                    log::warn!("Node has an invalid syntax, rebuilding the enclosing symbol");
+                    range = range.start.min(error.start_byte())..range.end.max(error.end_byte());
+                    recovering = true;
+                } else if node.is_extra() {
                     log::info!("");
                     log::info!("Node is extra, only update ranges");
+                    self.refresh_partial(root, &range);
                     continue;
                 }
             }
 
-            if *is_ws {
+            if is_ws && !recovering {
                 log::info!("");
                 log::info!("Whitespace edit, only update ranges");
+                self.refresh_partial(root, &range);
                 continue;
             }
 
@@ -184,13 +223,12 @@ impl<'a> MainBuilder<'a> {
                 false => None,
             };
 
-            let result = root.write().dyn_update(
-                start_byte,
-                (new_end_byte - old_end_byte) as isize,
-                parent_check,
-                self,
-            );
+            let result = root.write().dyn_update(&range, parent_check, self);
             match result {
+                ControlFlow::Break(Err(e)) if recovering => {
+                    // Syntax errors are already reported, the previous symbol is kept
+                    log::warn!("Symbol could not be rebuilt: {}", e.message);
+                }
                 ControlFlow::Break(Err(e)) => {
                     self.diagnostics.push(e);
                 }
@@ -201,47 +239,124 @@ impl<'a> MainBuilder<'a> {
                     let mut ast_builder = ast_parser(self, None);
                     match ast_builder {
                         Ok(ref mut new_root) => {
+                            let change = preserve_ids(root, new_root);
+                            self.changes.push(change);
                             root.swap(new_root);
                         }
                         Err(e) => {
                             self.diagnostics.push(e);
                         }
                     }
+                    // The whole ast was rebuilt, remaining edits are included
+                    self.refresh_partial(root, &range);
+                    break;
                 }
                 ControlFlow::Break(Ok(_)) => {}
             };
+            self.refresh_partial(root, &range);
+        }
+
+        // Old ranges were shifted along with the rest of the tree
+        for change in self.changes[first_change..].iter_mut() {
+            change.old_range = unshift_range(change.old_range.clone(), edit_ranges);
         }
         self
     }
 }
 
-/// Filter out intersecting edits and keep the biggest one
-fn filter_intersecting_edits(params: &Vec<(InputEdit, bool)>) -> Vec<(InputEdit, bool)> {
-    if params.is_empty() {
-        return vec![];
+impl MainBuilder<'_> {
+    /// Check if the region of the document contains syntax errors
+    pub fn has_syntax_error(&self, range: &Range<usize>) -> bool {
+        self.document
+            .cst
+            .root_node()
+            .descendant_for_byte_range(range.start, range.end)
+            .is_some_and(|node| node.has_error())
     }
 
-    if params.len() == 1 {
-        return params.clone();
+    /// Update the partial flag of the symbols containing the edited range, up to the root.
+    ///
+    /// Syntax errors may have been added or fixed within symbols that were not rebuilt.
+    fn refresh_partial(&self, root: &DynSymbol, range: &Range<usize>) {
+        let mut curr = root
+            .read()
+            .find_at_offset(range.start)
+            .or_else(|| Some(root.clone()));
+        while let Some(symbol) = curr {
+            let symbol_range = symbol.read().get_range();
+            if symbol_range.start <= range.start && range.end <= symbol_range.end {
+                let partial = self.has_syntax_error(&symbol_range);
+                symbol.write().set_partial(partial);
+            }
+            curr = symbol.read().get_parent().and_then(|p| p.to_dyn());
+        }
     }
+}
 
-    // Sort by range
-    let mut sorted_edits = params.clone();
-    sorted_edits.sort_by_key(|(edit, _)| edit.start_byte + edit.new_end_byte);
+/// Map a range shifted by the edits back to the previous version of the document
+fn unshift_range(mut range: Range<usize>, edits: &[(InputEdit, bool)]) -> Range<usize> {
+    for (edit, _) in edits.iter().rev() {
+        let unshift = |offset: usize, within: usize| {
+            if offset >= edit.new_end_byte {
+                offset - edit.new_end_byte + edit.old_end_byte
+            } else if offset > edit.start_byte {
+                // Offset within the new text
+                within
+            } else {
+                offset
+            }
+        };
+        range.start = unshift(range.start, edit.start_byte);
+        range.end = unshift(range.end, edit.old_end_byte);
+    }
+    range
+}
 
-    // Filter out overlapping edits
-    let mut filtered = Vec::new();
-    let mut last_end = sorted_edits[0].0.new_end_byte;
+/// Convert edits applied one after the other to byte ranges of the edited document.
+///
+/// Each range is shifted by the edits that follow it, no-op edits are dropped
+/// and overlapping or adjacent ranges are merged.
+///
+/// A merged range only contains whitespaces if all of its edits do.
+pub fn normalize_edits(edits: &[(InputEdit, bool)]) -> Vec<(Range<usize>, bool)> {
+    let mut ranges: Vec<(Range<usize>, bool)> = vec![];
+    for (edit, is_ws) in edits {
+        let is_noop = edit.old_end_byte == edit.start_byte && edit.new_end_byte == edit.start_byte;
+        if is_noop {
+            continue;
+        }
 
-    for edit in sorted_edits {
-        // Check if current edit starts after previous edit ends
-        if edit.0.start_byte >= last_end {
-            filtered.push(edit);
-            last_end = edit.0.new_end_byte;
+        let delta = edit.new_end_byte as isize - edit.old_end_byte as isize;
+        let shift = |position: usize| {
+            if position >= edit.old_end_byte {
+                position.saturating_add_signed(delta)
+            } else if position > edit.start_byte {
+                // Position within the replaced text
+                position.min(edit.new_end_byte)
+            } else {
+                position
+            }
+        };
+        for (range, _) in ranges.iter_mut() {
+            range.start = shift(range.start);
+            range.end = shift(range.end).max(range.start);
         }
+        ranges.push((edit.start_byte..edit.new_end_byte, *is_ws));
     }
 
-    filtered
+    ranges.sort_by_key(|(range, _)| (range.start, range.end));
+
+    let mut merged: Vec<(Range<usize>, bool)> = vec![];
+    for (range, is_ws) in ranges {
+        match merged.last_mut() {
+            Some((last, last_ws)) if range.start <= last.end => {
+                last.end = last.end.max(range.end);
+                *last_ws &= is_ws;
+            }
+            _ => merged.push((range, is_ws)),
+        }
+    }
+    merged
 }
 
 #[cfg(test)]
@@ -249,47 +364,51 @@ mod tests {
     use super::*;
     use tree_sitter::{InputEdit, Point};
 
+    fn edit(start_byte: usize, old_end_byte: usize, new_end_byte: usize) -> (InputEdit, bool) {
+        (
+            InputEdit {
+                start_byte,
+                old_end_byte,
+                new_end_byte,
+                start_position: Point::default(),
+                old_end_position: Point::default(),
+                new_end_position: Point::default(),
+            },
+            false,
+        )
+    }
+
     #[test]
-    fn test_intersecting_edits() {
+    fn test_sequential_edits() {
+        // Insert 2 bytes at 10, then 3 bytes at 0
+        let edits = vec![edit(10, 10, 12), edit(0, 0, 3)];
+        let normalized = normalize_edits(&edits);
+        assert_eq!(normalized, vec![(0..3, false), (13..15, false)]);
+
+        // Delete 10..20, then insert at 5
+        let edits = vec![edit(10, 20, 10), edit(5, 5, 6)];
+        let normalized = normalize_edits(&edits);
+        assert_eq!(normalized, vec![(5..6, false), (11..11, false)]);
+    }
+
+    #[test]
+    fn test_overlapping_edits() {
+        // Insert 10 bytes at 0, then replace 5..15 with 2 bytes
+        let edits = vec![edit(0, 0, 10), edit(5, 15, 7)];
+        let normalized = normalize_edits(&edits);
+        assert_eq!(normalized, vec![(0..7, false)]);
+
+        // Adjacent edits are merged, whitespace flags are combined
         let edits = vec![
-            (
-                InputEdit {
-                    start_byte: 0,
-                    new_end_byte: 20,
-                    old_end_byte: 0,
-                    start_position: Point::default(),
-                    old_end_position: Point::default(),
-                    new_end_position: Point::default(),
-                },
-                false,
-            ),
-            (
-                InputEdit {
-                    start_byte: 10,
-                    new_end_byte: 30,
-                    old_end_byte: 0,
-                    start_position: Point::default(),
-                    old_end_position: Point::default(),
-                    new_end_position: Point::default(),
-                },
-                false,
-            ),
-            (
-                InputEdit {
-                    start_byte: 20,
-                    new_end_byte: 40,
-                    old_end_byte: 0,
-                    start_position: Point::default(),
-                    old_end_position: Point::default(),
-                    new_end_position: Point::default(),
-                },
-                false,
-            ),
+            (edit(0, 0, 2).0, true),
+            edit(2, 2, 4),
+            (edit(8, 8, 9).0, true),
         ];
+        let normalized = normalize_edits(&edits);
+        assert_eq!(normalized, vec![(0..4, false), (8..9, true)]);
 
-        let filtered = filter_intersecting_edits(&edits);
-        assert_eq!(filtered.len(), 1);
-        assert_eq!(filtered[0].0.start_byte, 20);
-        assert_eq!(filtered[0].0.new_end_byte, 40);
+        // No-op edits are dropped
+        let edits = vec![edit(3, 3, 3)];
+        assert!(normalize_edits(&edits).is_empty());
     }
 }
@@ -1,6 +1,7 @@
 pub mod buildable;
 pub mod downcast;
 pub mod main_builder;
+pub mod query;
 pub mod stack_builder;
 pub mod symbol;
 pub mod utils;
@@ -0,0 +1,133 @@
+use std::{any::TypeId, collections::HashSet};
+
+use super::buildable::Queryable;
+
+/// Derive the patterns of the core query from a symbol
+///
+/// Implemented by the `seq` and `choice` macros, patterns are only derived for structs with a `node_kind`.
+pub trait DeriveQuery: Queryable + 'static {
+    /// Node kinds matched by the symbol, with the capture of each
+    fn node_kinds() -> Vec<(&'static str, &'static str)>;
+
+    /// Add the patterns of the symbols contained in this symbol, recursively.
+    fn derive_query(query: &mut QueryBuilder);
+}
+
+/// Patterns of a core query derived with [`DeriveQuery`]
+#[derive(Default)]
+pub struct QueryBuilder {
+    visited: HashSet<TypeId>,
+    /// Node kinds captured wherever they are, with their capture
+    nodes: Vec<(&'static str, &'static str)>,
+    /// Node kinds captured in a field of their parent: parent kind, field, node kind and capture
+    fields: Vec<(&'static str, &'static str, &'static str, &'static str)>,
+    /// Patterns of value fields and symbol fields with another capture
+    patterns: Vec<String>,
+    errors: Vec<String>,
+}
+
+impl QueryBuilder {
+    /// Returns `true` the first time a symbol is visited.
+    pub fn visit<T: 'static>(&mut self) -> bool {
+        self.visited.insert(TypeId::of::<T>())
+    }
+
+    /// Capture the node kinds of `T`, either anywhere or only in the `field` of the `parent` node kind.
+    pub fn add_symbol<T: DeriveQuery>(
+        &mut self,
+        parent: &'static str,
+        field: Option<&'static str>,
+    ) {
+        for (kind, capture) in T::node_kinds() {
+            match field {
+                None => {
+                    if !self.nodes.contains(&(kind, capture)) {
+                        self.nodes.push((kind, capture))
+                    }
+                }
+                Some(field) => self.fields.push((parent, field, kind, capture)),
+            }
+        }
+    }
+
+    /// Capture the node kinds of `T` with another capture, when they are children of the `parent` node kind.
+    pub fn add_capture<T: DeriveQuery>(
+        &mut self,
+        parent: &'static str,
+        field: Option<&'static str>,
+        capture: &'static str,
+    ) {
+        for (kind, _) in T::node_kinds() {
+            self.patterns.push(match field {
+                Some(field) => format!("({} {}: ({}) @{})", parent, field, kind, capture),
+                None => format!("({} ({}) @{})", parent, kind, capture),
+            });
+        }
+    }
+
+    /// Capture the node of a value field, given by the `field` of the `parent` node kind.
+    pub fn add_value(
+        &mut self,
+        parent: &'static str,
+        field: Option<&'static str>,
+        capture: &'static str,
+    ) {
+        match field {
+            Some(field) => self
+                .patterns
+                .push(format!("({} {}: (_) @{})", parent, field, capture)),
+            None => self.errors.push(format!(
+                "Value field {:?} of {:?} needs a tree-sitter field",
+                capture, parent
+            )),
+        }
+    }
+
+    /// Report an error, the query is not derived.
+    pub fn add_error(&mut self, error: String) {
+        self.errors.push(error);
+    }
+
+    /// Assemble the query, or return the errors reported while deriving it.
+    ///
+    /// Node kinds captured anywhere are not captured again in the fields of their parents.
+    pub fn build(self) -> Result<String, String> {
+        if !self.errors.is_empty() {
+            return Err(self.errors.join("\n"));
+        }
+
+        let mut patterns = self
+            .nodes
+            .iter()
+            .map(|(kind, capture)| format!("({}) @{}", kind, capture))
+            .collect::<Vec<_>>();
+
+        patterns.extend(
+            self.fields
+                .iter()
+                .filter(|(_, _, kind, capture)| !self.nodes.contains(&(kind, capture)))
+                .map(|(parent, field, kind, capture)| {
+                    format!("({} {}: ({}) @{})", parent, field, kind, capture)
+                }),
+        );
+        patterns.extend(self.patterns);
+
+        let mut query = String::new();
+        let mut seen = HashSet::new();
+        for pattern in patterns {
+            if seen.insert(pattern.clone()) {
+                query.push_str(&pattern);
+                query.push('\n');
+            }
+        }
+        Ok(query)
+    }
+}
+
+/// Derive the core query of an AST from its root symbol
+pub fn derive_query<T: DeriveQuery>() -> Result<String, String> {
+    let mut query = QueryBuilder::default();
+    query.add_symbol::<T>("", None);
+    T::derive_query(&mut query);
+    query.build()
+}
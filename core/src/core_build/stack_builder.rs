@@ -1,6 +1,6 @@
 use std::marker::PhantomData;
 
-use lsp_types::{Diagnostic, Position};
+use lsp_types::Diagnostic;
 use streaming_iterator::StreamingIterator;
 use tree_sitter::QueryCapture;
 
@@ -29,9 +29,10 @@ where
     fn create_root_node(&mut self, capture: &QueryCapture, capture_index: usize) {
         let mut node = T::new(self.params.url.clone(), &self.params.query, &capture);
 
-        let node_char_start = tree_sitter_range_to_lsp_range(&capture.node.range())
-            .start
-            .character as usize;
+        let node_char_start =
+            tree_sitter_range_to_lsp_range(self.params.document, &capture.node.range())
+                .start
+                .character as usize;
 
         log::debug!(
             "{}├──{:?} [root]",
@@ -46,7 +47,7 @@ where
                 self.stack.push(node);
             }
             None => self.params.diagnostics.push(builder_warning!(
-                tree_sitter_range_to_lsp_range(&capture.node.range()),
+                tree_sitter_range_to_lsp_range(self.params.document, &capture.node.range()),
                 format!(
                     "Unknown query {:?}",
                     self.params.query.capture_names()[capture_index as usize],
@@ -56,6 +57,33 @@ where
     }
 
     fn create_child_node(&mut self, parent: &PendingSymbol, capture: &QueryCapture) {
+        // Value fields are stored in the parent, which stays on the stack
+        let add_value = parent
+            .get_rc()
+            .borrow_mut()
+            .add_value(capture, self.params);
+        match add_value {
+            Ok(false) => (),
+            Ok(true) => {
+                self.stack.push(parent.clone());
+                log::debug!(
+                    "{}└──{:?} [value]",
+                    " ".repeat(
+                        tree_sitter_range_to_lsp_range(self.params.document, &capture.node.range())
+                            .start
+                            .character as usize
+                    ),
+                    self.params.query.capture_names()[capture.index as usize],
+                );
+                return;
+            }
+            Err(e) => {
+                self.params.diagnostics.push(e);
+                self.stack.push(parent.clone());
+                return;
+            }
+        }
+
         let add = parent.get_rc().borrow_mut().add(&capture, self.params);
         match add {
             Err(e) => {
@@ -63,7 +91,7 @@ where
             }
             Ok(None) => {
                 self.params.diagnostics.push(builder_warning!(
-                    tree_sitter_range_to_lsp_range(&capture.node.range()),
+                    tree_sitter_range_to_lsp_range(self.params.document, &capture.node.range()),
                     format!(
                         "Unknown query {:?}",
                         self.params.query.capture_names()[capture.index as usize],
@@ -76,7 +104,10 @@ where
                     .start
                     .character as usize;
 
-                let node_char_start = capture.node.start_position().column;
+                let node_char_start =
+                    tree_sitter_range_to_lsp_range(self.params.document, &capture.node.range())
+                        .start
+                        .character as usize;
 
                 log::warn!(
                     " {}└──{}{:?} [unknown]",
@@ -100,7 +131,10 @@ where
                     .start
                     .character as usize;
 
-                let node_char_start = capture.node.start_position().column;
+                let node_char_start =
+                    tree_sitter_range_to_lsp_range(self.params.document, &capture.node.range())
+                        .start
+                        .character as usize;
 
                 log::debug!(
                     "{}└──{}{:?}",
@@ -188,34 +222,18 @@ where
         match self.roots.pop() {
             Some(node) => Ok(node),
             None => match range {
-                Some(range) => {
-                    let node = self
-                        .params
-                        .document
-                        .cst
-                        .root_node()
-                        .descendant_for_byte_range(range.start, range.end)
-                        .unwrap();
-
-                    Err(builder_error!(
-                        lsp_types::Range {
-                            start: Position {
-                                line: node.start_position().row as u32,
-                                character: node.start_position().column as u32,
-                            },
-                            end: Position {
-                                line: node.end_position().row as u32,
-                                character: node.end_position().column as u32,
-                            },
-                        },
-                        match T::QUERY_NAMES.len() {
-                            1 => format!("Expected {}", T::QUERY_NAMES[0]),
-                            _ => format!("Expected one of {:?}", T::QUERY_NAMES.join(", ")),
-                        }
-                    ))
-                }
+                Some(range) => Err(builder_error!(
+                    self.params.document.range_at(range.clone()).unwrap(),
+                    match T::QUERY_NAMES.len() {
+                        1 => format!("Expected {}", T::QUERY_NAMES[0]),
+                        _ => format!("Expected one of {:?}", T::QUERY_NAMES.join(", ")),
+                    }
+                )),
                 None => Err(builder_error!(
-                    tree_sitter_range_to_lsp_range(&self.params.document.cst.root_node().range()),
+                    tree_sitter_range_to_lsp_range(
+                        self.params.document,
+                        &self.params.document.cst.root_node().range()
+                    ),
                     match T::QUERY_NAMES.len() {
                         1 => format!("Expected {}", T::QUERY_NAMES[0]),
                         _ => format!("Expected one of {:?}", T::QUERY_NAMES.join(", ")),
@@ -9,8 +9,11 @@ pub mod ast {
     pub use crate::core_ast::capabilities::*;
     pub use crate::core_ast::core::*;
     pub use crate::core_ast::data::*;
+    pub use crate::core_ast::dump::*;
+    pub use crate::core_ast::serialize::*;
     pub use crate::core_ast::symbol::*;
     pub use crate::core_ast::update::*;
+    pub use crate::core_ast::visit::*;
 }
 
 /// This module contains everything related to building ast symbols
@@ -18,6 +21,7 @@ pub mod build {
     pub use crate::core_build::buildable::*;
     pub use crate::core_build::downcast::*;
     pub use crate::core_build::main_builder::*;
+    pub use crate::core_build::query::*;
     pub use crate::core_build::symbol::*;
 }
 
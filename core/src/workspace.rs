@@ -1,11 +1,15 @@
 use crate::{
-    core_ast::symbol::{DynSymbol, WeakSymbol},
+    core_ast::{
+        data::SymbolId,
+        symbol::{DynSymbol, WeakSymbol},
+        update::CollectSymbols,
+    },
     core_build::main_builder::MainBuilder,
 };
-use lsp_types::Diagnostic;
+use lsp_types::{Diagnostic, PositionEncodingKind};
 use parking_lot::RwLock;
 use texter::core::text::Text;
-use tree_sitter::{Language, Parser, Point, Query, Tree};
+use tree_sitter::{Language, Parser, Query, Tree};
 
 pub struct Queries {
     pub core: Query,
@@ -14,11 +18,71 @@ pub struct Queries {
     pub highlights: Option<Query>,
 }
 
+/// Comment markers of a language, used to strip comments before displaying them (e.g. in hover information).
+///
+/// Markers are matched in order, so longer markers sharing a prefix with shorter ones must come first (`///` before `//`).
+#[derive(Debug, Clone, Copy)]
+pub struct CommentStyle {
+    /// Line comment prefixes, e.g. `//` or `#`
+    pub line: &'static [&'static str],
+    /// Block comment delimiters, e.g. `/*` and `*/`
+    pub block: &'static [(&'static str, &'static str)],
+}
+
+impl Default for CommentStyle {
+    fn default() -> Self {
+        Self {
+            line: &["///", "//!", "//", "#"],
+            block: &[("/**", "*/"), ("/*", "*/")],
+        }
+    }
+}
+
+impl CommentStyle {
+    /// Remove comment markers from a comment.
+    ///
+    /// Leading `*` of lines within a block comment are also removed.
+    pub fn strip(&self, comment: &str) -> String {
+        let mut comment = comment.trim();
+        let mut is_block = false;
+
+        for (start, end) in self.block {
+            if comment.starts_with(start)
+                && comment.ends_with(end)
+                && comment.len() >= start.len() + end.len()
+            {
+                comment = &comment[start.len()..comment.len() - end.len()];
+                is_block = true;
+                break;
+            }
+        }
+
+        let lines = comment.lines().map(|line| {
+            let line = line.trim_start();
+            let line = match is_block {
+                true => line.strip_prefix('*').unwrap_or(line),
+                false => self
+                    .line
+                    .iter()
+                    .find_map(|prefix| line.strip_prefix(prefix))
+                    .unwrap_or(line),
+            };
+            line.strip_prefix(' ').unwrap_or(line).trim_end()
+        });
+
+        lines.collect::<Vec<_>>().join("\n").trim().to_string()
+    }
+}
+
 pub struct TreeSitter {
     pub parser: RwLock<Parser>,
     pub node_types: &'static str,
     pub language: Language,
     pub queries: Queries,
+    /// Comment markers of the language
+    pub comment_style: CommentStyle,
+    /// Stable hash of the node types and queries, used to invalidate cached data when the parser changes.
+    pub parser_hash: u64,
 }
 
 pub type StaticBuildableFn = fn(
@@ -27,6 +91,8 @@ pub type StaticBuildableFn = fn(
 ) -> Result<DynSymbol, lsp_types::Diagnostic>;
 
 pub struct Parsers {
+    /// Name of the parser, used as the source of its diagnostics
+    pub name: &'static str,
     pub tree_sitter: TreeSitter,
     pub ast_parser: StaticBuildableFn,
 }
@@ -34,71 +100,111 @@ pub struct Parsers {
 pub struct Document {
     pub document: Text,
     pub cst: Tree,
+    /// Comment markers of the document's language
+    pub comment_style: CommentStyle,
+    /// Encoding of the [`lsp_types::Position`] characters negotiated with the client (UTF-8, 16 or 32).
+    ///
+    /// Must match the encoding of [`Document::document`].
+    /// Capabilities computing positions by hand should convert byte offsets with [`Document::position_at`]
+    /// and [`Document::offset_at`], which follow this encoding.
+    pub position_encoding: PositionEncodingKind,
 }
 
 impl Document {
+    /// Create a new document with the default [`CommentStyle`] and UTF-16 positions
     pub fn new(document: Text, cst: Tree) -> Self {
-        Self { document, cst }
+        Self {
+            document,
+            cst,
+            comment_style: CommentStyle::default(),
+            position_encoding: PositionEncodingKind::UTF16,
+        }
     }
 
-    /// Get the smallest node within the root node that spans the given range.
+    /// Number of code units used by `ch` in the position encoding
+    fn char_len(&self, ch: char) -> usize {
+        if self.position_encoding == PositionEncodingKind::UTF8 {
+            ch.len_utf8()
+        } else if self.position_encoding == PositionEncodingKind::UTF32 {
+            1
+        } else {
+            ch.len_utf16()
+        }
+    }
+
+    /// Get the content of a line, without its line break
+    fn line(&self, line: usize) -> Option<(usize, &str)> {
+        let text = &self.document;
+        let start = text.br_indexes.row_start(line)?;
+        let end = text
+            .br_indexes
+            .row_start(line + 1)
+            .map(|next| next - 1)
+            .unwrap_or(text.text.len());
+        let content = text.text.get(start..end)?;
+        Some((start, content.strip_suffix('\r').unwrap_or(content)))
+    }
+
+    /// Get the smallest node within the root node that spans the given position.
     pub fn descendant_at_position(
         &self,
         position: lsp_types::Position,
     ) -> Option<tree_sitter::Node<'_>> {
-        let position = Point {
-            row: position.line as usize,
-            column: position.character as usize,
-        };
-
+        let offset = self.offset_at(position)?;
         self.cst
             .root_node()
-            .descendant_for_point_range(position, position)
+            .descendant_for_byte_range(offset, offset)
     }
 
-    /// Get the smallest node within root node that spans the given range.
+    /// Get the position of the given byte offset.
+    ///
+    /// Returns `None` if the offset is past the end of the document,
+    /// an offset within a character is moved back to the start of the character.
     pub fn position_at(&self, offset: usize) -> Option<lsp_types::Position> {
-        self.cst
-            .root_node()
-            .descendant_for_byte_range(offset, offset)
-            .map(|pos| lsp_types::Position {
-                line: pos.start_position().row as u32,
-                character: pos.start_position().column as u32,
-            })
+        let text = &self.document;
+        if offset > text.text.len() {
+            return None;
+        }
+
+        // Line breaks are indexed from the second entry, the first one is always 0
+        let line = text.br_indexes.0[1..].partition_point(|br| *br < offset);
+        let (start, content) = self.line(line)?;
+
+        let character = content
+            .char_indices()
+            .take_while(|(index, ch)| start + index + ch.len_utf8() <= offset)
+            .map(|(_, ch)| self.char_len(ch))
+            .sum::<usize>();
+
+        Some(lsp_types::Position {
+            line: line as u32,
+            character: character as u32,
+        })
     }
 
-    /// Get the smallest node's range within root node that spans the given range.
-    pub fn range_at(&self, offset: usize) -> Option<lsp_types::Range> {
-        self.cst
-            .root_node()
-            .descendant_for_byte_range(offset, offset)
-            .map(|pos| lsp_types::Range {
-                start: lsp_types::Position {
-                    line: pos.start_position().row as u32,
-                    character: pos.start_position().column as u32,
-                },
-                end: lsp_types::Position {
-                    line: pos.end_position().row as u32,
-                    character: pos.end_position().column as u32,
-                },
-            })
+    /// Get the range of the given byte range.
+    pub fn range_at(&self, range: std::ops::Range<usize>) -> Option<lsp_types::Range> {
+        Some(lsp_types::Range {
+            start: self.position_at(range.start)?,
+            end: self.position_at(range.end)?,
+        })
     }
 
     /// Get the byte offset of the given position.
+    ///
+    /// Returns `None` if the line does not exist,
+    /// a character past the end of the line is moved back to the end of the line.
     pub fn offset_at(&self, position: lsp_types::Position) -> Option<usize> {
-        match self.cst.root_node().descendant_for_point_range(
-            Point {
-                row: position.line as usize,
-                column: position.character as usize,
-            },
-            Point {
-                row: position.line as usize,
-                column: position.character as usize,
-            },
-        ) {
-            Some(node) => Some(node.start_byte()),
-            None => None,
+        let (start, content) = self.line(position.line as usize)?;
+
+        let mut character = 0;
+        for (index, ch) in content.char_indices() {
+            character += self.char_len(ch);
+            if character > position.character as usize {
+                return Some(start + index);
+            }
         }
+        Some(start + content.len())
     }
 }
 
@@ -110,3 +216,16 @@ pub struct Workspace {
     pub unsolved_checks: Vec<WeakSymbol>,
     pub unsolved_references: Vec<WeakSymbol>,
 }
+
+impl Workspace {
+    /// Find the symbol with the given [`SymbolId`] in the AST.
+    ///
+    /// This walks the whole AST, ids are meant to find symbols back after an edit rather than for frequent lookups.
+    pub fn get_symbol(&self, id: SymbolId) -> Option<DynSymbol> {
+        let mut symbols = vec![];
+        self.ast.as_ref()?.collect_symbols(&mut symbols);
+        symbols
+            .into_iter()
+            .find(|symbol| symbol.read().get_id() == id)
+    }
+}
@@ -1,20 +1,33 @@
-use crate::PATHS;
+use crate::{
+    feature_builder::FeaturesCodeGen, features::*, field_builder::Fields, SymbolFeatures, PATHS,
+};
+use darling::ast;
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
-use syn::Ident;
+use syn::{parse_quote, Ident, Visibility};
 
 use super::variant_builder::{VariantBuilder, Variants};
 
 pub struct EnumBuilder<'a> {
+    pub features: &'a SymbolFeatures,
     pub fields: &'a Variants,
+    pub input_vis: &'a Visibility,
     pub input_name: &'a Ident,
     pub input_builder_name: &'a Ident,
 }
 
 impl<'a> EnumBuilder<'a> {
-    pub fn new(input_name: &'a Ident, input_builder_name: &'a Ident, fields: &'a Variants) -> Self {
+    pub fn new(
+        features: &'a SymbolFeatures,
+        input_vis: &'a Visibility,
+        input_name: &'a Ident,
+        input_builder_name: &'a Ident,
+        fields: &'a Variants,
+    ) -> Self {
         Self {
+            features,
             fields,
+            input_vis,
             input_name,
             input_builder_name,
         }
@@ -26,13 +39,20 @@ impl<'a> ToTokens for EnumBuilder<'a> {
         let mut builder = VariantBuilder::default();
 
         self.enum_input(&mut builder);
+        self.unit_variants(&mut builder);
 
         self.impl_ast_symbol(&mut builder);
         self.impl_locator(&mut builder);
         self.impl_dynamic_swap(&mut builder);
         self.impl_edit_range(&mut builder);
         self.impl_collect_references(&mut builder);
+        self.impl_collect_symbols(&mut builder);
+        self.impl_collect_children(&mut builder);
+        self.impl_walk(&mut builder);
+        self.impl_debug(&mut builder);
+        self.impl_serialize_fields(&mut builder);
         self.impl_queryable(&mut builder);
+        self.impl_derive_query(&mut builder);
         self.impl_parent(&mut builder);
         self.impl_scope(&mut builder);
         self.impl_comment(&mut builder);
@@ -67,9 +87,11 @@ impl<'a> ToTokens for EnumBuilder<'a> {
         });
         self.fn_new(&mut builder);
         self.fn_add(&mut builder);
+        self.fn_add_value(&mut builder);
         builder.stage_trait(&self.input_builder_name, &PATHS.symbol_builder_trait.path);
 
         self.impl_try_from(&mut builder);
+        self.impl_placeholder(&mut builder);
 
         tokens.extend(builder.to_token_stream());
     }
@@ -77,11 +99,43 @@ impl<'a> ToTokens for EnumBuilder<'a> {
 
 impl<'a> EnumBuilder<'a> {
     fn enum_input(&self, builder: &mut VariantBuilder) {
+        for (name, ty) in self
+            .fields
+            .variant_names
+            .iter()
+            .zip(self.fields.variant_types.iter())
+        {
+            builder.add(quote! { #name(#ty) });
+        }
+        builder.stage_enum(self.input_name);
+    }
+
+    /// Generates a symbol for each unit variant, matched by the query name or node kind of the variant.
+    ///
+    /// The symbols have the visibility of the enum.
+    fn unit_variants(&self, builder: &mut VariantBuilder) {
+        let vis = self.input_vis;
+        for unit_variant in &self.fields.unit_variants {
+            let type_name = &unit_variant.type_name;
+            let query_name = &unit_variant.query_name;
+            let node_kind = unit_variant
+                .node_kind
+                .as_ref()
+                .map(|node_kind| quote! { node_kind = #node_kind, });
+            builder.add(quote! {
+                #[auto_lsp::seq(query_name = #query_name, #node_kind kind(symbol()))]
+                #vis struct #type_name {}
+            });
+        }
+        builder.stage();
+    }
+
+    /// Replaces the dispatch of a capability to the variants by the implementation of `feature`,
+    /// the capability is then either implemented by the user or generated from the `choice` attributes.
+    fn code_gen(&self, builder: &mut VariantBuilder, feature: impl FeaturesCodeGen) {
         builder
-            .add_iter(&self.fields, |name, _type, _builder| {
-                quote! { #name(#_type) }
-            })
-            .stage_enum(&self.input_name);
+            .add(feature.code_gen(self.features).to_token_stream())
+            .stage();
     }
 
     fn impl_ast_symbol(&self, builder: &mut VariantBuilder) {
@@ -96,10 +150,24 @@ impl<'a> EnumBuilder<'a> {
                 &PATHS.symbol_trait.get_mut_data.sig,
                 &PATHS.symbol_trait.get_mut_data.variant,
             )
+            .add_pattern_match_iter(
+                self.fields,
+                &PATHS.symbol_trait.get_struct_type_name.sig,
+                &PATHS.symbol_trait.get_struct_type_name.variant,
+            )
             .stage_trait(&self.input_name, &PATHS.symbol_trait.path);
     }
 
     fn impl_check(&self, builder: &mut VariantBuilder) {
+        if self.features.check.is_some() {
+            let fields = Fields::default();
+            let helper = ast::Data::Struct(ast::Fields::new(ast::Style::Unit, vec![]));
+            return self.code_gen(
+                builder,
+                CheckBuilder::new(self.input_name, &helper, &fields),
+            );
+        }
+
         builder
             .add_pattern_match_iter(
                 &self.fields,
@@ -173,6 +241,95 @@ impl<'a> EnumBuilder<'a> {
             .stage_trait(&self.input_name, &PATHS.collect_references.path);
     }
 
+    fn impl_collect_symbols(&self, builder: &mut VariantBuilder) {
+        builder
+            .add_pattern_match_iter(
+                self.fields,
+                &PATHS.collect_symbols.collect_symbols.sig,
+                &PATHS.collect_symbols.collect_symbols.variant,
+            )
+            .stage_trait(self.input_name, &PATHS.collect_symbols.path);
+    }
+
+    fn impl_collect_children(&self, builder: &mut VariantBuilder) {
+        builder
+            .add_pattern_match_iter(
+                self.fields,
+                &PATHS.collect_children.collect_children.sig,
+                &PATHS.collect_children.collect_children.variant,
+            )
+            .stage_trait(self.input_name, &PATHS.collect_children.path);
+    }
+
+    /// The variants are not symbols, walking a choice visits the struct of the variant.
+    fn impl_walk(&self, builder: &mut VariantBuilder) {
+        let input_name = self.input_name;
+        let visit = &PATHS.visit.path;
+        let accept = &PATHS.visit.accept.variant;
+        let visit_mut = &PATHS.visit_mut.path;
+        let accept_mut = &PATHS.visit_mut.accept_mut.variant;
+        let walk = &PATHS.walk.path;
+        let walk_sig = &PATHS.walk.walk.sig;
+        let walk_mut = &PATHS.walk_mut.path;
+        let walk_mut_sig = &PATHS.walk_mut.walk_mut.sig;
+        let variant_names = &self.fields.variant_names;
+        let variant_types_names = &self.fields.variant_types_names;
+
+        builder
+            .add(quote! {
+                impl<V: ?Sized> #walk<V> for #input_name
+                where
+                    #(#variant_types_names: #visit<V>,)*
+                {
+                    #walk_sig {
+                        use #visit;
+                        match self {
+                            #(Self::#variant_names(inner) => inner.#accept,)*
+                        }
+                    }
+                }
+
+                impl<V: ?Sized> #walk_mut<V> for #input_name
+                where
+                    #(#variant_types_names: #visit_mut<V>,)*
+                {
+                    #walk_mut_sig {
+                        use #visit_mut;
+                        match self {
+                            #(Self::#variant_names(inner) => inner.#accept_mut,)*
+                        }
+                    }
+                }
+            })
+            .stage();
+    }
+
+    fn impl_debug(&self, builder: &mut VariantBuilder) {
+        let variant_names = &self.fields.variant_names;
+
+        builder
+            .add(quote! {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    match self {
+                        #(Self::#variant_names(inner) => f.debug_tuple(stringify!(#variant_names)).field(inner).finish(),)*
+                    }
+                }
+            })
+            .stage_trait(self.input_name, &parse_quote!(std::fmt::Debug));
+    }
+
+    /// Fields are only serialized with the `serde` feature, the trait is empty otherwise.
+    fn impl_serialize_fields(&self, builder: &mut VariantBuilder) {
+        #[cfg(feature = "serde")]
+        builder.add_pattern_match_iter(
+            &self.fields,
+            &PATHS.serialize_fields.serialize_fields.sig,
+            &PATHS.serialize_fields.serialize_fields.variant,
+        );
+
+        builder.stage_trait(self.input_name, &PATHS.serialize_fields.path);
+    }
+
     fn impl_queryable(&self, builder: &mut VariantBuilder) {
         let queryable = &PATHS.queryable.path;
 
@@ -227,9 +384,56 @@ impl<'a> EnumBuilder<'a> {
             builder
                 .add(quote! { const _: () = <#input_name as  #check_queryable>::CHECK; })
                 .stage();
+
+            self.impl_collect_query_names(builder);
         }
     }
 
+    #[cfg(feature = "assertions")]
+    fn impl_collect_query_names(&self, builder: &mut VariantBuilder) {
+        let collect_query_names = &PATHS.collect_query_names.path;
+        let sig = &PATHS.collect_query_names.collect_query_names.sig;
+        let variant = &PATHS.collect_query_names.collect_query_names.variant;
+        let variant_types_names = &self.fields.variant_types_names;
+
+        builder
+            .add(quote! {
+                #sig {
+                    if !visited.insert(std::any::TypeId::of::<Self>()) {
+                        return;
+                    }
+                    #(<#variant_types_names as #collect_query_names>::#variant;)*
+                }
+            })
+            .stage_trait(self.input_name, collect_query_names);
+    }
+
+    fn impl_derive_query(&self, builder: &mut VariantBuilder) {
+        let derive_query = &PATHS.derive_query.path;
+        let node_kinds_sig = &PATHS.derive_query.node_kinds.sig;
+        let node_kinds = &PATHS.derive_query.node_kinds.variant;
+        let derive_query_sig = &PATHS.derive_query.derive_query.sig;
+        let variant = &PATHS.derive_query.derive_query.variant;
+        let variant_types_names = &self.fields.variant_types_names;
+
+        builder
+            .add(quote! {
+                #node_kinds_sig {
+                    let mut node_kinds = vec![];
+                    #(node_kinds.extend(<#variant_types_names as #derive_query>::#node_kinds);)*
+                    node_kinds
+                }
+
+                #derive_query_sig {
+                    if !query.visit::<Self>() {
+                        return;
+                    }
+                    #(<#variant_types_names as #derive_query>::#variant;)*
+                }
+            })
+            .stage_trait(self.input_name, derive_query);
+    }
+
     fn impl_parent(&self, builder: &mut VariantBuilder) {
         builder
             .add_pattern_match_iter(
@@ -241,6 +445,11 @@ impl<'a> EnumBuilder<'a> {
     }
 
     fn impl_scope(&self, builder: &mut VariantBuilder) {
+        if self.features.scope.is_some() {
+            let fields = Fields::default();
+            return self.code_gen(builder, ScopeBuilder::new(self.input_name, &fields));
+        }
+
         builder
             .add_pattern_match_iter(
                 &self.fields,
@@ -259,16 +468,31 @@ impl<'a> EnumBuilder<'a> {
     }
 
     fn impl_comment(&self, builder: &mut VariantBuilder) {
+        if self.features.comment.is_some() {
+            let fields = Fields::default();
+            return self.code_gen(builder, CommentBuilder::new(self.input_name, &fields));
+        }
+
         builder
             .add_pattern_match_iter(
                 &self.fields,
                 &PATHS.is_comment.is_comment.sig,
                 &PATHS.is_comment.is_comment.variant,
             )
+            .add_pattern_match_iter(
+                self.fields,
+                &PATHS.is_comment.comment_position.sig,
+                &PATHS.is_comment.comment_position.variant,
+            )
             .stage_trait(&self.input_name, &PATHS.is_comment.path);
     }
 
     fn impl_code_lens(&self, builder: &mut VariantBuilder) {
+        if self.features.lsp_code_lens.is_some() {
+            let fields = Fields::default();
+            return self.code_gen(builder, CodeLensBuilder::new(self.input_name, &fields));
+        }
+
         builder
             .add_pattern_match_iter(
                 &self.fields,
@@ -279,6 +503,14 @@ impl<'a> EnumBuilder<'a> {
     }
 
     fn impl_completion_items(&self, builder: &mut VariantBuilder) {
+        if self.features.lsp_completion_items.is_some() {
+            let fields = Fields::default();
+            return self.code_gen(
+                builder,
+                CompletionItemsBuilder::new(self.input_name, &fields),
+            );
+        }
+
         builder
             .add_pattern_match_iter(
                 &self.fields,
@@ -289,6 +521,14 @@ impl<'a> EnumBuilder<'a> {
     }
 
     fn impl_document_symbol(&self, builder: &mut VariantBuilder) {
+        if self.features.lsp_document_symbols.is_some() {
+            let fields = Fields::default();
+            return self.code_gen(
+                builder,
+                DocumentSymbolBuilder::new(self.input_name, &fields),
+            );
+        }
+
         builder
             .add_pattern_match_iter(
                 &self.fields,
@@ -299,6 +539,11 @@ impl<'a> EnumBuilder<'a> {
     }
 
     fn impl_hover_info(&self, builder: &mut VariantBuilder) {
+        if self.features.lsp_hover_info.is_some() {
+            let fields = Fields::default();
+            return self.code_gen(builder, HoverBuilder::new(self.input_name, &fields));
+        }
+
         builder
             .add_pattern_match_iter(
                 &self.fields,
@@ -309,6 +554,11 @@ impl<'a> EnumBuilder<'a> {
     }
 
     fn impl_inlay_hint(&self, builder: &mut VariantBuilder) {
+        if self.features.lsp_inlay_hints.is_some() {
+            let fields = Fields::default();
+            return self.code_gen(builder, InlayHintsBuilder::new(self.input_name, &fields));
+        }
+
         builder
             .add_pattern_match_iter(
                 &self.fields,
@@ -319,6 +569,14 @@ impl<'a> EnumBuilder<'a> {
     }
 
     fn impl_semantic_tokens(&self, builder: &mut VariantBuilder) {
+        if self.features.lsp_semantic_tokens.is_some() {
+            let fields = Fields::default();
+            return self.code_gen(
+                builder,
+                SemanticTokensBuilder::new(self.input_name, &fields),
+            );
+        }
+
         builder
             .add_pattern_match_iter(
                 &self.fields,
@@ -329,6 +587,14 @@ impl<'a> EnumBuilder<'a> {
     }
 
     fn impl_go_to_definition(&self, builder: &mut VariantBuilder) {
+        if self.features.lsp_go_to_definition.is_some() {
+            let fields = Fields::default();
+            return self.code_gen(
+                builder,
+                GotoDefinitionBuilder::new(self.input_name, &fields),
+            );
+        }
+
         builder
             .add_pattern_match_iter(
                 &self.fields,
@@ -339,6 +605,14 @@ impl<'a> EnumBuilder<'a> {
     }
 
     fn impl_go_to_declaration(&self, builder: &mut VariantBuilder) {
+        if self.features.lsp_go_to_declaration.is_some() {
+            let fields = Fields::default();
+            return self.code_gen(
+                builder,
+                GoToDeclarationBuilder::new(self.input_name, &fields),
+            );
+        }
+
         builder
             .add_pattern_match_iter(
                 &self.fields,
@@ -395,11 +669,23 @@ impl<'a> EnumBuilder<'a> {
         });
     }
 
+    fn fn_add_value(&self, builder: &mut VariantBuilder) {
+        let sig = &PATHS.symbol_builder_trait.add_value.sig;
+        let variant = &PATHS.symbol_builder_trait.add_value.variant;
+
+        builder.add(quote! {
+            #sig {
+                self.unique_field.get_rc().borrow_mut().#variant
+            }
+        });
+    }
+
     fn impl_try_from(&self, builder: &mut VariantBuilder) {
         let name = self.input_name;
         let input_builder_name = &self.input_builder_name;
 
         let variant_names = &self.fields.variant_names;
+        let variant_types_names = &self.fields.variant_types_names;
         let variant_builder_names = &self.fields.variant_builder_names;
 
         let try_from_builder = &PATHS.try_from_builder;
@@ -416,7 +702,9 @@ impl<'a> EnumBuilder<'a> {
 
                     #(
                         if let Some(variant) = builder.unique_field.get_rc().borrow().downcast_ref::<#variant_builder_names>() {
-                            return Ok(Self::#variant_names(variant.try_into_builder(params)?));
+                            // Boxed variants are converted with `From<T> for Box<T>`
+                            let variant: #variant_types_names = variant.try_into_builder(params)?;
+                            return Ok(Self::#variant_names(variant.into()));
                         };
                     )*
                     Err(auto_lsp::core::builder_error!(
@@ -429,4 +717,32 @@ impl<'a> EnumBuilder<'a> {
         });
         builder.stage();
     }
+
+    /// The placeholder of a choice is the placeholder of its first variant that has one.
+    fn impl_placeholder(&self, builder: &mut VariantBuilder) {
+        let placeholder = &PATHS.placeholder.path;
+        let sig = &PATHS.placeholder.placeholder.sig;
+        let variant = &PATHS.placeholder.placeholder.variant;
+        let variant_names = &self.fields.variant_names;
+        let variant_types_names = &self.fields.variant_types_names;
+
+        builder
+            .add(quote! {
+                #sig {
+                    if !visited.insert(std::any::TypeId::of::<Self>()) {
+                        return None;
+                    }
+                    let mut placeholder = None;
+                    #(
+                        if placeholder.is_none() {
+                            placeholder = <#variant_types_names as #placeholder>::#variant
+                                .map(|variant| Self::#variant_names(variant.into()));
+                        }
+                    )*
+                    visited.remove(&std::any::TypeId::of::<Self>());
+                    placeholder
+                }
+            })
+            .stage_trait(self.input_name, placeholder);
+    }
 }
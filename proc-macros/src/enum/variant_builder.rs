@@ -1,14 +1,20 @@
-use crate::utilities::get_raw_type_name;
+use crate::{
+    utilities::{get_builder_path, get_symbol_path, is_option, is_vec},
+    VariantHelpers,
+};
+use darling::FromVariant;
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote, ToTokens};
-use syn::{Ident, Path};
+use syn::{Ident, Path, Type};
 
 /// A container for information about enum variants.
 ///
 /// This struct stores:
 /// - `variant_names`: The names of the variants in the enum.
-/// - `variant_types_names`: The types of the fields in the variants.
-/// - `variant_builder_names`: The builder names derived from the variant types.
+/// - `variant_types`: The types of the fields in the variants, as declared.
+/// - `variant_types_names`: The paths of the types of the fields in the variants, without `Box`.
+/// - `variant_builder_names`: The builder paths derived from the variant types.
+/// - `unit_variants`: The symbols generated for unit variants, with their query names and node kinds.
 ///
 /// # Example
 ///
@@ -16,13 +22,17 @@ use syn::{Ident, Path};
 /// enum MyEnum {
 ///     Variant1(u8),
 ///     Variant2(String),
+///     #[ast(query_name = "variant3")]
+///     Variant3,
 /// }
 ///
 /// // Extracted as:
 /// Variants {
-///     variant_names: vec![Variant1, Variant2],
-///     variant_types_names: vec![u8, String],
-///     variant_builder_names: vec![u8Builder, StringBuilder],
+///     variant_names: vec![Variant1, Variant2, Variant3],
+///     variant_types: vec![u8, String, MyEnumVariant3],
+///     variant_types_names: vec![u8, String, MyEnumVariant3],
+///     variant_builder_names: vec![u8Builder, StringBuilder, MyEnumVariant3Builder],
+///     unit_variants: vec![UnitVariant { type_name: MyEnumVariant3, query_name: "variant3", node_kind: None }],
 /// }
 /// ```
 #[derive(Debug)]
@@ -30,48 +40,117 @@ pub struct Variants {
     /// Names of the enum variants (e.g., `Variant1`).
     pub variant_names: Vec<proc_macro2::Ident>,
 
-    /// Types of the fields in the variants (e.g., `u8`).
-    pub variant_types_names: Vec<proc_macro2::Ident>,
+    /// Types of the fields in the variants as declared (e.g., `Box<Expr>`).
+    pub variant_types: Vec<Type>,
 
-    /// Builder names derived from the variant types (e.g., `u8Builder`).
-    pub variant_builder_names: Vec<proc_macro2::Ident>,
+    /// Paths of the types of the fields in the variants (e.g., `Expr`).
+    pub variant_types_names: Vec<Path>,
+
+    /// Builder paths derived from the variant types (e.g., `ExprBuilder`).
+    pub variant_builder_names: Vec<Path>,
+
+    /// Symbols generated for unit variants (e.g., `MyEnumVariant3`).
+    pub unit_variants: Vec<UnitVariant>,
+}
+
+/// A symbol generated for a unit variant, given with `#[ast(query_name = "...", node_kind = "...")]`.
+///
+/// The query name defaults to the node kind.
+#[derive(Debug)]
+pub struct UnitVariant {
+    pub type_name: proc_macro2::Ident,
+    pub query_name: String,
+    pub node_kind: Option<String>,
 }
 
 /// Extracts variant information from a syn::Data enum definition.
 ///
 /// See the `Variants` struct for more information.
-pub fn extract_variants(data: &syn::Data) -> Variants {
+///
+/// Returns an error if a variant is neither a unit variant with a query name nor a variant with a single field.
+pub fn extract_variants(input_name: &Ident, data: &syn::Data) -> darling::Result<Variants> {
+    let mut errors = darling::Error::accumulator();
     let mut ret_fields = Variants {
         variant_names: vec![],
 
+        variant_types: vec![],
+
         variant_types_names: vec![],
 
         variant_builder_names: vec![],
+
+        unit_variants: vec![],
     };
-    match data {
-        syn::Data::Enum(ref enum_data) => {
-            for variant in &enum_data.variants {
-                let variant_name = &variant.ident;
-                match &variant.fields {
-                    syn::Fields::Unnamed(fields) => {
-                        let first_field = fields.unnamed.first().unwrap();
-                        ret_fields.variant_names.push(variant_name.clone());
-                        ret_fields
-                            .variant_types_names
-                            .push(format_ident!("{}", get_raw_type_name(&first_field.ty)));
-                        ret_fields.variant_builder_names.push(format_ident!(
-                            "{}Builder",
-                            get_raw_type_name(&first_field.ty)
-                        ));
-                    }
-                    _ => panic!("This proc macro only works with enums"),
+    let enum_data = match data {
+        syn::Data::Enum(ref enum_data) => enum_data,
+        _ => {
+            return Err(darling::Error::custom(
+                "This proc macro only works with enums",
+            ))
+        }
+    };
+
+    for variant in &enum_data.variants {
+        let variant = match errors.handle(VariantHelpers::from_variant(variant)) {
+            Some(variant) => variant,
+            None => continue,
+        };
+        let variant_name = &variant.ident;
+
+        let query_name = variant.query_name.as_ref().or(variant.node_kind.as_ref());
+        let (ty, type_name) = match (variant.fields.fields.first(), query_name) {
+            (Some(field), None) if is_vec(&field.ty) || is_option(&field.ty) => {
+                errors.push(
+                    darling::Error::custom(
+                        "Variants must hold a single symbol, `Vec` and `Option` can't be used",
+                    )
+                    .with_span(&field.ty),
+                );
+                continue;
+            }
+            (Some(field), None) => match get_symbol_path(&field.ty) {
+                Ok(path) => (field.ty.clone(), path),
+                Err(err) => {
+                    errors.push(err.into());
+                    continue;
                 }
+            },
+            (None, Some(query_name)) => {
+                let type_name = format_ident!("{}{}", input_name, variant_name);
+                ret_fields.unit_variants.push(UnitVariant {
+                    type_name: type_name.clone(),
+                    query_name: query_name.clone(),
+                    node_kind: variant.node_kind.clone(),
+                });
+                (syn::parse_quote!(#type_name), Path::from(type_name))
             }
-        }
-        _ => panic!("This proc macro only works with enums"),
+            (Some(_), Some(_)) => {
+                errors.push(
+                    darling::Error::custom("Only unit variants accept a query name or a node kind")
+                        .with_span(variant_name),
+                );
+                continue;
+            }
+            (None, None) => {
+                errors.push(
+                    darling::Error::custom(
+                        "Unit variants require a query name, use #[ast(query_name = \"...\")]",
+                    )
+                    .with_span(variant_name),
+                );
+                continue;
+            }
+        };
+
+        ret_fields.variant_names.push(variant_name.clone());
+        ret_fields.variant_types.push(ty);
+        ret_fields
+            .variant_builder_names
+            .push(get_builder_path(&type_name));
+        ret_fields.variant_types_names.push(type_name);
     }
 
-    ret_fields
+    errors.finish_with(ret_fields)
 }
 
 /// Builder for enum variants
@@ -96,11 +175,11 @@ impl VariantBuilder {
     ///
     /// The closure takes 3 arguments:
     /// - 1: `&Ident`: The variant name
-    /// - 2: `&Ident`: The variant type name
-    /// - 3: `&Ident`: The variant builder name
+    /// - 2: `&Path`: The variant type path
+    /// - 3: `&Path`: The variant builder path
     pub fn add_iter<F>(&mut self, variants: &Variants, body: F) -> &mut Self
     where
-        F: Fn(&Ident, &Ident, &Ident) -> TokenStream,
+        F: Fn(&Ident, &Path, &Path) -> TokenStream,
     {
         let variants = variants
             .variant_names
@@ -154,8 +233,8 @@ impl VariantBuilder {
     ///
     /// The closure takes 3 arguments:
     /// - 1: `&Ident`: The variant name
-    /// - 2: `&Ident`: The variant type name
-    /// - 3: `&Ident`: The variant builder name
+    /// - 2: `&Path`: The variant type path
+    /// - 3: `&Path`: The variant builder path
     pub fn add_fn_iter<F>(
         &mut self,
         variants: &Variants,
@@ -165,7 +244,7 @@ impl VariantBuilder {
         after: Option<TokenStream>,
     ) -> &mut Self
     where
-        F: Fn(&Ident, &Ident, &Ident) -> TokenStream,
+        F: Fn(&Ident, &Path, &Path) -> TokenStream,
     {
         let variants = variants
             .variant_names
@@ -290,24 +369,132 @@ mod tests {
 
         let input: DeriveInput = syn::parse2(data).unwrap();
         let data = &input.data;
-        let variants = extract_variants(&data);
+        let variants = extract_variants(&input.ident, data).unwrap();
 
         assert_eq!(variants.variant_names.len(), 2);
         assert_eq!("Variant1", variants.variant_names[0].to_string());
         assert_eq!("Variant2", variants.variant_names[1].to_string());
 
         assert_eq!(variants.variant_types_names.len(), 2);
-        assert_eq!("u8", variants.variant_types_names[0].to_string());
-        assert_eq!("String", variants.variant_types_names[1].to_string());
+        assert_eq!(
+            "u8",
+            variants.variant_types_names[0]
+                .to_token_stream()
+                .to_string()
+        );
+        assert_eq!(
+            "String",
+            variants.variant_types_names[1]
+                .to_token_stream()
+                .to_string()
+        );
 
         assert_eq!(variants.variant_builder_names.len(), 2);
-        assert_eq!("u8Builder", variants.variant_builder_names[0].to_string());
+        assert_eq!(
+            "u8Builder",
+            variants.variant_builder_names[0]
+                .to_token_stream()
+                .to_string()
+        );
         assert_eq!(
             "StringBuilder",
-            variants.variant_builder_names[1].to_string()
+            variants.variant_builder_names[1]
+                .to_token_stream()
+                .to_string()
         );
     }
 
+    #[test]
+    fn test_extract_unit_variants() {
+        let input: DeriveInput = parse_quote! {
+            enum Visibility {
+                #[ast(query_name = "public")]
+                Public,
+                Private(Private),
+            }
+        };
+        let variants = extract_variants(&input.ident, &input.data).unwrap();
+
+        assert_eq!("Public", variants.variant_names[0].to_string());
+        assert_eq!(
+            "VisibilityPublic",
+            variants.variant_types_names[0]
+                .to_token_stream()
+                .to_string()
+        );
+        assert_eq!(
+            "VisibilityPublicBuilder",
+            variants.variant_builder_names[0]
+                .to_token_stream()
+                .to_string()
+        );
+        assert_eq!(
+            "Private",
+            variants.variant_types_names[1]
+                .to_token_stream()
+                .to_string()
+        );
+
+        assert_eq!(variants.unit_variants.len(), 1);
+        assert_eq!(
+            "VisibilityPublic",
+            variants.unit_variants[0].type_name.to_string()
+        );
+        assert_eq!("public", variants.unit_variants[0].query_name);
+        assert_eq!(None, variants.unit_variants[0].node_kind);
+    }
+
+    #[test]
+    fn test_extract_variant_paths() {
+        let input: DeriveInput = parse_quote! {
+            enum Expression {
+                Function(crate::ast::Function),
+                Parenthesized(Box<Expression>),
+            }
+        };
+        let variants = extract_variants(&input.ident, &input.data).unwrap();
+
+        assert_eq!(
+            "crate :: ast :: Function",
+            variants.variant_types_names[0]
+                .to_token_stream()
+                .to_string()
+        );
+        assert_eq!(
+            "crate :: ast :: FunctionBuilder",
+            variants.variant_builder_names[0]
+                .to_token_stream()
+                .to_string()
+        );
+        assert_eq!(
+            "Box < Expression >",
+            variants.variant_types[1].to_token_stream().to_string()
+        );
+        assert_eq!(
+            "Expression",
+            variants.variant_types_names[1]
+                .to_token_stream()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_unsupported_variants() {
+        let input: DeriveInput = parse_quote! {
+            enum MyEnum {
+                Unit,
+                #[ast(query_name = "tuple")]
+                Tuple(u8),
+                Named { field: u8 },
+                Pair(u8, u8),
+                Many(Vec<u8>),
+            }
+        };
+        let errors = extract_variants(&input.ident, &input.data).unwrap_err();
+
+        assert_eq!(errors.len(), 5);
+    }
+
     #[test]
     fn test_stage_trait() {
         let data = quote! {
@@ -321,7 +508,7 @@ mod tests {
 
         let input: DeriveInput = syn::parse2(data).unwrap();
         let data = &input.data;
-        let variants = extract_variants(&data);
+        let variants = extract_variants(&input.ident, data).unwrap();
 
         builder.add_iter(&variants, |name, _type, _| {
             quote! {
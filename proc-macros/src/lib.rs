@@ -1,4 +1,6 @@
 #![allow(deprecated)]
+// PATHS is rebuilt on each use on purpose: its tokens are only valid during the current macro expansion
+#![allow(clippy::borrow_interior_mutable_const)]
 
 extern crate proc_macro;
 
@@ -13,14 +15,17 @@ use quote::{format_ident, ToTokens};
 use r#enum::*;
 use r#struct::*;
 use struct_builder::StructBuilder;
-use syn::{parse_macro_input, DeriveInput};
+use syn::punctuated::Punctuated;
+use syn::{parse_macro_input, DeriveInput, Path, Token};
 use variant_builder::extract_variants;
+use visitor::VisitorBuilder;
 
 mod r#enum;
 mod meta;
 mod paths;
 mod r#struct;
 mod utilities;
+mod visitor;
 
 use std::cell::LazyCell;
 
@@ -39,8 +44,56 @@ const PATHS: LazyCell<Paths> = LazyCell::new(|| Paths::default());
 /// ## Attributes
 ///
 /// - `query_name`: The name of the Tree-sitter query associated with this struct.
+/// - `node_kind`: The Tree-sitter node kind matched by this struct, used to derive the core query (see `configure_parsers!`).
+///   `query_name` defaults to the node kind.
 /// - `kind`: Specifies the type of symbol to generate, which can be either `symbol` or `reference`.
 ///
+/// ## Fields
+///
+/// Fields are other symbols generated by `seq` or `choice`, wrapped in a `Vec` or an `Option` if needed.
+///
+/// Field types can be module-qualified paths such as `crate::ast::Function`, and `Box<T>` is accepted
+/// for recursive grammars (symbols are always stored behind a pointer, so the `Box` is unwrapped).
+///
+/// Tuple structs such as `struct Name(Identifier);` are supported as well, their fields are accessed with `.0`, `.1`, ...
+///
+/// Fields annotated with `#[ast(text = "capture_name")]` are values instead of symbols:
+/// the text of the capture is parsed with [`std::str::FromStr`] when the symbol is built,
+/// and a diagnostic is reported if parsing fails.
+///
+/// Value fields can be wrapped in a `Vec` or an `Option` as well.
+/// Value types must implement [`std::fmt::Debug`], which is generated for every symbol,
+/// and `serde::Serialize` with the `serde` feature.
+///
+/// Other field attributes are:
+///
+/// - `capture`: The capture of a symbol field, when it differs from the query name of its type.
+///   This allows several fields to share a type, the type must be a `seq` struct.
+/// - `min` and `max`: The number of elements accepted by a `Vec` field.
+/// - `default`: A field that is absent takes a default value instead of failing.
+///   Value fields take their [`Default`] value, symbol fields take an empty placeholder symbol
+///   (see `Placeholder` in `auto_lsp::core::build`), built at the end of the parent.
+/// - `field`: The Tree-sitter field of the node, used to derive the core query.
+///   A symbol field is then only captured in this field of the parent node, and value fields require one.
+///   Symbol fields in different Tree-sitter fields can share query names, such as the `left` and `right` of a binary operator.
+///
+/// Invalid fields are reported as diagnostics when the symbol is built.
+///
+/// ```ignore
+/// #[seq(query_name = "function", kind(symbol()))]
+/// struct Function {
+///     #[ast(capture = "name")]
+///     name: Identifier,
+///     #[ast(capture = "parameter", max = 255)]
+///     parameters: Vec<Identifier>,
+///     #[ast(text = "default")]
+///     defaults: Vec<i64>,
+///     #[ast(text = "return_type", default)]
+///     return_type: String,
+///     body: Option<Body>,
+/// }
+/// ```
+///
 /// ### symbol
 ///
 /// When the `kind` attribute is set to `symbol`, the generated symbol will implement the `AstSymbol` trait.
@@ -161,11 +214,35 @@ pub fn seq(args: TokenStream, input: TokenStream) -> TokenStream {
             .into();
     }
 
+    if !input.generics.params.is_empty() {
+        return syn::Error::new_spanned(
+            &input.generics,
+            "Generic parameters are not supported by `seq`",
+        )
+        .to_compile_error()
+        .into();
+    }
+
     let input_name = &input.ident;
     let input_builder_name = format_ident!("{}Builder", input_name);
 
-    let fields = extract_fields(&derive_input.data);
-    let query_name = attributes.query_name;
+    let fields = match extract_fields(&derive_input.data) {
+        Ok(v) => v,
+        Err(e) => return e.write_errors().into(),
+    };
+    let query_name = match attributes
+        .query_name
+        .as_ref()
+        .or(attributes.node_kind.as_ref())
+    {
+        Some(query_name) => query_name,
+        None => {
+            return darling::Error::custom("Expected `query_name` or `node_kind`")
+                .write_errors()
+                .into()
+        }
+    };
+    let node_kind = attributes.node_kind.as_deref();
 
     let input_attr = input.attrs;
     let tokens = match attributes.kind {
@@ -173,9 +250,10 @@ pub fn seq(args: TokenStream, input: TokenStream) -> TokenStream {
             &ReferenceOrSymbolFeatures::Reference(&reference_features),
             &derive_input.data,
             &input_attr,
-            &input_name,
+            input_name,
             &input_builder_name,
-            &query_name,
+            query_name,
+            node_kind,
             &fields,
         )
         .to_token_stream(),
@@ -183,9 +261,10 @@ pub fn seq(args: TokenStream, input: TokenStream) -> TokenStream {
             &ReferenceOrSymbolFeatures::Symbol(&symbol_features),
             &derive_input.data,
             &input_attr,
-            &input_name,
+            input_name,
             &input_builder_name,
-            &query_name,
+            query_name,
+            node_kind,
             &fields,
         )
         .to_token_stream(),
@@ -205,22 +284,133 @@ pub fn seq(args: TokenStream, input: TokenStream) -> TokenStream {
 /// }
 /// ```
 ///
-/// The `choice` macro does not accept any attributes. It invokes the AstSymbol implementation of the inner variant.
+/// By default, the `choice` macro invokes the AstSymbol implementation of the inner variant.
 ///
 /// This macro functions similarly to `enum_dispatch` but is tailored for the specific needs of `auto_lsp`.
 ///
 /// However, every variant of the enum **has to be** a struct or enum that implements the `AstSymbol` trait.
 ///
 /// This means that all variants must be a unique symbol, and therefore a `Vec` or Option of `AstSymbol` can't be used.
+///
+/// As with `seq` fields, variant types can be module-qualified paths such as `crate::ast::Function`,
+/// and `Box<T>` is accepted for recursive grammars (the variant then holds the boxed symbol).
+///
+/// ## Attributes
+///
+/// `choice` accepts the same capabilities attributes as `seq` symbols, without `query_name` and `kind`.
+///
+/// When a capability is set, the enum no longer dispatches it to its variants:
+/// it is either implemented manually (using `user`) or generated (using `code_gen`).
+///
+/// Code generation applies to the enum itself, paths such as `self::name` can't refer to the fields of a variant.
+///
+/// ```ignore
+/// #[choice(lsp_hover_info(user))]
+/// enum Statement {
+///     Function(Function),
+///     Class(Class),
+/// }
+///
+/// impl GetHover for Statement {
+///     fn get_hover(&self, doc: &Document) -> Option<lsp_types::Hover> {
+///         /* ... */
+///     }
+/// }
+/// ```
+///
+/// ## Unit variants
+///
+/// Unit variants are matched by the query name given with `#[ast(query_name = "...")]`.
+/// A `node_kind` can be given as well to derive the core query, the query name then defaults to the node kind.
+///
+/// A symbol named after the enum and the variant is generated for each of them (`VisibilityPublic` below),
+/// with the visibility of the enum, so the variant is matched with `Visibility::Public(_)`.
+///
+/// ```ignore
+/// #[choice]
+/// enum Visibility {
+///     #[ast(query_name = "public")]
+///     Public,
+///     #[ast(query_name = "private")]
+///     Private,
+/// }
+/// ```
 #[proc_macro_attribute]
-pub fn choice(_args: TokenStream, input: TokenStream) -> TokenStream {
+pub fn choice(args: TokenStream, input: TokenStream) -> TokenStream {
+    // Parse args
+
+    let attr_meta = match NestedMeta::parse_meta_list(args.into()) {
+        Ok(v) => v,
+        Err(e) => return e.into_compile_error().into(),
+    };
+
+    let features = match SymbolFeatures::from_list(&attr_meta) {
+        Ok(v) => v,
+        Err(e) => return e.write_errors().into(),
+    };
+
+    // Parse input
+
     let input = parse_macro_input!(input as DeriveInput);
 
     let input_name = &input.ident;
     let input_builder_name = format_ident!("{}Builder", input_name);
-    let fields = extract_variants(&input.data);
+    let fields = match extract_variants(input_name, &input.data) {
+        Ok(v) => v,
+        Err(e) => return e.write_errors().into(),
+    };
     let mut tokens = proc_macro2::TokenStream::new();
 
-    EnumBuilder::new(&input_name, &input_builder_name, &fields).to_tokens(&mut tokens);
+    EnumBuilder::new(
+        &features,
+        &input.vis,
+        input_name,
+        &input_builder_name,
+        &fields,
+    )
+    .to_tokens(&mut tokens);
+    tokens.into()
+}
+
+/// A procedural macro for generating the `Visitor` and `VisitorMut` traits of an AST.
+///
+/// Takes the list of symbols generated by `seq` and `choice`, each symbol gets a `visit_<type>` hook
+/// named after its type in snake case (`CompoundStatement` becomes `visit_compound_statement`).
+///
+/// By default, a hook visits the children of the symbol in the order of its fields,
+/// and the hook of a `choice` visits the struct of its variant.
+/// An overridden hook can call `walk` (or `walk_mut`) to keep visiting the children.
+///
+/// Every symbol reachable from a visited symbol must be listed, including the symbols generated for unit variants.
+/// A missing symbol fails to compile with an error telling to add it to the `visitor!` macro,
+/// and two symbols with the same hook name are rejected.
+///
+/// ```ignore
+/// visitor!(Module, Function, Identifier);
+///
+/// struct FunctionNames(Vec<String>);
+///
+/// impl Visitor for FunctionNames {
+///     fn visit_function(&mut self, node: &Function) {
+///         self.0.push(/* ... */);
+///         node.walk(self);
+///     }
+/// }
+///
+/// let mut names = FunctionNames(vec![]);
+/// module.accept(&mut names);
+/// ```
+#[proc_macro]
+pub fn visitor(input: TokenStream) -> TokenStream {
+    let types = parse_macro_input!(input with Punctuated::<Path, Token![,]>::parse_terminated);
+    let types = types.into_iter().collect::<Vec<_>>();
+
+    let builder = VisitorBuilder::new(&types);
+    if let Err(e) = builder.check_hooks() {
+        return e.into_compile_error().into();
+    }
+
+    let mut tokens = proc_macro2::TokenStream::new();
+    builder.to_tokens(&mut tokens);
     tokens.into()
 }
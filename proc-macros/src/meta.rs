@@ -2,7 +2,7 @@
 use syn::{Ident, Type};
 
 use crate::features::*;
-use darling::{ast, util, FromDeriveInput, FromField, FromMeta};
+use darling::{ast, util, FromDeriveInput, FromField, FromMeta, FromVariant};
 
 /// Struct input when `seq` macro is used
 #[derive(Debug, FromDeriveInput)]
@@ -10,10 +10,13 @@ pub struct StructInput {
     pub data: ast::Data<util::Ignored, StructHelpers>,
 }
 
-/// Mandatory `query_name` field and `kind` field (reference or symbol)
+/// `query_name` or `node_kind` field and mandatory `kind` field (reference or symbol)
 #[derive(Debug, FromMeta)]
 pub struct UserFeatures {
-    pub query_name: String,
+    /// Defaults to `node_kind`
+    pub query_name: Option<String>,
+    /// Node kind matched by the symbol when the core query is derived
+    pub node_kind: Option<String>,
     pub kind: AstStructKind,
 }
 
@@ -22,6 +25,38 @@ pub struct UserFeatures {
 pub struct StructHelpers {
     pub ident: Option<Ident>,
     pub ty: Type,
+    /// Capture whose text is parsed into the field with [`std::str::FromStr`]
+    #[darling(default)]
+    pub text: Option<String>,
+    /// Capture of the field, when it differs from the query name of its type
+    #[darling(default)]
+    pub capture: Option<String>,
+    /// Minimum number of symbols in a `Vec` field
+    #[darling(default)]
+    pub min: Option<usize>,
+    /// Maximum number of symbols in a `Vec` field
+    #[darling(default)]
+    pub max: Option<usize>,
+    /// Value fields that are absent take their [`Default`] value
+    #[darling(default)]
+    pub default: bool,
+    /// Tree-sitter field of the node, used to derive the core query and to route captures to symbol fields
+    #[darling(default)]
+    pub field: Option<String>,
+}
+
+/// Variant of an enum when `choice` macro is used
+#[derive(FromVariant, Debug)]
+#[darling(attributes(ast), supports(unit, newtype))]
+pub struct VariantHelpers {
+    pub ident: Ident,
+    pub fields: ast::Fields<syn::Field>,
+    /// Query name matched by a unit variant
+    #[darling(default)]
+    pub query_name: Option<String>,
+    /// Node kind matched by a unit variant, used to derive the core query
+    #[darling(default)]
+    pub node_kind: Option<String>,
 }
 
 /// Reference or Symbol kind
@@ -48,16 +48,35 @@ nested_struct!(
         #[cfg(feature = "assertions")]
         pub check_conflicts: Path,
 
+        #[cfg(feature = "assertions")]
+        pub collect_query_names: CollectQueryNames {
+            pub path: Path,
+            pub collect_query_names: Method
+        },
+
+        pub derive_query: DeriveQuery {
+            pub path: Path,
+            pub node_kinds: Method,
+            pub derive_query: Method
+        },
+
+        pub placeholder: Placeholder {
+            pub path: Path,
+            pub placeholder: Method
+        },
+
         pub symbol_trait: SymbolTrait {
             pub path: Path,
             pub get_data: Method,
             pub get_mut_data: Method,
+            pub get_struct_type_name: Method,
         },
 
         pub symbol_builder_trait: SymbolBuilderTrait {
             pub path: Path,
             pub new: Method,
             pub add: Method,
+            pub add_value: Method,
             pub get_url: Method,
             pub get_range: Method,
             pub get_query_index: Method,
@@ -67,6 +86,7 @@ nested_struct!(
         pub try_from_builder: Path,
 
         pub add_symbol_trait: Path,
+        pub is_in_field: Path,
         pub try_downcast_trait: Path,
         pub finalize_trait: Path,
 
@@ -112,8 +132,10 @@ nested_struct!(
         },
         pub is_comment: IsComment {
             pub path: Path,
-            pub is_comment: Method
+            pub is_comment: Method,
+            pub comment_position: Method
         },
+        pub comment_position: Path,
         pub is_scope: IsScope {
             pub path: Path,
             pub is_scope: Method
@@ -146,6 +168,7 @@ nested_struct!(
             pub path: Path,
             pub swap: Method
         },
+        pub update_children: Path,
         pub edit_range: EditRange {
             pub path: Path,
             pub edit_range: Method
@@ -154,6 +177,39 @@ nested_struct!(
             pub path: Path,
             pub collect_references: Method
         },
+        pub collect_symbols: CollectSymbols {
+            pub path: Path,
+            pub collect_symbols: Method
+        },
+        pub collect_children: CollectChildren {
+            pub path: Path,
+            pub collect_children: Method
+        },
+        pub visit: Visit {
+            pub path: Path,
+            pub accept: Method
+        },
+        pub visit_mut: VisitMut {
+            pub path: Path,
+            pub accept_mut: Method
+        },
+        pub walk: Walk {
+            pub path: Path,
+            pub walk: Method
+        },
+        pub walk_mut: WalkMut {
+            pub path: Path,
+            pub walk_mut: Method
+        },
+        pub serialize_fields: SerializeFields {
+            pub path: Path,
+            pub serialize_fields: Method
+        },
+        #[cfg(feature = "serde")]
+        pub to_json: ToJson {
+            pub path: Path,
+            pub to_json: Method
+        },
     }
 );
 
@@ -188,6 +244,41 @@ impl Default for Paths {
             },
             #[cfg(feature = "assertions")]
             check_conflicts: core_build(parse_quote!(check_conflicts)),
+            #[cfg(feature = "assertions")]
+            collect_query_names: CollectQueryNames {
+                path: core_build(parse_quote!(CollectQueryNames)),
+                collect_query_names: Method {
+                    sig: quote! { fn collect_query_names(
+                        names: &mut Vec<&'static str>,
+                        visited: &mut std::collections::HashSet<std::any::TypeId>,
+                    ) },
+                    variant: quote! { collect_query_names(names, visited) },
+                },
+            },
+
+            placeholder: Placeholder {
+                path: core_build(parse_quote!(Placeholder)),
+                placeholder: Method {
+                    sig: quote! { fn placeholder(
+                        url: std::sync::Arc<auto_lsp::lsp_types::Url>,
+                        range: std::ops::Range<usize>,
+                        visited: &mut std::collections::HashSet<std::any::TypeId>,
+                    ) -> Option<Self> },
+                    variant: quote! { placeholder(url.clone(), range.clone(), visited) },
+                },
+            },
+
+            derive_query: DeriveQuery {
+                path: core_build(parse_quote!(DeriveQuery)),
+                node_kinds: Method {
+                    sig: quote! { fn node_kinds() -> Vec<(&'static str, &'static str)> },
+                    variant: quote! { node_kinds() },
+                },
+                derive_query: Method {
+                    sig: quote! { fn derive_query(query: &mut auto_lsp::core::build::QueryBuilder) },
+                    variant: quote! { derive_query(query) },
+                },
+            },
 
             symbol_trait: SymbolTrait {
                 path: core_ast(parse_quote!(AstSymbol)),
@@ -199,6 +290,10 @@ impl Default for Paths {
                     sig: quote! { fn get_mut_data(&mut self) -> &mut auto_lsp::core::ast::SymbolData },
                     variant: quote! { get_mut_data() },
                 },
+                get_struct_type_name: Method {
+                    sig: quote! { fn get_struct_type_name(&self) -> &'static str },
+                    variant: quote! { get_struct_type_name() },
+                },
             },
 
             symbol_builder_trait: SymbolBuilderTrait {
@@ -219,6 +314,14 @@ impl Default for Paths {
                     ) -> Result<Option<auto_lsp::core::build::PendingSymbol>, auto_lsp::lsp_types::Diagnostic> },
                     variant: quote! { add(capture, params) },
                 },
+                add_value: Method {
+                    sig: quote! { fn add_value(
+                        &mut self,
+                        capture: &auto_lsp::tree_sitter::QueryCapture,
+                        params: &mut auto_lsp::core::build::MainBuilder,
+                    ) -> Result<bool, auto_lsp::lsp_types::Diagnostic> },
+                    variant: quote! { add_value(capture, params) },
+                },
                 get_url: Method {
                     sig: quote! { fn get_url(&self) -> std::sync::Arc<auto_lsp::lsp_types::Url> },
                     variant: quote! { get_url() },
@@ -235,6 +338,7 @@ impl Default for Paths {
             try_from_builder: core_build(parse_quote!(TryFromBuilder)),
             try_into_builder: core_build(parse_quote!(TryIntoBuilder)),
             add_symbol_trait: core_build(parse_quote!(AddSymbol)),
+            is_in_field: core_build(parse_quote!(is_in_field)),
             try_downcast_trait: core_build(parse_quote!(TryDownCast)),
             finalize_trait: core_build(parse_quote!(Finalize)),
 
@@ -314,7 +418,12 @@ impl Default for Paths {
                     sig: quote! { fn is_comment(&self) -> bool },
                     variant: quote! { is_comment() },
                 },
+                comment_position: Method {
+                    sig: quote! { fn comment_position(&self) -> auto_lsp::core::ast::CommentPosition },
+                    variant: quote! { comment_position() },
+                },
             },
+            comment_position: core_ast(parse_quote!(CommentPosition)),
             is_scope: IsScope {
                 path: core_ast(parse_quote!(IsScope)),
                 is_scope: Method {
@@ -362,12 +471,11 @@ impl Default for Paths {
                 swap: Method {
                     sig: quote! { fn dyn_update<'a>(
                         &mut self,
-                        start: usize,
-                        offset: isize,
+                        range: &std::ops::Range<usize>,
                         parent_check: Option<auto_lsp::core::ast::WeakSymbol>,
                         builder_params: &'a mut auto_lsp::core::build::MainBuilder,
                     ) -> std::ops::ControlFlow<Result<(), auto_lsp::lsp_types::Diagnostic>, ()> },
-                    variant: quote! { dyn_update(start, offset, parent_check, builder_params) },
+                    variant: quote! { dyn_update(range, parent_check, builder_params) },
                 },
             },
             static_swap: StaticSwap {
@@ -375,14 +483,14 @@ impl Default for Paths {
                 swap: Method {
                     sig: quote! { fn update<'a>(
                         &mut self,
-                        start: usize,
-                        offset: isize,
+                        range: &std::ops::Range<usize>,
                         parent_check: Option<auto_lsp::core::ast::WeakSymbol>,
                         builder_params: &'a mut auto_lsp::core::build::MainBuilder,
                     ) -> std::ops::ControlFlow<Result<(), auto_lsp::lsp_types::Diagnostic>, ()> },
-                    variant: quote! { update(start, offset, parent_check, builder_params) },
+                    variant: quote! { update(range, parent_check, builder_params) },
                 },
             },
+            update_children: core_ast(parse_quote!(UpdateChildren)),
             edit_range: EditRange {
                 path: core_ast(parse_quote!(UpdateRange)),
                 edit_range: Method {
@@ -397,6 +505,63 @@ impl Default for Paths {
                     variant: quote! { collect_references(builder_params) },
                 },
             },
+            collect_symbols: CollectSymbols {
+                path: core_ast(parse_quote!(CollectSymbols)),
+                collect_symbols: Method {
+                    sig: quote! { fn collect_symbols(&self, acc: &mut Vec<auto_lsp::core::ast::DynSymbol>) },
+                    variant: quote! { collect_symbols(acc) },
+                },
+            },
+            collect_children: CollectChildren {
+                path: core_ast(parse_quote!(CollectChildren)),
+                collect_children: Method {
+                    sig: quote! { fn collect_children(&self, acc: &mut Vec<auto_lsp::core::ast::DynSymbol>) },
+                    variant: quote! { collect_children(acc) },
+                },
+            },
+            visit: Visit {
+                path: core_ast(parse_quote!(Visit)),
+                accept: Method {
+                    sig: quote! { fn accept(&self, visitor: &mut V) },
+                    variant: quote! { accept(visitor) },
+                },
+            },
+            visit_mut: VisitMut {
+                path: core_ast(parse_quote!(VisitMut)),
+                accept_mut: Method {
+                    sig: quote! { fn accept_mut(&mut self, visitor: &mut V) },
+                    variant: quote! { accept_mut(visitor) },
+                },
+            },
+            walk: Walk {
+                path: core_ast(parse_quote!(Walk)),
+                walk: Method {
+                    sig: quote! { fn walk(&self, visitor: &mut V) },
+                    variant: quote! { walk(visitor) },
+                },
+            },
+            walk_mut: WalkMut {
+                path: core_ast(parse_quote!(WalkMut)),
+                walk_mut: Method {
+                    sig: quote! { fn walk_mut(&mut self, visitor: &mut V) },
+                    variant: quote! { walk_mut(visitor) },
+                },
+            },
+            serialize_fields: SerializeFields {
+                path: core_ast(parse_quote!(SerializeFields)),
+                serialize_fields: Method {
+                    sig: quote! { fn serialize_fields(&self, document: &auto_lsp::core::workspace::Document, fields: &mut auto_lsp::serde_json::Map<String, auto_lsp::serde_json::Value>) -> Result<(), auto_lsp::serde_json::Error> },
+                    variant: quote! { serialize_fields(document, fields) },
+                },
+            },
+            #[cfg(feature = "serde")]
+            to_json: ToJson {
+                path: core_ast(parse_quote!(ToJson)),
+                to_json: Method {
+                    sig: quote! { fn to_json(&self, document: &auto_lsp::core::workspace::Document) -> Result<auto_lsp::serde_json::Value, auto_lsp::serde_json::Error> },
+                    variant: quote! { to_json(document)? },
+                },
+            },
         }
     }
 }
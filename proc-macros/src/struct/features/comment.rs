@@ -11,7 +11,17 @@ use crate::{
 };
 
 #[derive(Debug, FromMeta)]
-pub struct CommentFeature {}
+pub struct CommentFeature {
+    pub position: CommentPosition,
+}
+
+/// Where comments are attached relative to the symbol, `position = "leading"`, `"trailing"` or `"both"`
+#[derive(Debug, FromMeta)]
+pub enum CommentPosition {
+    Leading,
+    Trailing,
+    Both,
+}
 
 pub struct CommentBuilder<'a> {
     pub input_name: &'a Ident,
@@ -48,8 +58,26 @@ impl<'a> FeaturesCodeGen for CommentBuilder<'a> {
                         }
                     }
                 },
-                Feature::CodeGen(_) => {
-                    panic!("Comment does not provide code generation");
+                Feature::CodeGen(comment) => {
+                    let position = match comment.position {
+                        CommentPosition::Leading => quote! { Leading },
+                        CommentPosition::Trailing => quote! { Trailing },
+                        CommentPosition::Both => quote! { Both },
+                    };
+                    let comment_position = &PATHS.comment_position;
+                    let comment_position_sig = &PATHS.is_comment.comment_position.sig;
+
+                    quote! {
+                        impl #is_comment for #input_name {
+                            fn is_comment(&self) -> bool {
+                                true
+                            }
+
+                            #comment_position_sig {
+                                #comment_position::#position
+                            }
+                        }
+                    }
                 }
             },
         }
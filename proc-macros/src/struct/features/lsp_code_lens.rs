@@ -52,9 +52,9 @@ impl<'a> FeaturesCodeGen for CodeLensBuilder<'a> {
                 Feature::CodeGen(code_lens) => {
                     let call = path_to_dot_tokens(&code_lens.code_lens_fn, None);
 
-                    let field_names = &self.fields.field_names.get_field_names();
-                    let field_vec_names = &self.fields.field_vec_names.get_field_names();
-                    let field_option_names = &self.fields.field_option_names.get_field_names();
+                    let field_names = &self.fields.field_names.get_field_members();
+                    let field_vec_names = &self.fields.field_vec_names.get_field_members();
+                    let field_option_names = &self.fields.field_option_names.get_field_members();
 
                     quote! {
                         impl #code_lens_path for #input_name {
@@ -1,6 +1,6 @@
 extern crate proc_macro;
 
-use darling::FromMeta;
+use darling::{util::PathList, FromMeta};
 use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{Ident, Path};
@@ -23,6 +23,21 @@ pub struct CompletionItemFeature {
 pub struct CompletionItem {
     pub label: Path,
     pub kind: Path,
+    pub snippet: Option<Snippet>,
+}
+
+/// Snippet inserted instead of the label, with one placeholder per child symbol.
+///
+/// `snippet(placeholders(self::parameters))` on a function named `foo` produces `foo(${1:a}, ${2:b})`.
+///
+/// The placeholder of a child is the label of its first completion item, or its text when it has none.
+/// A path to a field of the child, such as `self::parameters::name`, takes the text of this field instead.
+#[derive(Debug, FromMeta)]
+pub struct Snippet {
+    pub placeholders: PathList,
+    pub open: Option<String>,
+    pub close: Option<String>,
+    pub separator: Option<String>,
 }
 
 pub struct CompletionItemsBuilder<'a> {
@@ -43,6 +58,67 @@ impl<'a> CompletionItemsBuilder<'a> {
             impl #completion_items_path for #input_name { }
         }
     }
+
+    /// Generate the code pushing the name of each placeholder to `placeholders`.
+    ///
+    /// Vec and Option fields are iterated, other fields are pushed as a single placeholder.
+    fn placeholders(&self, snippet: &Snippet) -> Vec<TokenStream> {
+        let completion_items_path = &PATHS.lsp_completion_items.path;
+
+        snippet
+            .placeholders
+            .iter()
+            .map(|path| {
+                // `self::field` is followed by the path to a field of the child, if any
+                let field_len = match path.segments.first() {
+                    Some(segment) if segment.ident == "self" => 2.min(path.segments.len()),
+                    _ => 1,
+                };
+                let mut field_path = path.clone();
+                field_path.segments = path.segments.iter().take(field_len).cloned().collect();
+                let child_path = path
+                    .segments
+                    .iter()
+                    .skip(field_len)
+                    .map(|segment| &segment.ident)
+                    .collect::<Vec<_>>();
+
+                let field = path_to_dot_tokens(&field_path, None);
+                let name = &field_path.segments.last().unwrap().ident;
+                let push = match child_path.is_empty() {
+                    true => quote! {
+                        let mut items = vec![];
+                        #completion_items_path::build_completion_items(&*item.read(), &mut items, doc);
+                        match items.into_iter().next() {
+                            Some(child) => placeholders.push(child.label),
+                            None => {
+                                if let Some(text) = item.read().get_text(doc.document.text.as_bytes()) {
+                                    placeholders.push(text.to_string());
+                                }
+                            }
+                        }
+                    },
+                    false => quote! {
+                        if let Some(text) = item.read()#(.#child_path)*.read().get_text(doc.document.text.as_bytes()) {
+                            placeholders.push(text.to_string());
+                        }
+                    },
+                };
+                if self.fields.field_vec_names.iter().any(|f| &f.ident == name) {
+                    quote! { for item in #field.iter() { #push } }
+                } else if self
+                    .fields
+                    .field_option_names
+                    .iter()
+                    .any(|f| &f.ident == name)
+                {
+                    quote! { if let Some(item) = #field.as_ref() { #push } }
+                } else {
+                    quote! { { let item = &#field; #push } }
+                }
+            })
+            .collect()
+    }
 }
 
 impl<'a> FeaturesCodeGen for CompletionItemsBuilder<'a> {
@@ -60,17 +136,52 @@ impl<'a> FeaturesCodeGen for CompletionItemsBuilder<'a> {
                     let kind = &item.kind;
                     let label = path_to_dot_tokens(&item.label, None);
 
+                    let snippet = match &item.snippet {
+                        None => quote! {
+                            let insert_text = None;
+                            let insert_text_format = None;
+                        },
+                        Some(snippet) => {
+                            let placeholders = self.placeholders(snippet);
+                            let open = snippet.open.as_deref().unwrap_or("(");
+                            let close = snippet.close.as_deref().unwrap_or(")");
+                            let separator = snippet.separator.as_deref().unwrap_or(", ");
+                            quote! {
+                                let mut placeholders: Vec<String> = vec![];
+                                #(#placeholders)*
+                                let placeholders = placeholders
+                                    .iter()
+                                    .enumerate()
+                                    .map(|(index, text)| {
+                                        let text = text
+                                            .replace('\\', "\\\\")
+                                            .replace('$', "\\$")
+                                            .replace('}', "\\}");
+                                        format!("${{{}:{}}}", index + 1, text)
+                                    })
+                                    .collect::<Vec<_>>();
+                                let insert_text = Some(format!("{}{}{}{}", label, #open, placeholders.join(#separator), #close));
+                                let insert_text_format = Some(auto_lsp::lsp_types::InsertTextFormat::SNIPPET);
+                            }
+                        }
+                    };
+
                     quote! {
                         impl #completion_items_path for #input_name {
                             #sig {
                                 let read = #label.read();
 
-                                acc.push(auto_lsp::lsp_types::CompletionItem {
-                                    label: read.get_text(doc.document.text.as_bytes()).to_string(),
-                                    kind: Some(#kind),
-                                    detail: None,
-                                    ..Default::default()
-                                });
+                                if let Some(label) = read.get_text(doc.document.text.as_bytes()) {
+                                    #snippet
+                                    acc.push(auto_lsp::lsp_types::CompletionItem {
+                                        label: label.to_string(),
+                                        kind: Some(#kind),
+                                        detail: None,
+                                        insert_text,
+                                        insert_text_format,
+                                        ..Default::default()
+                                    });
+                                }
                             }
                         }
                     }
@@ -94,7 +205,7 @@ impl<'a> FeaturesCodeGen for CompletionItemsBuilder<'a> {
                             #sig {
                                 if let Some(reference) = &self.get_target() {
                                     if let Some(reference) = reference.to_dyn() {
-                                        reference.read().build_completion_items(doc, acc)
+                                        reference.read().build_completion_items(acc, doc)
                                     }
                                 }
                             }
@@ -10,8 +10,13 @@ use crate::field_builder::Fields;
 use crate::{ReferenceFeature, ReferenceFeatures, SymbolFeatures, PATHS};
 
 use crate::Feature;
+/// Markdown hover generated from the symbol's comment and declaration.
+///
+/// `language` is the language of the code block showing the first line of the declaration.
 #[derive(Debug, FromMeta)]
-pub struct HoverFeature {}
+pub struct HoverFeature {
+    pub language: Option<String>,
+}
 
 pub struct HoverBuilder<'a> {
     pub input_name: &'a Ident,
@@ -31,22 +36,30 @@ impl<'a> HoverBuilder<'a> {
             impl #hover_info_path for #input_name { }
         }
     }
-}
 
-impl<'a> FeaturesCodeGen for HoverBuilder<'a> {
-    fn code_gen(&self, params: &SymbolFeatures) -> impl quote::ToTokens {
-        match &params.lsp_hover_info {
-            None => self.default_impl(),
-            Some(params) => match params {
-                Feature::User => quote! {},
-                Feature::CodeGen(_) => {
-                    panic!("Hover Info does not provide code generation, instead implement the trait GetGoToDefinition manually");
+    /// Forward the hover request to the target of the reference.
+    pub fn reference_impl(&self) -> TokenStream {
+        let input_name = &self.input_name;
+        let hover_info_path = &PATHS.lsp_hover_info.path;
+        let sig = &PATHS.lsp_hover_info.get_hover.sig;
+
+        quote! {
+            impl #hover_info_path for #input_name {
+                #sig {
+                    if let Some(reference) = &self.get_target() {
+                        if let Some(reference) = reference.to_dyn() {
+                            return reference.read().get_hover(doc)
+                        }
+                    }
+                    None
                 }
-            },
+            }
         }
     }
+}
 
-    fn code_gen_reference(&self, params: &ReferenceFeatures) -> impl quote::ToTokens {
+impl<'a> FeaturesCodeGen for HoverBuilder<'a> {
+    fn code_gen(&self, params: &SymbolFeatures) -> impl quote::ToTokens {
         let input_name = &self.input_name;
         let hover_info_path = &PATHS.lsp_hover_info.path;
         let sig = &PATHS.lsp_hover_info.get_hover.sig;
@@ -54,21 +67,47 @@ impl<'a> FeaturesCodeGen for HoverBuilder<'a> {
         match &params.lsp_hover_info {
             None => self.default_impl(),
             Some(params) => match params {
-                ReferenceFeature::Disable => self.default_impl(),
-                ReferenceFeature::Reference => {
+                Feature::User => quote! {},
+                Feature::CodeGen(hover) => {
+                    let language = hover.language.as_deref().unwrap_or_default();
+
                     quote! {
                         impl #hover_info_path for #input_name {
                             #sig {
-                                if let Some(reference) = &self.get_target() {
-                                    if let Some(reference) = reference.to_dyn() {
-                                        return reference.read().get_hover(doc)
+                                let source_code = doc.document.text.as_bytes();
+                                let declaration = self.get_text(source_code)?.lines().next()?.trim_end();
+
+                                let mut value = format!("```{}\n{}\n```", #language, declaration);
+                                if let Some(comment) = self.get_comment(source_code) {
+                                    let comment = doc.comment_style.strip(comment);
+                                    if !comment.is_empty() {
+                                        value.push_str("\n---\n");
+                                        value.push_str(&comment);
                                     }
                                 }
-                                None
+
+                                Some(auto_lsp::lsp_types::Hover {
+                                    contents: auto_lsp::lsp_types::HoverContents::Markup(auto_lsp::lsp_types::MarkupContent {
+                                        kind: auto_lsp::lsp_types::MarkupKind::Markdown,
+                                        value,
+                                    }),
+                                    range: Some(self.get_lsp_range(doc)),
+                                })
                             }
                         }
                     }
                 }
+            },
+        }
+    }
+
+    fn code_gen_reference(&self, params: &ReferenceFeatures) -> impl quote::ToTokens {
+        // References use the hover information of their target unless told otherwise
+        match &params.lsp_hover_info {
+            None => self.reference_impl(),
+            Some(params) => match params {
+                ReferenceFeature::Disable => self.default_impl(),
+                ReferenceFeature::Reference => self.reference_impl(),
                 ReferenceFeature::User => quote! {},
             },
         }
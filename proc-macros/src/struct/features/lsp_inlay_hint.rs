@@ -49,9 +49,9 @@ impl<'a> FeaturesCodeGen for InlayHintsBuilder<'a> {
             Some(params) => match params {
                 Feature::User => quote! {},
                 Feature::CodeGen(opt) => {
-                    let field_names = &self.fields.field_names.get_field_names();
-                    let field_vec_names = &self.fields.field_vec_names.get_field_names();
-                    let field_option_names = &self.fields.field_option_names.get_field_names();
+                    let field_names = &self.fields.field_names.get_field_members();
+                    let field_vec_names = &self.fields.field_vec_names.get_field_members();
+                    let field_option_names = &self.fields.field_option_names.get_field_members();
                     let queryable = &PATHS.queryable.path;
 
                     if opt.query.is_some() {
@@ -55,9 +55,9 @@ impl<'a> FeaturesCodeGen for SemanticTokensBuilder<'a> {
                     let token_index = &semantic.token_type_index;
                     let range = path_to_dot_tokens(&semantic.range, Some(quote! { read() }));
 
-                    let field_names = &self.fields.field_names.get_field_names();
-                    let field_vec_names = &self.fields.field_vec_names.get_field_names();
-                    let field_option_names = &self.fields.field_option_names.get_field_names();
+                    let field_names = &self.fields.field_names.get_field_members();
+                    let field_vec_names = &self.fields.field_vec_names.get_field_members();
+                    let field_option_names = &self.fields.field_option_names.get_field_members();
 
                     let modifiers = match &semantic.modifiers_fn {
                         None => quote! { 0 },
@@ -1,26 +1,65 @@
 use crate::{
-    utilities::{get_raw_type_name, is_option, is_vec},
+    utilities::{get_builder_path, get_inner_type, get_symbol_path, is_option, is_vec},
     StructHelpers,
 };
 use darling::{ast, util};
 use proc_macro2::{Ident, TokenStream};
 use quote::quote;
 use quote::{format_ident, ToTokens};
-use syn::{Attribute, Path};
+use syn::{Member, Path};
 
+/// A field of the struct.
+///
+/// - `ident`: The name of the field in the builder, `field_0`, `field_1`, ... for tuple structs.
+/// - `member`: The name of the field in the struct, `0`, `1`, ... for tuple structs.
+/// - `index`: The position of the field in the struct.
 pub struct FieldInfo {
     pub ident: Ident,
-    pub attr: Vec<Attribute>,
+    pub member: Member,
+    pub index: usize,
+    pub attr: FieldAttributes,
+}
+
+/// Attributes of a field, given with `#[ast(...)]`.
+///
+/// - `capture`: The capture of the field, when it differs from the query name of its type.
+/// - `min` and `max`: The number of symbols accepted by a `Vec` field.
+/// - `default`: An absent value field takes its [`Default`] value, an absent symbol field takes a placeholder.
+/// - `field`: The tree-sitter field of the node, used to derive the core query and to route symbol fields.
+#[derive(Default)]
+pub struct FieldAttributes {
+    pub capture: Option<String>,
+    pub min: Option<usize>,
+    pub max: Option<usize>,
+    pub default: bool,
+    pub field: Option<String>,
+}
+
+impl FieldAttributes {
+    fn new(field: &StructHelpers) -> Self {
+        Self {
+            capture: field.capture.clone(),
+            min: field.min,
+            max: field.max,
+            default: field.default,
+            field: field.field.clone(),
+        }
+    }
 }
 
 pub trait FieldInfoExtract {
     fn get_field_names<'a>(&'a self) -> Vec<&'a Ident>;
+    fn get_field_members(&self) -> Vec<&Member>;
 }
 
 impl FieldInfoExtract for Vec<FieldInfo> {
     fn get_field_names<'a>(&'a self) -> Vec<&'a Ident> {
         self.iter().map(|field| &field.ident).collect()
     }
+
+    fn get_field_members(&self) -> Vec<&Member> {
+        self.iter().map(|field| &field.member).collect()
+    }
 }
 
 /// A container for information about struct fields.
@@ -30,7 +69,7 @@ impl FieldInfoExtract for Vec<FieldInfo> {
 /// - `field_vec_names`: The names of the fields in the struct that are vectors.
 /// - `field_option_names`: The names of the fields in the struct that are options.
 ///
-/// - `field_types_names`: The types of the fields in the struct.
+/// - `field_types_names`: The paths of the types of the fields in the struct, without `Vec`, `Option` or `Box`.
 /// - `field_vec_types_names`: The types of the fields in the struct that are vectors.
 /// - `field_option_types_names`: The types of the fields in the struct that are options.
 ///
@@ -38,6 +77,12 @@ impl FieldInfoExtract for Vec<FieldInfo> {
 /// - `field_vec_builder_names`: The builder names derived from the field types that are vectors.
 /// - `field_option_builder_names`: The builder names derived from the field types that are options.
 ///
+/// - `field_values`: The fields parsed from the text of a capture, see [`ValueField`].
+///
+/// - `tuple`: Whether the struct is a tuple struct.
+///
+/// Value fields are not symbols, hence they are not part of the other lists.
+///
 /// # Example
 ///
 /// ```ignore
@@ -64,21 +109,41 @@ impl FieldInfoExtract for Vec<FieldInfo> {
 /// }
 /// ```
 ///
+#[derive(Default)]
 pub struct Fields {
     // [Name]: Type
     pub field_names: Vec<FieldInfo>,
     pub field_vec_names: Vec<FieldInfo>,
     pub field_option_names: Vec<FieldInfo>,
 
-    // Field: [Type] -> Ident
-    pub field_types_names: Vec<proc_macro2::Ident>,
-    pub field_vec_types_names: Vec<proc_macro2::Ident>,
-    pub field_option_types_names: Vec<proc_macro2::Ident>,
+    // Field: [Type] -> Path
+    pub field_types_names: Vec<Path>,
+    pub field_vec_types_names: Vec<Path>,
+    pub field_option_types_names: Vec<Path>,
 
     // Field(Builder): Type
-    pub field_builder_names: Vec<proc_macro2::Ident>,
-    pub field_vec_builder_names: Vec<proc_macro2::Ident>,
-    pub field_option_builder_names: Vec<proc_macro2::Ident>,
+    pub field_builder_names: Vec<Path>,
+    pub field_vec_builder_names: Vec<Path>,
+    pub field_option_builder_names: Vec<Path>,
+
+    // #[ast(text = "capture")] [Name]: Type
+    pub field_values: Vec<ValueField>,
+
+    pub tuple: bool,
+}
+
+/// A field annotated with `#[ast(text = "capture")]`.
+///
+/// The text of the capture is parsed with [`std::str::FromStr`] when the symbol is built.
+pub struct ValueField {
+    pub ident: Ident,
+    pub member: Member,
+    pub index: usize,
+    /// Type the text is parsed into, without the `Vec` or `Option` wrapper.
+    pub ty: syn::Type,
+    pub capture: String,
+    pub kind: FieldType,
+    pub attr: FieldAttributes,
 }
 
 impl Fields {
@@ -91,8 +156,28 @@ impl Fields {
         ret
     }
 
+    /// Returns a list of field members in the struct, regardless of type.
+    pub fn get_field_members(&self) -> Vec<&Member> {
+        let mut ret = vec![];
+        ret.extend(self.field_names.get_field_members());
+        ret.extend(self.field_vec_names.get_field_members());
+        ret.extend(self.field_option_names.get_field_members());
+        ret
+    }
+
+    /// Returns the member of the [`SymbolData`](auto_lsp_core::ast::SymbolData) of the struct,
+    /// the last field of tuple structs.
+    pub fn get_data_member(&self) -> Member {
+        match self.tuple {
+            true => {
+                Member::Unnamed((self.get_field_names().len() + self.field_values.len()).into())
+            }
+            false => Member::Named(format_ident!("_data")),
+        }
+    }
+
     // Returns a list of field types in the struct, regardless of type.
-    pub fn get_field_types<'a>(&'a self) -> Vec<&'a Ident> {
+    pub fn get_field_types(&self) -> Vec<&Path> {
         let mut ret = vec![];
         ret.extend(&self.field_types_names);
         ret.extend(&self.field_vec_types_names);
@@ -100,8 +185,41 @@ impl Fields {
         ret
     }
 
+    /// Returns the symbol fields and their types, in the order they are declared.
+    pub fn get_ordered_fields(&self) -> Vec<(&FieldInfo, &Path)> {
+        let mut ret = self
+            .field_names
+            .iter()
+            .zip(&self.field_types_names)
+            .chain(self.field_vec_names.iter().zip(&self.field_vec_types_names))
+            .chain(
+                self.field_option_names
+                    .iter()
+                    .zip(&self.field_option_types_names),
+            )
+            .collect::<Vec<_>>();
+        ret.sort_by_key(|(field, _)| field.index);
+        ret
+    }
+
+    /// Returns the members of all fields, symbols and values, in the order they are declared.
+    pub fn get_ordered_members(&self) -> Vec<&Member> {
+        let mut ret = self
+            .get_ordered_fields()
+            .into_iter()
+            .map(|(field, _)| (field.index, &field.member))
+            .chain(
+                self.field_values
+                    .iter()
+                    .map(|field| (field.index, &field.member)),
+            )
+            .collect::<Vec<_>>();
+        ret.sort_by_key(|(index, _)| *index);
+        ret.into_iter().map(|(_, member)| member).collect()
+    }
+
     // Returns a list of field builder names in the struct, regardless of type.
-    pub fn get_field_builder_names<'a>(&'a self) -> Vec<&'a Ident> {
+    pub fn get_field_builder_names(&self) -> Vec<&Path> {
         let mut ret = vec![];
         ret.extend(&self.field_builder_names);
         ret.extend(&self.field_vec_builder_names);
@@ -113,7 +231,10 @@ impl Fields {
 /// Extracts field information from a syn::Data struct definition.
 ///
 /// See the `Fields` struct for more information.
-pub fn extract_fields(data: &ast::Data<util::Ignored, StructHelpers>) -> Fields {
+///
+/// Returns an error if the type or the attributes of a field are not supported.
+pub fn extract_fields(data: &ast::Data<util::Ignored, StructHelpers>) -> darling::Result<Fields> {
+    let mut errors = darling::Error::accumulator();
     let mut ret_fields = Fields {
         field_names: vec![],
         field_vec_names: vec![],
@@ -126,50 +247,98 @@ pub fn extract_fields(data: &ast::Data<util::Ignored, StructHelpers>) -> Fields
         field_builder_names: vec![],
         field_vec_builder_names: vec![],
         field_option_builder_names: vec![],
+
+        field_values: vec![],
+
+        tuple: false,
     };
 
-    data.as_ref()
-        .take_struct()
-        .unwrap()
-        .fields
-        .iter()
-        .for_each(|field| {
-            if is_vec(&field.ty) {
-                ret_fields.field_vec_names.push(FieldInfo {
-                    ident: field.ident.as_ref().unwrap().clone(),
-                    attr: vec![],
-                });
-                ret_fields
-                    .field_vec_types_names
-                    .push(format_ident!("{}", get_raw_type_name(&field.ty)));
-                ret_fields
-                    .field_vec_builder_names
-                    .push(format_ident!("{}Builder", get_raw_type_name(&field.ty)));
+    let fields = data.as_ref().take_struct().unwrap();
+    ret_fields.tuple = fields.is_tuple();
+
+    fields.fields.iter().enumerate().for_each(|(index, field)| {
+        if let Err(e) = check_attributes(field) {
+            errors.push(e);
+            return;
+        }
+        let (ident, member) = match &field.ident {
+            Some(ident) => (ident.clone(), Member::Named(ident.clone())),
+            None => (
+                format_ident!("field_{}", index),
+                Member::Unnamed(index.into()),
+            ),
+        };
+        if let Some(capture) = &field.text {
+            let (kind, ty) = if is_vec(&field.ty) {
+                (FieldType::Vec, get_inner_type(&field.ty, 0).unwrap())
             } else if is_option(&field.ty) {
-                ret_fields.field_option_names.push(FieldInfo {
-                    ident: field.ident.as_ref().unwrap().clone(),
-                    attr: vec![],
-                });
-                ret_fields
-                    .field_option_types_names
-                    .push(format_ident!("{}", get_raw_type_name(&field.ty)));
-                ret_fields
-                    .field_option_builder_names
-                    .push(format_ident!("{}Builder", get_raw_type_name(&field.ty)));
+                (FieldType::Option, get_inner_type(&field.ty, 0).unwrap())
             } else {
-                ret_fields.field_names.push(FieldInfo {
-                    ident: field.ident.as_ref().unwrap().clone(),
-                    attr: vec![],
-                });
-                ret_fields
-                    .field_types_names
-                    .push(format_ident!("{}", get_raw_type_name(&field.ty)));
-                ret_fields
-                    .field_builder_names
-                    .push(format_ident!("{}Builder", get_raw_type_name(&field.ty)));
+                (FieldType::Normal, field.ty.clone())
+            };
+            ret_fields.field_values.push(ValueField {
+                ident,
+                member,
+                index,
+                ty,
+                capture: capture.clone(),
+                kind,
+                attr: FieldAttributes::new(field),
+            });
+            return;
+        }
+
+        let path = match get_symbol_path(&field.ty) {
+            Ok(path) => path,
+            Err(e) => {
+                errors.push(e.into());
+                return;
             }
-        });
-    ret_fields
+        };
+        let builder = get_builder_path(&path);
+        let info = FieldInfo {
+            ident,
+            member,
+            index,
+            attr: FieldAttributes::new(field),
+        };
+
+        if is_vec(&field.ty) {
+            ret_fields.field_vec_names.push(info);
+            ret_fields.field_vec_types_names.push(path);
+            ret_fields.field_vec_builder_names.push(builder);
+        } else if is_option(&field.ty) {
+            ret_fields.field_option_names.push(info);
+            ret_fields.field_option_types_names.push(path);
+            ret_fields.field_option_builder_names.push(builder);
+        } else {
+            ret_fields.field_names.push(info);
+            ret_fields.field_types_names.push(path);
+            ret_fields.field_builder_names.push(builder);
+        }
+    });
+    errors.finish_with(ret_fields)
+}
+
+/// Checks that the attributes of a field are valid for its type.
+fn check_attributes(field: &StructHelpers) -> darling::Result<()> {
+    let error = |message: &str| Err(darling::Error::custom(message).with_span(&field.ty));
+
+    if field.text.is_some() && field.capture.is_some() {
+        return error("`capture` can't be used on value fields, the capture is given by `text`");
+    }
+    if (field.min.is_some() || field.max.is_some()) && !is_vec(&field.ty) {
+        return error("`min` and `max` are only supported on `Vec` fields");
+    }
+    if let (Some(min), Some(max)) = (field.min, field.max) {
+        if min > max {
+            return error("`min` must be lower than or equal to `max`");
+        }
+    }
+    if field.default && (is_vec(&field.ty) || is_option(&field.ty)) {
+        return error("`default` can't be used on `Vec` or `Option` fields");
+    }
+    Ok(())
 }
 
 /// Builder for struct fields.
@@ -201,13 +370,13 @@ impl FieldBuilder {
     ///
     /// The closure takes 5 arguments:
     /// - `FieldType`: The type of the field.
-    /// - `Vec<Attribute>`: The attributes of the field.
+    /// - `&FieldInfo`: The field, with its member and attributes.
     /// - `&Ident`: The name of the field.
-    /// - `&Ident`: The type of the field.
-    /// - `&Ident`: The builder name of the field.
+    /// - `&Path`: The type of the field.
+    /// - `&Path`: The builder name of the field.
     pub fn add_iter<F>(&mut self, fields: &Fields, f: F) -> &mut Self
     where
-        F: Fn(FieldType, &Vec<Attribute>, &Ident, &Ident, &Ident) -> TokenStream,
+        F: Fn(FieldType, &FieldInfo, &Ident, &Path, &Path) -> TokenStream,
     {
         let mut _fields: Vec<TokenStream> = vec![];
 
@@ -224,16 +393,27 @@ impl FieldBuilder {
         self
     }
 
+    /// Adds an **unstaged** TokenStream to the builder.
+    ///
+    /// This function takes a closure that will receive all value fields in `fields` and return a `TokenStream`.
+    pub fn add_value_iter<F>(&mut self, fields: &Fields, f: F) -> &mut Self
+    where
+        F: Fn(&ValueField) -> TokenStream,
+    {
+        self.unstaged.extend(fields.field_values.iter().map(f));
+        self
+    }
+
     /// Adds an **unstaged** TokenStream to the builder.
     ///
     /// This function takes a closure that will receive all fields in `fields` and return a `TokenStream`.
     ///
     /// The closure takes 5 arguments:
     /// - `FieldType`: The type of the field.
-    /// - `Vec<Attribute>`: The attributes of the field.
+    /// - `&FieldInfo`: The field, with its member and attributes.
     /// - `&Ident`: The name of the field.
-    /// - `&Ident`: The type of the field.
-    /// - `&Ident`: The builder name of the field.
+    /// - `&Path`: The type of the field.
+    /// - `&Path`: The builder name of the field.
     ///
     /// `before` and `after` are optional TokenStreams that will be added before and after the body, respectively.
     pub fn add_fn_iter<F>(
@@ -245,7 +425,7 @@ impl FieldBuilder {
         after: Option<TokenStream>,
     ) -> &mut Self
     where
-        F: Fn(FieldType, &Vec<Attribute>, &Ident, &Ident, &Ident) -> TokenStream,
+        F: Fn(FieldType, &FieldInfo, &Ident, &Path, &Path) -> TokenStream,
     {
         let mut _body: Vec<TokenStream> = vec![];
         if !fields.field_names.is_empty() {
@@ -333,7 +513,7 @@ impl FieldBuilder {
     /// Inner fn for applying a closure to all fields.
     fn apply<F>(&mut self, fields: &Fields, f: F) -> Vec<TokenStream>
     where
-        F: Fn(FieldType, &Vec<Attribute>, &Ident, &Ident, &Ident) -> TokenStream,
+        F: Fn(FieldType, &FieldInfo, &Ident, &Path, &Path) -> TokenStream,
     {
         fields
             .field_names
@@ -343,7 +523,7 @@ impl FieldBuilder {
             .map(|((field, field_type), field_builder)| {
                 f(
                     FieldType::Normal,
-                    &field.attr,
+                    field,
                     &field.ident,
                     &field_type,
                     &field_builder,
@@ -355,7 +535,7 @@ impl FieldBuilder {
     /// Inner fn for applying a closure to all fields that are options.
     fn apply_opt<F>(&mut self, fields: &Fields, f: F) -> Vec<TokenStream>
     where
-        F: Fn(FieldType, &Vec<Attribute>, &Ident, &Ident, &Ident) -> TokenStream,
+        F: Fn(FieldType, &FieldInfo, &Ident, &Path, &Path) -> TokenStream,
     {
         fields
             .field_option_names
@@ -365,7 +545,7 @@ impl FieldBuilder {
             .map(|((field, field_type), field_builder)| {
                 f(
                     FieldType::Option,
-                    &field.attr,
+                    field,
                     &field.ident,
                     &field_type,
                     &field_builder,
@@ -377,7 +557,7 @@ impl FieldBuilder {
     /// Inner fn for applying a closure to all fields that are vectors.
     fn apply_vec<F>(&mut self, fields: &Fields, f: F) -> Vec<TokenStream>
     where
-        F: Fn(FieldType, &Vec<Attribute>, &Ident, &Ident, &Ident) -> TokenStream,
+        F: Fn(FieldType, &FieldInfo, &Ident, &Path, &Path) -> TokenStream,
     {
         fields
             .field_vec_names
@@ -387,7 +567,7 @@ impl FieldBuilder {
             .map(|((field, field_type), field_builder)| {
                 f(
                     FieldType::Vec,
-                    &field.attr,
+                    field,
                     &field.ident,
                     &field_type,
                     &field_builder,
@@ -429,7 +609,7 @@ mod tests {
         let input: DeriveInput = syn::parse2(data).unwrap();
         let derive_input = StructInput::from_derive_input(&input).unwrap();
 
-        let fields = extract_fields(&derive_input.data);
+        let fields = extract_fields(&derive_input.data).unwrap();
         assert_eq!(fields.field_names.len(), 1);
         assert_eq!(fields.field_vec_names.len(), 1);
         assert_eq!(fields.field_option_names.len(), 1);
@@ -443,6 +623,120 @@ mod tests {
         assert_eq!(fields.field_option_builder_names.len(), 1);
     }
 
+    #[test]
+    fn test_extract_value_fields() {
+        let data = quote! {
+            struct MyStruct {
+                field1: Symbol1,
+                #[ast(text = "name")]
+                field2: String,
+                #[ast(text = "numbers")]
+                field3: Vec<i64>,
+                #[ast(text = "flag")]
+                field4: Option<bool>,
+            }
+        };
+
+        let input: DeriveInput = syn::parse2(data).unwrap();
+        let derive_input = StructInput::from_derive_input(&input).unwrap();
+
+        let fields = extract_fields(&derive_input.data).unwrap();
+        assert_eq!(fields.get_field_names().len(), 1);
+        assert_eq!(fields.field_values.len(), 3);
+
+        let values = &fields.field_values;
+        assert_eq!(values[0].capture, "name");
+        assert!(matches!(values[0].kind, FieldType::Normal));
+        assert_eq!(values[0].ty.to_token_stream().to_string(), "String");
+        assert!(matches!(values[1].kind, FieldType::Vec));
+        assert_eq!(values[1].ty.to_token_stream().to_string(), "i64");
+        assert!(matches!(values[2].kind, FieldType::Option));
+        assert_eq!(values[2].ty.to_token_stream().to_string(), "bool");
+    }
+
+    #[test]
+    fn test_extract_field_attributes() {
+        let data = quote! {
+            struct MyStruct {
+                #[ast(capture = "name")]
+                field1: Symbol1,
+                #[ast(min = 1, max = 2)]
+                field2: Vec<Symbol2>,
+                #[ast(text = "count", default)]
+                field3: u32,
+            }
+        };
+
+        let input: DeriveInput = syn::parse2(data).unwrap();
+        let derive_input = StructInput::from_derive_input(&input).unwrap();
+
+        let fields = extract_fields(&derive_input.data).unwrap();
+        assert_eq!(fields.field_names[0].attr.capture.as_deref(), Some("name"));
+        assert_eq!(fields.field_vec_names[0].attr.min, Some(1));
+        assert_eq!(fields.field_vec_names[0].attr.max, Some(2));
+        assert!(fields.field_values[0].attr.default);
+    }
+
+    #[test]
+    fn test_invalid_field_attributes() {
+        let invalid = [
+            quote! { #[ast(text = "name", capture = "name")] field: String },
+            quote! { #[ast(min = 1)] field: Symbol },
+            quote! { #[ast(min = 2, max = 1)] field: Vec<Symbol> },
+            quote! { #[ast(default)] field: Vec<Symbol> },
+            quote! { #[ast(text = "name", default)] field: Option<String> },
+        ];
+
+        for field in invalid {
+            let input: DeriveInput = syn::parse2(quote! { struct MyStruct { #field } }).unwrap();
+            let derive_input = StructInput::from_derive_input(&input).unwrap();
+            assert!(extract_fields(&derive_input.data).is_err());
+        }
+    }
+
+    #[test]
+    fn test_extract_tuple_fields() {
+        let data = quote! {
+            struct MyStruct(crate::ast::Symbol1, Vec<Box<Symbol2>>);
+        };
+
+        let input: DeriveInput = syn::parse2(data).unwrap();
+        let derive_input = StructInput::from_derive_input(&input).unwrap();
+
+        let fields = extract_fields(&derive_input.data).unwrap();
+        assert!(fields.tuple);
+        assert_eq!(fields.field_names[0].ident.to_string(), "field_0");
+        assert_eq!(fields.field_names[0].member, parse_quote! { 0 });
+        assert_eq!(fields.field_vec_names[0].member, parse_quote! { 1 });
+        assert_eq!(fields.get_data_member(), parse_quote! { 2 });
+        assert_eq!(
+            fields.field_builder_names[0].to_token_stream().to_string(),
+            "crate :: ast :: Symbol1Builder"
+        );
+        assert_eq!(
+            fields.field_vec_types_names[0]
+                .to_token_stream()
+                .to_string(),
+            "Symbol2"
+        );
+    }
+
+    #[test]
+    fn test_unsupported_field_types() {
+        let data = quote! {
+            struct MyStruct {
+                field1: Symbol1<u8>,
+                field2: &'static Symbol2,
+            }
+        };
+
+        let input: DeriveInput = syn::parse2(data).unwrap();
+        let derive_input = StructInput::from_derive_input(&input).unwrap();
+
+        let errors = extract_fields(&derive_input.data).err().unwrap();
+        assert_eq!(errors.len(), 2);
+    }
+
     #[test]
     fn test_stage_fields() {
         let data = quote! {
@@ -456,7 +750,7 @@ mod tests {
         let input: DeriveInput = syn::parse2(data).unwrap();
         let derive_input = StructInput::from_derive_input(&input).unwrap();
 
-        let fields = extract_fields(&derive_input.data);
+        let fields = extract_fields(&derive_input.data).unwrap();
 
         // Transform fields into a Rc<RefCell<**field**>> for testing
         let mut builder = FieldBuilder::default();
@@ -494,7 +788,7 @@ mod tests {
         let input: DeriveInput = syn::parse2(data).unwrap();
         let derive_input = StructInput::from_derive_input(&input).unwrap();
 
-        let fields = extract_fields(&derive_input.data);
+        let fields = extract_fields(&derive_input.data).unwrap();
 
         let mut builder = FieldBuilder::default();
         builder.add_iter(&fields, |_, _, name, _type, _| {
@@ -537,7 +831,7 @@ mod tests {
         let input: DeriveInput = syn::parse2(data).unwrap();
         let derive_input = StructInput::from_derive_input(&input).unwrap();
 
-        let fields = extract_fields(&derive_input.data);
+        let fields = extract_fields(&derive_input.data).unwrap();
 
         let mut builder = FieldBuilder::default();
         builder.add_iter(&fields, |_, _, name, _type, _| {
@@ -1,26 +1,27 @@
 #![allow(unused)]
-use super::{feature_builder::Features, field_builder::{FieldBuilder, FieldType, Fields}};
-use crate::{
-    ReferenceOrSymbolFeatures, StructHelpers,
-    PATHS,
+use super::{
+    feature_builder::Features,
+    field_builder::{FieldAttributes, FieldBuilder, FieldType, Fields, ValueField},
 };
+use crate::{ReferenceOrSymbolFeatures, StructHelpers, PATHS};
 use darling::{ast, util};
 use proc_macro2::{Ident, TokenStream};
 use quote::{quote, ToTokens};
-use syn::{Attribute, Path};
+use syn::{parse_quote, Attribute, Member, Path};
 
 /// Builder for generating the AST symbol from a struct.
-/// 
+///
 /// This is the core builder called by the `#[seq]` macro.
-/// 
+///
 /// It generates:
-///     - The implementation of all capabilitties and `AstSymbol` traits. 
+///     - The implementation of all capabilitties and `AstSymbol` traits.
 ///     - The builder struct (named `input_builder_name`) that is used to create the AST symbol.
 pub struct StructBuilder<'a> {
     // Input data
     pub input_attr: &'a Vec<Attribute>,
     pub input_name: &'a Ident,
     pub query_name: &'a str,
+    pub node_kind: Option<&'a str>,
     pub input_builder_name: &'a Ident,
     pub fields: &'a Fields,
     // Features
@@ -28,6 +29,7 @@ pub struct StructBuilder<'a> {
 }
 
 impl<'a> StructBuilder<'a> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         params: &'a ReferenceOrSymbolFeatures<'a>,
         helpers: &'a ast::Data<util::Ignored, StructHelpers>,
@@ -35,12 +37,14 @@ impl<'a> StructBuilder<'a> {
         input_name: &'a Ident,
         input_buider_name: &'a Ident,
         query_name: &'a str,
+        node_kind: Option<&'a str>,
         fields: &'a Fields,
     ) -> Self {
         Self {
             input_name,
             input_attr,
             query_name,
+            node_kind,
             input_builder_name: input_buider_name,
             fields,
             features: Features::new(&params, &helpers, &input_name, &fields),
@@ -54,7 +58,7 @@ impl<'a> ToTokens for StructBuilder<'a> {
 
         let mut builder = FieldBuilder::default();
 
-        /// Create the struct 
+        /// Create the struct
         self.struct_input(&mut builder);
 
         // Implement the AstSymbol trait
@@ -66,6 +70,11 @@ impl<'a> ToTokens for StructBuilder<'a> {
         self.impl_dynamic_swap(&mut builder);
         self.impl_edit_range(&mut builder);
         self.impl_collect_references(&mut builder);
+        self.impl_collect_symbols(&mut builder);
+        self.impl_collect_children(&mut builder);
+        self.impl_walk(&mut builder);
+        self.impl_debug(&mut builder);
+        self.impl_serialize_fields(&mut builder);
 
         // Implement other features
         builder.add(self.features.to_token_stream());
@@ -90,14 +99,21 @@ impl<'a> ToTokens for StructBuilder<'a> {
         });
         self.fn_new(&mut builder);
         self.fn_add(&mut builder);
+        self.fn_add_value(&mut builder);
         builder.stage_trait(&self.input_builder_name, &PATHS.symbol_builder_trait.path);
 
         // Implement `TryFromBuilder`
         self.impl_try_from(&mut builder);
 
+        // Implement `Placeholder`
+        self.impl_placeholder(&mut builder);
+
         // Implement `Queryable`
         self.impl_queryable(&mut builder);
 
+        // Implement `DeriveQuery`
+        self.impl_derive_query(&mut builder);
+
         tokens.extend(builder.to_token_stream());
     }
 }
@@ -107,6 +123,10 @@ impl<'a> StructBuilder<'a> {
         let symbol = &PATHS.symbol;
         let symbol_data = &PATHS.symbol_data;
 
+        if self.fields.tuple {
+            return self.tuple_struct_input(builder);
+        }
+
         builder
             .add(quote! { _data: #symbol_data })
             .add_iter(&self.fields, |ty, _, name, field_type, _| match ty {
@@ -120,16 +140,77 @@ impl<'a> StructBuilder<'a> {
                     pub #name: Option<#symbol<#field_type>>
                 },
             })
+            .add_value_iter(self.fields, |field| {
+                let name = &field.ident;
+                let ty = &field.ty;
+                match field.kind {
+                    FieldType::Normal => quote! { pub #name: #ty },
+                    FieldType::Vec => quote! { pub #name: Vec<#ty> },
+                    FieldType::Option => quote! { pub #name: Option<#ty> },
+                }
+            })
             .stage_struct(&self.input_name);
     }
 
+    /// Tuple structs keep the order of their fields, the symbol data being the last one.
+    fn tuple_struct_input(&self, builder: &mut FieldBuilder) {
+        let symbol = &PATHS.symbol;
+        let symbol_data = &PATHS.symbol_data;
+        let input_name = self.input_name;
+        let fields = self.fields;
+
+        let symbols = fields
+            .field_names
+            .iter()
+            .zip(&fields.field_types_names)
+            .map(|(field, ty)| (&field.member, quote! { pub #symbol<#ty> }))
+            .chain(
+                fields
+                    .field_vec_names
+                    .iter()
+                    .zip(&fields.field_vec_types_names)
+                    .map(|(field, ty)| (&field.member, quote! { pub Vec<#symbol<#ty>> })),
+            )
+            .chain(
+                fields
+                    .field_option_names
+                    .iter()
+                    .zip(&fields.field_option_types_names)
+                    .map(|(field, ty)| (&field.member, quote! { pub Option<#symbol<#ty>> })),
+            );
+        let values = fields.field_values.iter().map(|field| {
+            let ty = &field.ty;
+            let ty = match field.kind {
+                FieldType::Normal => quote! { pub #ty },
+                FieldType::Vec => quote! { pub Vec<#ty> },
+                FieldType::Option => quote! { pub Option<#ty> },
+            };
+            (&field.member, ty)
+        });
+
+        let mut types = symbols.chain(values).collect::<Vec<_>>();
+        types.sort_by_key(|(member, _)| match member {
+            Member::Unnamed(index) => index.index,
+            Member::Named(_) => unreachable!("Tuple structs only have unnamed fields"),
+        });
+        let types = types.into_iter().map(|(_, ty)| ty);
+
+        builder
+            .add(quote! {
+                #[derive(Clone)]
+                pub struct #input_name(#(#types,)* #symbol_data);
+            })
+            .stage();
+    }
+
     fn impl_ast_symbol(&self, builder: &mut FieldBuilder) {
         let get_data = &PATHS.symbol_trait.get_data.sig;
         let get_mut_data = &PATHS.symbol_trait.get_mut_data.sig;
+        let data = self.fields.get_data_member();
 
         builder
-            .add(quote! { #get_data { &self._data } })
-            .add(quote! { #get_mut_data { &mut self._data } })
+            .add(quote! { #get_data { &self.#data } })
+            .add(quote! { #get_mut_data { &mut self.#data } })
             .stage_trait(&self.input_name, &PATHS.symbol_trait.path);
     }
 
@@ -145,7 +226,8 @@ impl<'a> StructBuilder<'a> {
                         return None;
                     }
                 }),
-                |_, _, name, _, _| {
+                |_, field, _, _, _| {
+                    let name = &field.member;
                     quote! {
                         if let Some(symbol) = self.#name.find_at_offset(offset) {
                            return Some(symbol);
@@ -163,7 +245,8 @@ impl<'a> StructBuilder<'a> {
                 &self.fields,
                 &PATHS.parent.inject_parent.sig,
                 None,
-                |_, _, name, _, _| {
+                |_, field, _, _, _| {
+                    let name = &field.member;
                     quote! {
                         self.#name.inject_parent(parent.clone());
                     }
@@ -185,54 +268,201 @@ impl<'a> StructBuilder<'a> {
             .add(quote! { const QUERY_NAMES: &'static [&'static str] = &[#query_name]; })
             .stage_trait(&self.input_builder_name, queryable);
 
-        let names = self
-            .fields
-            .get_field_names()
-            .iter()
-            .map(|name| quote! { stringify!(#name) })
-            .collect::<Vec<_>>();
-
         #[cfg(feature = "assertions")]
         {
-        let check_queryable = &PATHS.check_queryable.path;
+            let check_queryable = &PATHS.check_queryable.path;
+
+            // Symbol fields in a Tree-sitter field are told apart by the field, they can share query names
+            let fields = &self.fields;
+            let (names, concat): (Vec<_>, Vec<_>) = fields
+                .field_names
+                .iter()
+                .zip(&fields.field_builder_names)
+                .chain(
+                    fields
+                        .field_vec_names
+                        .iter()
+                        .zip(&fields.field_vec_builder_names),
+                )
+                .chain(
+                    fields
+                        .field_option_names
+                        .iter()
+                        .zip(&fields.field_option_builder_names),
+                )
+                .filter(|(field, _)| field.attr.field.is_none())
+                .map(|(field, builder)| {
+                    let name = &field.ident;
+                    let query_names = match &field.attr.capture {
+                        Some(capture) => quote! { &[#capture] },
+                        None => quote! { #builder::QUERY_NAMES },
+                    };
+                    (quote! { stringify!(#name) }, query_names)
+                })
+                .chain(self.fields.field_values.iter().map(|field| {
+                    let name = &field.ident;
+                    let capture = &field.capture;
+                    (quote! { stringify!(#name) }, quote! { &[#capture] })
+                }))
+                .unzip();
 
-        let names = quote! { &[#(#names),*] };
+            let names = quote! { &[#(#names),*] };
 
-        let concat = self
+            let input_name = self.input_name;
+            let check_conflicts = &PATHS.check_conflicts;
+
+            builder
+                .add(quote! { const CHECK: () = {
+                    use #queryable;
+                    use #check_queryable;
+                    let queries = auto_lsp::constcat::concat_slices!([&str]: #(#concat),*);
+                    #check_conflicts(stringify!(#input_name), #names, queries);
+                }; })
+                .stage_trait(&self.input_name, check_queryable);
+
+            builder
+                .add(quote! { const _: () = <#input_name as  #check_queryable>::CHECK; })
+                .stage();
+
+            self.impl_collect_query_names(builder);
+        }
+    }
+
+    #[cfg(feature = "assertions")]
+    fn impl_collect_query_names(&self, builder: &mut FieldBuilder) {
+        let queryable = &PATHS.queryable.path;
+        let collect_query_names = &PATHS.collect_query_names.path;
+        let variant = &PATHS.collect_query_names.collect_query_names.variant;
+
+        let captures = self
             .fields
-            .get_field_builder_names()
+            .field_values
             .iter()
-            .map(|name| quote! { #name::QUERY_NAMES })
+            .map(|field| &field.capture)
             .collect::<Vec<_>>();
 
+        builder
+            .add_fn_iter(
+                self.fields,
+                &PATHS.collect_query_names.collect_query_names.sig,
+                Some(quote! {
+                    if !visited.insert(std::any::TypeId::of::<Self>()) {
+                        return;
+                    }
+                }),
+                |_, field, _, field_type, _| {
+                    let query_names = match &field.attr.capture {
+                        Some(capture) => quote! { &[#capture] },
+                        None => quote! { <#field_type as #queryable>::QUERY_NAMES },
+                    };
+                    quote! {
+                        names.extend_from_slice(#query_names);
+                        <#field_type as #collect_query_names>::#variant;
+                    }
+                },
+                Some(quote! { names.extend_from_slice(&[#(#captures),*]); }),
+            )
+            .stage_trait(self.input_name, collect_query_names);
+    }
+
+    fn impl_derive_query(&self, builder: &mut FieldBuilder) {
         let input_name = self.input_name;
-        let check_conflicts = &PATHS.check_conflicts;
+        let query_name = self.query_name;
+        let derive_query = &PATHS.derive_query.path;
+        let node_kinds_sig = &PATHS.derive_query.node_kinds.sig;
+        let derive_query_sig = &PATHS.derive_query.derive_query.sig;
+
+        let node_kind = match self.node_kind {
+            Some(node_kind) => node_kind,
+            None => {
+                builder
+                    .add(quote! {
+                        #node_kinds_sig {
+                            vec![]
+                        }
+
+                        #derive_query_sig {
+                            if query.visit::<Self>() {
+                                query.add_error(format!("{} has no node_kind", stringify!(#input_name)));
+                            }
+                        }
+                    })
+                    .stage_trait(input_name, derive_query);
+                return;
+            }
+        };
+
+        let tree_sitter_field = |field: &Option<String>| match field {
+            Some(field) => quote! { Some(#field) },
+            None => quote! { None },
+        };
+
+        let values = self
+            .fields
+            .field_values
+            .iter()
+            .map(|value| {
+                let capture = &value.capture;
+                let field = tree_sitter_field(&value.attr.field);
+                quote! { query.add_value(#node_kind, #field, #capture); }
+            })
+            .collect::<Vec<_>>();
 
         builder
-            .add(quote! { const CHECK: () = {
-                use #queryable;
-                use #check_queryable;
-                let queries = auto_lsp::constcat::concat_slices!([&str]: #(#concat),*);
-                #check_conflicts(stringify!(#input_name), #names, queries);
-            }; })
-            .stage_trait(&self.input_name, check_queryable);
-        
-            builder
-                .add(quote! { const _: () = <#input_name as  #check_queryable>::CHECK; })
-                .stage();
-        }
+            .add(quote! {
+                #node_kinds_sig {
+                    vec![(#node_kind, #query_name)]
+                }
+            })
+            .add_fn_iter(
+                self.fields,
+                derive_query_sig,
+                Some(quote! {
+                    if !query.visit::<Self>() {
+                        return;
+                    }
+                }),
+                |_, field, _, field_type, _| {
+                    let field_name = tree_sitter_field(&field.attr.field);
+                    let add = match &field.attr.capture {
+                        Some(capture) => quote! {
+                            query.add_capture::<#field_type>(#node_kind, #field_name, #capture);
+                        },
+                        None => quote! {
+                            query.add_symbol::<#field_type>(#node_kind, #field_name);
+                        },
+                    };
+                    quote! {
+                        #add
+                        <#field_type as #derive_query>::derive_query(query);
+                    }
+                },
+                Some(quote! { #(#values)* }),
+            )
+            .stage_trait(input_name, derive_query);
     }
 
     fn impl_dynamic_swap(&self, builder: &mut FieldBuilder) {
         let static_update_trait = &PATHS.static_swap.path;
+        let update_children_trait = &PATHS.update_children;
         builder
             .add_fn_iter(
                 &self.fields,
                 &PATHS.dynamic_swap.swap.sig,
-                Some(quote! { use #static_update_trait; }),
-                |_, _, name, _, _| {
-                    quote! {
-                        self.#name.update(start, offset, parent_check.clone(), builder_params)?;
+                Some(quote! {
+                    use #static_update_trait;
+                    use #update_children_trait;
+                }),
+                |_, field, _, _, _| {
+                    let name = &field.member;
+                    match field.attr.capture {
+                        // Symbols built from another capture are rebuilt by their parent
+                        Some(_) => quote! {
+                            self.#name.update_children(range, parent_check.clone(), builder_params)?;
+                        },
+                        None => quote! {
+                            self.#name.update(range, parent_check.clone(), builder_params)?;
+                        },
                     }
                 },
                 Some(quote! { std::ops::ControlFlow::Continue(()) }),
@@ -247,7 +477,8 @@ impl<'a> StructBuilder<'a> {
                 &self.fields,
                 &PATHS.edit_range.edit_range.sig,
                 Some(quote! { use #static_update_trait; }),
-                |_, _, name, _, _| {
+                |_, field, _, _, _| {
+                    let name = &field.member;
                     quote! {
                         self.#name.edit_range(start, offset);
                     }
@@ -263,7 +494,8 @@ impl<'a> StructBuilder<'a> {
                 &self.fields,
                 &PATHS.collect_references.collect_references.sig,
                 None,
-                |_, _, name, _, _| {
+                |_, field, _, _, _| {
+                    let name = &field.member;
                     quote! {
                         self.#name.collect_references(builder_params);
                     }
@@ -273,6 +505,164 @@ impl<'a> StructBuilder<'a> {
             .stage_trait(&self.input_name, &PATHS.collect_references.path);
     }
 
+    fn impl_collect_symbols(&self, builder: &mut FieldBuilder) {
+        let collect_symbols_trait = &PATHS.collect_symbols.path;
+        builder
+            .add_fn_iter(
+                self.fields,
+                &PATHS.collect_symbols.collect_symbols.sig,
+                Some(quote! { use #collect_symbols_trait; }),
+                |_, field, _, _, _| {
+                    let name = &field.member;
+                    quote! {
+                        self.#name.collect_symbols(acc);
+                    }
+                },
+                None,
+            )
+            .stage_trait(self.input_name, &PATHS.collect_symbols.path);
+    }
+
+    fn impl_collect_children(&self, builder: &mut FieldBuilder) {
+        let collect_children_trait = &PATHS.collect_children.path;
+        let collect_children_sig = &PATHS.collect_children.collect_children.sig;
+        let members = self
+            .fields
+            .get_ordered_fields()
+            .into_iter()
+            .map(|(field, _)| &field.member)
+            .collect::<Vec<_>>();
+
+        builder
+            .add(quote! {
+                #collect_children_sig {
+                    use #collect_children_trait;
+                    #(self.#members.collect_children(acc);)*
+                }
+            })
+            .stage_trait(self.input_name, collect_children_trait);
+    }
+
+    fn impl_walk(&self, builder: &mut FieldBuilder) {
+        let input_name = self.input_name;
+        let visit = &PATHS.visit.path;
+        let accept = &PATHS.visit.accept.variant;
+        let visit_mut = &PATHS.visit_mut.path;
+        let accept_mut = &PATHS.visit_mut.accept_mut.variant;
+        let walk = &PATHS.walk.path;
+        let walk_sig = &PATHS.walk.walk.sig;
+        let walk_mut = &PATHS.walk_mut.path;
+        let walk_mut_sig = &PATHS.walk_mut.walk_mut.sig;
+
+        let fields = self.fields.get_ordered_fields();
+        let members = fields
+            .iter()
+            .map(|(field, _)| &field.member)
+            .collect::<Vec<_>>();
+        let types = fields.iter().map(|(_, ty)| ty).collect::<Vec<_>>();
+
+        builder
+            .add(quote! {
+                impl<V: ?Sized> #walk<V> for #input_name
+                where
+                    #(#types: #visit<V>,)*
+                {
+                    #walk_sig {
+                        use #visit;
+                        #(self.#members.#accept;)*
+                    }
+                }
+
+                impl<V: ?Sized> #walk_mut<V> for #input_name
+                where
+                    #(#types: #visit_mut<V>,)*
+                {
+                    #walk_mut_sig {
+                        use #visit_mut;
+                        #(self.#members.#accept_mut;)*
+                    }
+                }
+            })
+            .stage();
+    }
+
+    /// Symbol fields print the symbol they hold, the symbol data prints its parent and target as ids.
+    fn impl_debug(&self, builder: &mut FieldBuilder) {
+        let input_name = self.input_name;
+        let members = self.fields.get_ordered_members();
+        let data = self.fields.get_data_member();
+
+        let body = match self.fields.tuple {
+            true => quote! {
+                f.debug_tuple(stringify!(#input_name))
+                    #(.field(&self.#members))*
+                    .field(&self.#data)
+                    .finish()
+            },
+            false => quote! {
+                f.debug_struct(stringify!(#input_name))
+                    #(.field(stringify!(#members), &self.#members))*
+                    .field(stringify!(#data), &self.#data)
+                    .finish()
+            },
+        };
+
+        builder
+            .add(quote! {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    #body
+                }
+            })
+            .stage_trait(input_name, &parse_quote!(std::fmt::Debug));
+    }
+
+    /// Fields are only serialized with the `serde` feature, the trait is empty otherwise.
+    fn impl_serialize_fields(&self, builder: &mut FieldBuilder) {
+        #[cfg(feature = "serde")]
+        {
+            let to_json = &PATHS.to_json.path;
+            let to_json_variant = &PATHS.to_json.to_json.variant;
+
+            let mut fields = self
+                .fields
+                .get_ordered_fields()
+                .into_iter()
+                .map(|(field, _)| {
+                    let member = &field.member;
+                    (
+                        field.index,
+                        quote! { fields.insert(stringify!(#member).into(), self.#member.#to_json_variant); },
+                    )
+                })
+                .chain(self.fields.field_values.iter().map(|field| {
+                    let member = &field.member;
+                    (
+                        field.index,
+                        quote! {
+                            fields.insert(
+                                stringify!(#member).into(),
+                                auto_lsp::serde_json::to_value(&self.#member)?,
+                            );
+                        },
+                    )
+                }))
+                .collect::<Vec<_>>();
+            fields.sort_by_key(|(index, _)| *index);
+            let fields = fields.into_iter().map(|(_, field)| field);
+
+            let serialize_fields_sig = &PATHS.serialize_fields.serialize_fields.sig;
+            builder.add(quote! {
+                #serialize_fields_sig {
+                    use #to_json;
+                    #(#fields)*
+                    Ok(())
+                }
+            });
+        }
+
+        builder.stage_trait(self.input_name, &PATHS.serialize_fields.path);
+    }
+
     fn struct_input_builder(&self, builder: &mut FieldBuilder) {
         let maybe_pending_symbol = &PATHS.maybe_pending_symbol;
         let pending_symbol = &PATHS.pending_symbol;
@@ -285,6 +675,13 @@ impl<'a> StructBuilder<'a> {
                 FieldType::Vec => quote! { #name: Vec<#pending_symbol> },
                 _ => quote! { #name: #maybe_pending_symbol },
             })
+            .add_value_iter(self.fields, |field| {
+                let name = &field.ident;
+                match field.kind {
+                    FieldType::Vec => quote! { #name: Vec<std::ops::Range<usize>> },
+                    _ => quote! { #name: Option<std::ops::Range<usize>> },
+                }
+            })
             .stage_struct(&self.input_builder_name)
             .to_token_stream();
     }
@@ -298,6 +695,13 @@ impl<'a> StructBuilder<'a> {
                 FieldType::Vec => quote! { #name: vec![] },
                 _ => quote! { #name: #maybe_pending_symbol::none() },
             })
+            .add_value_iter(self.fields, |field| {
+                let name = &field.ident;
+                match field.kind {
+                    FieldType::Vec => quote! { #name: vec![] },
+                    _ => quote! { #name: None },
+                }
+            })
             .stage_fields()
             .to_token_stream();
 
@@ -320,24 +724,95 @@ impl<'a> StructBuilder<'a> {
     fn fn_add(&self, builder: &mut FieldBuilder) {
         let input_name = &self.input_name;
         let add_symbol_trait = &PATHS.add_symbol_trait;
+        let queryable = &PATHS.queryable.path;
+        let is_in_field = &PATHS.is_in_field;
         builder.add_fn_iter(
             &self.fields,
             &PATHS.symbol_builder_trait.add.sig,
             Some(quote! { use #add_symbol_trait; }),
-            |_, _, name, field_type, builder| {
-                quote! {
-                    
-                    if let Some(node) =  self.#name.add::<#builder>(capture, params, stringify!(#input_name), stringify!(#field_type))? {
+            |_, field, name, field_type, builder| {
+                let query_names = match &field.attr.capture {
+                    Some(capture) => quote! { &[#capture] },
+                    None => quote! { <#builder as #queryable>::QUERY_NAMES },
+                };
+                let add = quote! {
+                    if let Some(node) =  self.#name.add::<#builder>(capture, params, #query_names, stringify!(#input_name), stringify!(#field_type))? {
                        return Ok(Some(node))
                     };
+                };
+                match &field.attr.field {
+                    Some(field) => quote! {
+                        if #is_in_field(capture, #field) {
+                            #add
+                        }
+                    },
+                    None => add,
                 }
             },
             Some(quote! { Ok(None) }),
         );
     }
 
+    fn fn_add_value(&self, builder: &mut FieldBuilder) {
+        if self.fields.field_values.is_empty() {
+            return;
+        }
+
+        let input_name = &self.input_name;
+        let sig = &PATHS.symbol_builder_trait.add_value.sig;
+
+        let values = FieldBuilder::default()
+            .add_value_iter(self.fields, |field| {
+                let name = &field.ident;
+                let capture = &field.capture;
+                match field.kind {
+                    FieldType::Vec => quote! {
+                        if capture_name == #capture {
+                            self.#name.push(capture.node.byte_range());
+                            return Ok(true);
+                        }
+                    },
+                    _ => quote! {
+                        if capture_name == #capture {
+                            if self.#name.is_some() {
+                                return Err(auto_lsp::core::builder_error!(
+                                    auto_lsp,
+                                    params.document.range_at(capture.node.byte_range()).unwrap(),
+                                    format!("{:?} already set in {:?}", stringify!(#name), stringify!(#input_name))
+                                ));
+                            }
+                            self.#name = Some(capture.node.byte_range());
+                            return Ok(true);
+                        }
+                    },
+                }
+            })
+            .stage()
+            .to_token_stream();
+
+        builder.add(quote! {
+            #sig {
+                let capture_name = params.query.capture_names()[capture.index as usize];
+                #values
+                Ok(false)
+            }
+        });
+    }
+
     fn impl_try_from(&self, builder: &mut FieldBuilder) {
-        let fields = self.fields.get_field_names();
+        let members = self
+            .fields
+            .get_field_members()
+            .into_iter()
+            .chain(self.fields.field_values.iter().map(|field| &field.member))
+            .collect::<Vec<_>>();
+        let fields = self
+            .fields
+            .get_field_names()
+            .into_iter()
+            .chain(self.fields.field_values.iter().map(|field| &field.ident))
+            .collect::<Vec<_>>();
+        let data = self.fields.get_data_member();
 
         let input_name = self.input_name;
         let input_builder_name = &self.input_builder_name;
@@ -349,34 +824,135 @@ impl<'a> StructBuilder<'a> {
         let try_downcast = &PATHS.try_downcast_trait;
         let finalize = &PATHS.finalize_trait;
 
+        // Placeholders are empty and placed at the end of the symbol
+        let placeholder = |field_type: &Path| {
+            let placeholder = &PATHS.placeholder.path;
+            quote! {
+                <#field_type as #placeholder>::placeholder(
+                    builder.url.clone(),
+                    builder.range.end..builder.range.end,
+                    &mut std::collections::HashSet::new()
+                )
+            }
+        };
+
         let _builder = FieldBuilder::default()
             .add(quote! {
                 use #try_downcast;
                 use #finalize;
             })
             .add_iter(&self.fields,
-                |ty, _, name, field_type, _| match ty  {
-                FieldType::Normal  => quote! {
-                    let #name = Symbol::new_and_check(builder
-                        .#name
-                        .as_ref()
-                        .ok_or(auto_lsp::core::builder_error!(
-                            auto_lsp,
-                            builder_range,
-                            format!(
-                                "Invalid {:?} for {:?}, {:?} is mandatory but received empty value",
-                                stringify!(#name), 
-                                stringify!(#input_name),
-                                stringify!(#name)
-                            )
-                        ))?
-                        .try_downcast(params, stringify!(#field_type), builder_range, stringify!(#input_name))?, params);
+                |ty, field, name, field_type, _| match ty  {
+                FieldType::Normal if field.attr.default => {
+                    let placeholder = placeholder(field_type);
+                    quote! {
+                        let #name = match builder.#name.as_ref() {
+                            Some(#name) => Symbol::new_and_check(
+                                #name.try_downcast(params, stringify!(#field_type), builder_range, stringify!(#input_name))?,
+                                params
+                            ),
+                            None => Symbol::new_placeholder(#placeholder.ok_or(auto_lsp::core::builder_error!(
+                                auto_lsp,
+                                builder_range,
+                                format!(
+                                    "Invalid {:?} for {:?}, {:?} is empty and {:?} has no placeholder",
+                                    stringify!(#name),
+                                    stringify!(#input_name),
+                                    stringify!(#name),
+                                    stringify!(#field_type)
+                                )
+                            ))?),
+                        };
+                    }
                 },
-                _=> quote! {
+                // In lenient mode, a missing or invalid field is reported and replaced by a placeholder
+                FieldType::Normal => {
+                    let placeholder = placeholder(field_type);
+                    quote! {
+                        let #name = match builder.#name.as_ref() {
+                            Some(#name) => #name.try_downcast(params, stringify!(#field_type), builder_range, stringify!(#input_name)),
+                            None => Err(auto_lsp::core::builder_error!(
+                                auto_lsp,
+                                builder_range,
+                                format!(
+                                    "Invalid {:?} for {:?}, {:?} is mandatory but received empty value",
+                                    stringify!(#name),
+                                    stringify!(#input_name),
+                                    stringify!(#name)
+                                )
+                            )),
+                        };
+                        let #name = match #name
+                        {
+                            Ok(#name) => Symbol::new_and_check(#name, params),
+                            Err(err) if params.lenient => match #placeholder {
+                                Some(#name) => {
+                                    params.diagnostics.push(err);
+                                    Symbol::new_placeholder(#name)
+                                }
+                                None => return Err(err),
+                            },
+                            Err(err) => return Err(err),
+                        };
+                    }
+                },
+                FieldType::Option => quote! {
                         let #name = builder
                             .#name
                             .try_downcast(params, stringify!(#field_type), builder_range, stringify!(#input_name))?.finalize(params);
+                    },
+                FieldType::Vec => {
+                    let cardinality = check_cardinality(input_name, name, &field.attr);
+                    quote! {
+                        let #name = builder
+                            .#name
+                            .try_downcast(params, stringify!(#field_type), builder_range, stringify!(#input_name))?.finalize(params);
+                        #cardinality
                     }
+                }
+            })
+            .add_value_iter(self.fields, |field| {
+                let name = &field.ident;
+                let parse = parse_value(input_name, field);
+                match field.kind {
+                    FieldType::Normal if field.attr.default => quote! {
+                        let #name = match builder.#name.as_ref() {
+                            Some(range) => #parse,
+                            None => Default::default(),
+                        };
+                    },
+                    FieldType::Normal => quote! {
+                        let #name = match builder.#name.as_ref() {
+                            Some(range) => #parse,
+                            None => return Err(auto_lsp::core::builder_error!(
+                                auto_lsp,
+                                builder_range,
+                                format!(
+                                    "Invalid {:?} for {:?}, {:?} is mandatory but received empty value",
+                                    stringify!(#name),
+                                    stringify!(#input_name),
+                                    stringify!(#name)
+                                )
+                            )),
+                        };
+                    },
+                    FieldType::Option => quote! {
+                        let #name = match builder.#name.as_ref() {
+                            Some(range) => Some(#parse),
+                            None => None,
+                        };
+                    },
+                    FieldType::Vec => {
+                        let cardinality = check_cardinality(input_name, name, &field.attr);
+                        quote! {
+                            let mut #name = Vec::with_capacity(builder.#name.len());
+                            for range in builder.#name.iter() {
+                                #name.push(#parse);
+                            }
+                            #cardinality
+                        }
+                    }
+                }
             })
             .stage()
             .to_token_stream();
@@ -394,8 +970,8 @@ impl<'a> StructBuilder<'a> {
                     #_builder
 
                     Ok(#input_name {
-                        _data: #symbol_data::new(builder.url.clone(), builder.range.clone()),
-                        #(#fields),*
+                        #data: #symbol_data::new(builder.url.clone(), builder.range.clone()),
+                        #(#members: #fields),*
                     })
                 }
             }
@@ -403,3 +979,141 @@ impl<'a> StructBuilder<'a> {
         builder.stage();
     }
 }
+
+impl<'a> StructBuilder<'a> {
+    /// Mandatory symbol fields are placeholders as well, there's no placeholder if a mandatory value field has no default.
+    fn impl_placeholder(&self, builder: &mut FieldBuilder) {
+        let placeholder = &PATHS.placeholder.path;
+        let sig = &PATHS.placeholder.placeholder.sig;
+        let variant = &PATHS.placeholder.placeholder.variant;
+        let symbol = &PATHS.symbol;
+        let symbol_data = &PATHS.symbol_data;
+
+        if self
+            .fields
+            .field_values
+            .iter()
+            .any(|field| matches!(field.kind, FieldType::Normal) && !field.attr.default)
+        {
+            builder
+                .add(quote! {
+                    #sig {
+                        let _ = (url, range, visited);
+                        None
+                    }
+                })
+                .stage_trait(self.input_name, placeholder);
+            return;
+        }
+
+        let data = self.fields.get_data_member();
+
+        let symbols = self
+            .fields
+            .field_names
+            .iter()
+            .zip(self.fields.field_types_names.iter())
+            .map(|(field, field_type)| {
+                let member = &field.member;
+                quote! { #member: #symbol::new_placeholder(<#field_type as #placeholder>::#variant?) }
+            });
+        let options = self.fields.field_option_names.iter().map(|field| {
+            let member = &field.member;
+            quote! { #member: None }
+        });
+        let vecs = self.fields.field_vec_names.iter().map(|field| {
+            let member = &field.member;
+            quote! { #member: vec![] }
+        });
+        let values = self.fields.field_values.iter().map(|field| {
+            let member = &field.member;
+            match field.kind {
+                FieldType::Normal => quote! { #member: Default::default() },
+                FieldType::Option => quote! { #member: None },
+                FieldType::Vec => quote! { #member: vec![] },
+            }
+        });
+        let fields = symbols
+            .chain(options)
+            .chain(vecs)
+            .chain(values)
+            .collect::<Vec<_>>();
+
+        builder
+            .add(quote! {
+                #sig {
+                    if !visited.insert(std::any::TypeId::of::<Self>()) {
+                        return None;
+                    }
+                    let placeholder = (|| {
+                        Some(Self {
+                            #data: #symbol_data::new(url.clone(), range.clone()),
+                            #(#fields,)*
+                        })
+                    })();
+                    visited.remove(&std::any::TypeId::of::<Self>());
+                    placeholder
+                }
+            })
+            .stage_trait(self.input_name, placeholder);
+    }
+}
+
+/// Parse the text at `range` into the type of a value field, returns early with a diagnostic on failure.
+fn parse_value(input_name: &Ident, field: &ValueField) -> TokenStream {
+    let name = &field.ident;
+    let ty = &field.ty;
+    quote! {{
+        let text = &params.document.document.text[range.clone()];
+        text.parse::<#ty>().map_err(|_| auto_lsp::core::builder_error!(
+            auto_lsp,
+            params.document.range_at(range.clone()).unwrap(),
+            format!(
+                "Invalid {:?} for {:?}, {:?} is not a valid {}",
+                stringify!(#name),
+                stringify!(#input_name),
+                text,
+                stringify!(#ty)
+            )
+        ))?
+    }}
+}
+
+/// Check the number of elements of a `Vec` field against its `min` and `max` attributes.
+fn check_cardinality(input_name: &Ident, name: &Ident, attr: &FieldAttributes) -> TokenStream {
+    let min = attr.min.map(|min| {
+        quote! {
+            if #name.len() < #min {
+                return Err(auto_lsp::core::builder_error!(
+                    auto_lsp,
+                    builder_range,
+                    format!(
+                        "Invalid {:?} for {:?}, expected at least {} but received {}",
+                        stringify!(#name),
+                        stringify!(#input_name),
+                        #min,
+                        #name.len()
+                    )
+                ));
+            }
+        }
+    });
+    let max = attr.max.map(|max| {
+        quote! {
+            if #name.len() > #max {
+                return Err(auto_lsp::core::builder_error!(
+                    auto_lsp,
+                    builder_range,
+                    format!(
+                        "Invalid {:?} for {:?}, expected at most {} but received {}",
+                        stringify!(#name),
+                        stringify!(#input_name),
+                        #max,
+                        #name.len()
+                    )
+                ));
+            }
+        }
+    });
+    quote! { #min #max }
+}
@@ -1,35 +1,5 @@
-use syn::{Type, TypePath};
-
-/// Extracts the raw type name by recursively unwrapping wrapper types
-///
-/// This function traverses through common wrapper types (Arc, RwLock, Vec, Option)
-/// to get the underlying type name.
-pub fn get_raw_type_name(ty: &Type) -> String {
-    match ty {
-        Type::Path(TypePath { path, .. }) => {
-            let type_name = path
-                .segments
-                .iter()
-                .map(|segment| segment.ident.to_string())
-                .collect::<Vec<_>>()
-                .join("::");
-
-            // Check if the type is Arc, RwLock, Vec, Option and recursively get the inner type
-            if type_name == "Arc"
-                || type_name == "RwLock"
-                || type_name == "Vec"
-                || type_name == "Option"
-            {
-                if let Some(inner_type) = get_inner_type(ty, 0) {
-                    return get_raw_type_name(&inner_type);
-                }
-            }
-
-            type_name
-        }
-        _ => panic!("Expected a type path"),
-    }
-}
+use quote::format_ident;
+use syn::{Path, Type, TypePath};
 
 pub fn get_inner_type(ty: &Type, index: usize) -> Option<Type> {
     if let Type::Path(TypePath { path, .. }) = ty {
@@ -66,17 +36,61 @@ pub fn is_option(ty: &Type) -> bool {
     false
 }
 
+/// Checks if the type is a Box
+pub fn is_box(ty: &Type) -> bool {
+    if let Type::Path(TypePath { path, .. }) = ty {
+        if let Some(segment) = path.segments.first() {
+            return segment.ident == "Box";
+        }
+    }
+    false
+}
+
+/// Extracts the path of the symbol type of a field, unwrapping `Vec`, `Option` and `Box`.
+///
+/// `Box` is accepted for recursive grammars, although symbols are already stored behind a pointer.
+///
+/// Returns an error for types that can't be symbols, such as references, tuples or generic types.
+pub fn get_symbol_path(ty: &Type) -> syn::Result<Path> {
+    let mut ty = ty.clone();
+    if is_vec(&ty) || is_option(&ty) {
+        ty = get_inner_type(&ty, 0)
+            .ok_or_else(|| syn::Error::new_spanned(&ty, "Expected a type argument"))?;
+    }
+    if is_box(&ty) {
+        ty = get_inner_type(&ty, 0)
+            .ok_or_else(|| syn::Error::new_spanned(&ty, "Expected a type argument"))?;
+    }
+    match ty {
+        Type::Path(TypePath { qself: None, path }) => {
+            match path.segments.iter().find(|segment| !segment.arguments.is_none()) {
+                Some(segment) => Err(syn::Error::new_spanned(
+                    segment,
+                    "Generic types are not supported, fields must be symbols generated by `seq` or `choice`",
+                )),
+                None => Ok(path),
+            }
+        }
+        ty => Err(syn::Error::new_spanned(
+            ty,
+            "Unsupported field type, expected a symbol optionally wrapped in `Vec`, `Option` or `Box`",
+        )),
+    }
+}
+
+/// Returns the path of the builder of a symbol, `path::to::Symbol` gives `path::to::SymbolBuilder`.
+pub fn get_builder_path(path: &Path) -> Path {
+    let mut path = path.clone();
+    let last = path.segments.last_mut().unwrap();
+    last.ident = format_ident!("{}Builder", last.ident, span = last.ident.span());
+    path
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use syn::parse_quote;
 
-    #[test]
-    fn test_get_raw_type_name() {
-        let ty = parse_quote! { Arc<RwLock<Vec<Option<String>>>> };
-        assert_eq!(get_raw_type_name(&ty), "String");
-    }
-
     #[test]
     fn test_is_vec() {
         let ty = parse_quote! { Vec<String> };
@@ -88,4 +102,33 @@ mod tests {
         let ty = parse_quote! { Option<String> };
         assert!(is_option(&ty));
     }
+
+    #[test]
+    fn test_get_symbol_path() {
+        let path = |ty: Type| {
+            get_symbol_path(&ty).map(|path| quote::ToTokens::to_token_stream(&path).to_string())
+        };
+
+        assert_eq!(path(parse_quote! { Foo }).unwrap(), "Foo");
+        assert_eq!(path(parse_quote! { Box<Foo> }).unwrap(), "Foo");
+        assert_eq!(path(parse_quote! { Vec<Box<Foo>> }).unwrap(), "Foo");
+        assert_eq!(
+            path(parse_quote! { Option<crate::ast::Foo> }).unwrap(),
+            "crate :: ast :: Foo"
+        );
+
+        assert!(path(parse_quote! { Foo<Bar> }).is_err());
+        assert!(path(parse_quote! { Vec<Option<Foo>> }).is_err());
+        assert!(path(parse_quote! { &'static Foo }).is_err());
+        assert!(path(parse_quote! { (Foo, Bar) }).is_err());
+    }
+
+    #[test]
+    fn test_get_builder_path() {
+        let path = get_builder_path(&parse_quote! { crate::ast::Foo });
+        assert_eq!(
+            quote::ToTokens::to_token_stream(&path).to_string(),
+            "crate :: ast :: FooBuilder"
+        );
+    }
 }
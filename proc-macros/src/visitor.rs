@@ -0,0 +1,164 @@
+use crate::PATHS;
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::Path;
+
+/// Builder for the `Visitor` and `VisitorMut` traits of an AST.
+///
+/// This is the builder called by the `visitor!` macro.
+///
+/// It generates:
+///     - The `Visitor` and `VisitorMut` traits, with a `visit_<type>` hook per symbol.
+///     - The implementations of `Visit` and `VisitMut` that call the hook of each symbol.
+pub struct VisitorBuilder<'a> {
+    pub types: &'a [Path],
+}
+
+impl<'a> VisitorBuilder<'a> {
+    pub fn new(types: &'a [Path]) -> Self {
+        Self { types }
+    }
+
+    /// Check that each symbol gets its own hook.
+    ///
+    /// Missing symbols can't be known here, they are reported by the `Visit` bounds of `walk`.
+    pub fn check_hooks(&self) -> syn::Result<()> {
+        let mut hooks = std::collections::HashMap::new();
+        let mut errors: Option<syn::Error> = None;
+        for ty in self.types {
+            if let Some(previous) = hooks.insert(hook_name(ty), ty) {
+                let error = syn::Error::new_spanned(
+                    ty,
+                    format!(
+                        "{} has the same hook as {}",
+                        ty.to_token_stream(),
+                        previous.to_token_stream()
+                    ),
+                );
+                match errors.as_mut() {
+                    Some(errors) => errors.combine(error),
+                    None => errors = Some(error),
+                }
+            }
+        }
+        match errors {
+            Some(errors) => Err(errors),
+            None => Ok(()),
+        }
+    }
+}
+
+impl ToTokens for VisitorBuilder<'_> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let visit = &PATHS.visit.path;
+        let accept = &PATHS.visit.accept.sig;
+        let visit_mut = &PATHS.visit_mut.path;
+        let accept_mut = &PATHS.visit_mut.accept_mut.sig;
+        let walk = &PATHS.walk.path;
+        let walk_mut = &PATHS.walk_mut.path;
+
+        let types = self.types;
+        let hooks = types.iter().map(hook_name).collect::<Vec<_>>();
+
+        tokens.extend(quote! {
+            /// Visit the AST with a hook per symbol.
+            ///
+            /// By default, hooks visit the children of the symbol, overridden hooks can call `walk` to keep visiting them.
+            pub trait Visitor {
+                #(
+                    fn #hooks(&mut self, node: &#types) {
+                        #walk::walk(node, self);
+                    }
+                )*
+            }
+
+            /// Visit the AST mutably with a hook per symbol.
+            ///
+            /// By default, hooks visit the children of the symbol, overridden hooks can call `walk_mut` to keep visiting them.
+            pub trait VisitorMut {
+                #(
+                    fn #hooks(&mut self, node: &mut #types) {
+                        #walk_mut::walk_mut(node, self);
+                    }
+                )*
+            }
+
+            #(
+                impl<V: Visitor + ?Sized> #visit<V> for #types {
+                    #accept {
+                        visitor.#hooks(self);
+                    }
+                }
+
+                impl<V: VisitorMut + ?Sized> #visit_mut<V> for #types {
+                    #accept_mut {
+                        visitor.#hooks(self);
+                    }
+                }
+            )*
+        });
+    }
+}
+
+/// Name of the hook of a symbol, `CompoundStatement` becomes `visit_compound_statement`.
+fn hook_name(ty: &Path) -> Ident {
+    let name = ty.segments.last().unwrap().ident.to_string();
+    let chars = name.chars().collect::<Vec<_>>();
+
+    let mut snake_case = String::new();
+    for (index, char) in chars.iter().enumerate() {
+        if char.is_uppercase() && index > 0 {
+            let previous = chars[index - 1];
+            let next = chars.get(index + 1);
+            // Acronyms are kept together, `HTMLElement` becomes `html_element`
+            if !previous.is_uppercase() || next.is_some_and(|next| next.is_lowercase()) {
+                snake_case.push('_');
+            }
+        }
+        snake_case.extend(char.to_lowercase());
+    }
+    format_ident!("visit_{}", snake_case)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn test_hook_name() {
+        assert_eq!(hook_name(&parse_quote!(Module)), "visit_module");
+        assert_eq!(
+            hook_name(&parse_quote!(crate::ast::CompoundStatement)),
+            "visit_compound_statement"
+        );
+        assert_eq!(hook_name(&parse_quote!(HTMLElement)), "visit_html_element");
+        assert_eq!(hook_name(&parse_quote!(Utf8String)), "visit_utf8_string");
+    }
+
+    #[test]
+    fn test_check_hooks() {
+        let types: Vec<Path> = vec![parse_quote!(Module), parse_quote!(Function)];
+        assert!(VisitorBuilder::new(&types).check_hooks().is_ok());
+
+        let types: Vec<Path> = vec![
+            parse_quote!(Module),
+            parse_quote!(a::Name),
+            parse_quote!(b::Name),
+            parse_quote!(Module),
+        ];
+        let errors = VisitorBuilder::new(&types)
+            .check_hooks()
+            .unwrap_err()
+            .into_iter()
+            .map(|error| error.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            vec![
+                "b :: Name has the same hook as a :: Name",
+                "Module has the same hook as Module"
+            ]
+        );
+    }
+}
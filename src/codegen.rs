@@ -0,0 +1,493 @@
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+/// Entry of the `node-types.json` file of a grammar.
+#[derive(Deserialize)]
+struct NodeType {
+    #[serde(rename = "type")]
+    kind: String,
+    named: bool,
+    #[serde(default)]
+    fields: BTreeMap<String, ChildType>,
+    children: Option<ChildType>,
+    /// Only set for supertypes
+    subtypes: Option<Vec<TypeRef>>,
+}
+
+/// Field or children of a node.
+#[derive(Deserialize)]
+struct ChildType {
+    multiple: bool,
+    required: bool,
+    types: Vec<TypeRef>,
+}
+
+#[derive(Deserialize)]
+struct TypeRef {
+    #[serde(rename = "type")]
+    kind: String,
+    named: bool,
+}
+
+/// Core query and AST definitions generated by [`generate`].
+pub struct GeneratedAst {
+    /// Core query, with one capture per node kind, named after the kind.
+    pub query: String,
+    /// `seq` structs and `choice` enums, in the order they are reached from the root.
+    pub ast: String,
+}
+
+/// Generate the core query and a skeleton of the AST from the `node-types.json` file of a grammar.
+///
+/// Only the node kinds reachable from `root` are generated:
+/// - Supertypes become `choice` enums, with a variant per subtype.
+/// - Other named nodes become `seq` structs with a `node_kind`, with a field per tree-sitter field and a `children` field for the other children.
+/// - Fields accepting several node kinds get their own `choice` enum, named after the struct and the field.
+/// - Fields are wrapped in a `Vec` if they accept multiple nodes, in an `Option` if they are not required.
+///
+/// Fields are annotated with their tree-sitter field (`#[ast(field = "...")]`),
+/// so fields accepting the same node kinds, such as the `left` and `right` of a binary operator, are told apart.
+///
+/// Variants whose node kinds are matched by a previous variant of the same enum are skipped,
+/// a supertype sharing only some of its node kinds is replaced by its other subtypes.
+///
+/// Anonymous nodes (keywords, operators and punctuation) are not captured.
+///
+/// This function is meant to be called from a build script, the output can then be included or copied and edited.
+/// The AST of a large grammar is deeply nested and may require a higher `#![recursion_limit]`.
+///
+/// ```no_run
+/// // build.rs
+/// let generated = auto_lsp::codegen::generate(tree_sitter_python::NODE_TYPES, "module").unwrap();
+///
+/// let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap());
+/// std::fs::write(out_dir.join("ast.rs"), generated.ast).unwrap();
+/// std::fs::write(out_dir.join("core.scm"), generated.query).unwrap();
+/// ```
+///
+/// The core query is then given to `configure_parsers!` through a static, as `core` expects a path:
+///
+/// ```ignore
+/// include!(concat!(env!("OUT_DIR"), "/ast.rs"));
+///
+/// static CORE_QUERY: &str = include_str!(concat!(env!("OUT_DIR"), "/core.scm"));
+///
+/// configure_parsers!(
+///     "python" => {
+///         language: tree_sitter_python::LANGUAGE,
+///         node_types: tree_sitter_python::NODE_TYPES,
+///         ast_root: Module,
+///         core: CORE_QUERY,
+///         comment: None,
+///         fold: None,
+///         highlights: None
+///     }
+/// );
+/// ```
+pub fn generate(node_types: &str, root: &str) -> anyhow::Result<GeneratedAst> {
+    let node_types = serde_json::from_str::<Vec<NodeType>>(node_types)?;
+    let mut generator = Generator {
+        node_types: node_types
+            .iter()
+            .filter(|node_type| node_type.named)
+            .map(|node_type| (node_type.kind.as_str(), node_type))
+            .collect(),
+        queue: VecDeque::new(),
+        visited: HashSet::new(),
+        captures: vec![],
+        items: vec![],
+    };
+
+    if !generator.node_types.contains_key(root) {
+        return Err(anyhow::format_err!("Node kind {:?} not found", root));
+    }
+
+    generator.enqueue(root);
+    while let Some(kind) = generator.queue.pop_front() {
+        generator.node(&kind);
+    }
+
+    let mut query = String::new();
+    for capture in &generator.captures {
+        query.push_str(&format!("({}) @{}\n", capture, capture));
+    }
+
+    let mut ast = String::from("use auto_lsp::core::ast::*;\nuse auto_lsp::{choice, seq};\n");
+    for item in &generator.items {
+        ast.push('\n');
+        ast.push_str(item);
+    }
+
+    Ok(GeneratedAst { query, ast })
+}
+
+struct Generator<'a> {
+    node_types: HashMap<&'a str, &'a NodeType>,
+    queue: VecDeque<String>,
+    visited: HashSet<String>,
+    /// Node kinds captured by the core query
+    captures: Vec<String>,
+    /// Generated structs and enums
+    items: Vec<String>,
+}
+
+impl<'a> Generator<'a> {
+    fn enqueue(&mut self, kind: &str) {
+        if self.visited.insert(kind.to_string()) {
+            self.queue.push_back(kind.to_string());
+        }
+    }
+
+    fn node(&mut self, kind: &str) {
+        let node_type = match self.node_types.get(kind) {
+            Some(node_type) => *node_type,
+            None => {
+                // Kinds without an entry have no children
+                self.captures.push(kind.to_string());
+                self.items.push(format!(
+                    "#[seq(node_kind = \"{}\", kind(symbol()))]\npub struct {} {{}}\n",
+                    kind,
+                    type_name(kind)
+                ));
+                return;
+            }
+        };
+
+        if let Some(subtypes) = &node_type.subtypes {
+            let variants = subtypes
+                .iter()
+                .filter(|subtype| subtype.named)
+                .map(|subtype| subtype.kind.as_str())
+                .collect::<Vec<_>>();
+            self.choice(&type_name(kind), &variants);
+            return;
+        }
+
+        let name = type_name(kind);
+        self.captures.push(kind.to_string());
+
+        let mut fields = String::new();
+
+        let children = node_type
+            .fields
+            .iter()
+            .map(|(field, child)| (Some(field.as_str()), child))
+            .chain(node_type.children.iter().map(|child| (None, child)));
+
+        for (field, child) in children {
+            let kinds = child
+                .types
+                .iter()
+                .filter(|ty| ty.named)
+                .map(|ty| ty.kind.as_str())
+                .collect::<Vec<_>>();
+
+            if kinds.is_empty() {
+                continue;
+            }
+
+            let ty = match kinds.as_slice() {
+                [kind] => {
+                    self.enqueue(kind);
+                    type_name(kind)
+                }
+                kinds => {
+                    let ty = format!("{}{}", name, type_name(field.unwrap_or("children")));
+                    self.choice(&ty, kinds);
+                    ty
+                }
+            };
+            let ty = match (child.multiple, child.required) {
+                (true, _) => format!("Vec<{}>", ty),
+                (false, false) => format!("Option<{}>", ty),
+                (false, true) => ty,
+            };
+
+            match field {
+                Some(field) => fields.push_str(&format!(
+                    "    #[ast(field = \"{}\")]\n    pub {}: {},\n",
+                    field,
+                    field_name(field),
+                    ty
+                )),
+                None => fields.push_str(&format!("    pub children: {},\n", ty)),
+            }
+        }
+
+        let body = match fields.is_empty() {
+            true => "{}".to_string(),
+            false => format!("{{\n{}}}", fields),
+        };
+        self.items.push(format!(
+            "#[seq(node_kind = \"{}\", kind(symbol()))]\npub struct {} {}\n",
+            kind, name, body
+        ));
+    }
+
+    fn choice(&mut self, name: &str, kinds: &[&str]) {
+        let mut variants = String::new();
+        let mut query_names = HashSet::new();
+        for kind in kinds {
+            self.variant(kind, &mut variants, &mut query_names);
+        }
+        self.items
+            .push(format!("#[choice]\npub enum {} {{\n{}}}\n", name, variants));
+    }
+
+    /// Add a variant for `kind`, unless its query names are already matched by a previous variant.
+    ///
+    /// A supertype sharing some query names with a previous variant is replaced by its subtypes.
+    fn variant(&mut self, kind: &str, variants: &mut String, query_names: &mut HashSet<String>) {
+        let variant_query_names = self.query_names(kind);
+        if variant_query_names
+            .iter()
+            .all(|query_name| query_names.contains(query_name))
+        {
+            return;
+        }
+
+        if variant_query_names
+            .iter()
+            .any(|query_name| query_names.contains(query_name))
+        {
+            let subtypes = self.subtypes(kind);
+            for subtype in subtypes {
+                self.variant(subtype, variants, query_names);
+            }
+            return;
+        }
+
+        let ty = type_name(kind);
+        self.enqueue(kind);
+        variants.push_str(&format!("    {}({}),\n", ty, ty));
+        query_names.extend(variant_query_names);
+    }
+
+    /// Named subtypes of a supertype, empty for other node kinds
+    fn subtypes(&self, kind: &str) -> Vec<&'a str> {
+        self.node_types
+            .get(kind)
+            .copied()
+            .and_then(|node_type| node_type.subtypes.as_ref())
+            .map(|subtypes| {
+                subtypes
+                    .iter()
+                    .filter(|subtype| subtype.named)
+                    .map(|subtype| subtype.kind.as_str())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Captures matched by a node kind, supertypes are expanded into their subtypes
+    fn query_names(&self, kind: &str) -> Vec<String> {
+        let mut names = vec![];
+        let mut stack = vec![kind];
+        let mut visited = HashSet::new();
+        while let Some(kind) = stack.pop() {
+            if !visited.insert(kind) {
+                continue;
+            }
+            match self
+                .node_types
+                .get(kind)
+                .and_then(|node_type| node_type.subtypes.as_ref())
+            {
+                Some(subtypes) => stack.extend(
+                    subtypes
+                        .iter()
+                        .filter(|subtype| subtype.named)
+                        .map(|subtype| subtype.kind.as_str()),
+                ),
+                None => names.push(kind.to_string()),
+            }
+        }
+        names
+    }
+}
+
+/// Types that would shadow the prelude or the symbols of [`crate::core::ast`]
+const RESERVED_TYPES: &[&str] = &[
+    "Box", "Err", "None", "Ok", "Option", "Result", "Self", "Some", "String", "Symbol", "Vec",
+];
+
+/// Convert a node kind to a type name, `_compound_statement` becomes `CompoundStatement`.
+fn type_name(kind: &str) -> String {
+    let name = kind
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect::<String>();
+    match RESERVED_TYPES.contains(&name.as_str()) {
+        true => format!("{}Node", name),
+        false => name,
+    }
+}
+
+/// Convert a tree-sitter field to a field name, keywords are escaped.
+fn field_name(field: &str) -> String {
+    match field {
+        "crate" | "self" | "super" | "Self" => format!("{}_", field),
+        "abstract" | "as" | "async" | "await" | "become" | "box" | "break" | "const"
+        | "continue" | "do" | "dyn" | "else" | "enum" | "extern" | "false" | "final" | "fn"
+        | "for" | "gen" | "if" | "impl" | "in" | "let" | "loop" | "macro" | "match" | "mod"
+        | "move" | "mut" | "override" | "priv" | "pub" | "ref" | "return" | "static" | "struct"
+        | "trait" | "true" | "try" | "type" | "typeof" | "unsafe" | "unsized" | "use"
+        | "virtual" | "where" | "while" | "yield" => format!("r#{}", field),
+        _ => field.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static NODE_TYPES: &str = r#"[
+        {
+            "type": "_statement",
+            "named": true,
+            "subtypes": [
+                { "type": "function", "named": true },
+                { "type": "return", "named": true }
+            ]
+        },
+        {
+            "type": "module",
+            "named": true,
+            "fields": {},
+            "children": {
+                "multiple": true,
+                "required": false,
+                "types": [{ "type": "_statement", "named": true }]
+            }
+        },
+        {
+            "type": "function",
+            "named": true,
+            "fields": {
+                "name": {
+                    "multiple": false,
+                    "required": true,
+                    "types": [{ "type": "identifier", "named": true }]
+                },
+                "type": {
+                    "multiple": false,
+                    "required": false,
+                    "types": [
+                        { "type": "identifier", "named": true },
+                        { "type": "string", "named": true }
+                    ]
+                },
+                "body": {
+                    "multiple": true,
+                    "required": true,
+                    "types": [
+                        { "type": ":", "named": false },
+                        { "type": "_statement", "named": true }
+                    ]
+                }
+            }
+        },
+        {
+            "type": "return",
+            "named": true,
+            "fields": {
+                "operator": {
+                    "multiple": false,
+                    "required": true,
+                    "types": [{ "type": "return", "named": false }]
+                },
+                "value": {
+                    "multiple": false,
+                    "required": false,
+                    "types": [
+                        { "type": "function", "named": true },
+                        { "type": "_statement", "named": true }
+                    ]
+                }
+            }
+        },
+        { "type": "identifier", "named": true },
+        { "type": "string", "named": true },
+        { "type": "unused", "named": true },
+        { "type": "return", "named": false }
+    ]"#;
+
+    #[test]
+    fn generate_query() {
+        let generated = generate(NODE_TYPES, "module").unwrap();
+
+        assert_eq!(
+            generated.query,
+            "(module) @module\n(function) @function\n(return) @return\n(identifier) @identifier\n(string) @string\n"
+        );
+    }
+
+    #[test]
+    fn generate_ast() {
+        let generated = generate(NODE_TYPES, "module").unwrap();
+
+        assert_eq!(
+            generated.ast,
+            r#"use auto_lsp::core::ast::*;
+use auto_lsp::{choice, seq};
+
+#[seq(node_kind = "module", kind(symbol()))]
+pub struct Module {
+    pub children: Vec<Statement>,
+}
+
+#[choice]
+pub enum Statement {
+    Function(Function),
+    Return(Return),
+}
+
+#[choice]
+pub enum FunctionType {
+    Identifier(Identifier),
+    StringNode(StringNode),
+}
+
+#[seq(node_kind = "function", kind(symbol()))]
+pub struct Function {
+    #[ast(field = "body")]
+    pub body: Vec<Statement>,
+    #[ast(field = "name")]
+    pub name: Identifier,
+    #[ast(field = "type")]
+    pub r#type: Option<FunctionType>,
+}
+
+#[choice]
+pub enum ReturnValue {
+    Function(Function),
+    Return(Return),
+}
+
+#[seq(node_kind = "return", kind(symbol()))]
+pub struct Return {
+    #[ast(field = "value")]
+    pub value: Option<ReturnValue>,
+}
+
+#[seq(node_kind = "identifier", kind(symbol()))]
+pub struct Identifier {}
+
+#[seq(node_kind = "string", kind(symbol()))]
+pub struct StringNode {}
+"#
+        );
+    }
+
+    #[test]
+    fn unknown_root() {
+        assert!(generate(NODE_TYPES, "program").is_err());
+    }
+}
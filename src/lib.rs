@@ -1,4 +1,6 @@
 #![allow(rustdoc::private_intra_doc_links)]
+// The AST generated from tree-sitter-python in the codegen tests is deeply nested
+#![cfg_attr(test, recursion_limit = "256")]
 //! # Auto LSP
 //!
 //! A Rust crate for creating [Abstract Syntax Trees](https://en.wikipedia.org/wiki/Abstract_syntax_tree) (AST)
@@ -44,20 +46,26 @@
 //! Now that you have your AST defined, you can:
 //!  - Implement the [LSP traits](core::ast) and create a LSP server (with the `lsp_server` feature).
 //!  - Add your own logic for testing purposes, code_generation, etc.
+//!  - Traverse the AST with [`children`](core::ast::AstSymbol::children) or the visitors generated by [`visitor!`].
 //!
 //! You can find more examples in the `tests` folder.
 //!
 //! ## Features
-//! - `assertions`: Enable compile-time checks for conflicting queries.
+//! - `assertions`: Enable compile-time checks for conflicting queries, and a generated test checking the core query against the symbols.
+//! - `codegen`: Enable the generation of the core query and AST from a `node-types.json` file, see [`codegen::generate`].
 //! - `deadlock_detection`: Enable [`parking_lot`]'s deadlock detection (not compatible with `wasm`).
 //! - `log`: Enable logging. (uses [`stderrlog`])
 //! - `lsp_server`: Enable the LSP server (uses [`lsp_server`]).
 //! - `python_test`: Enable the python workspace mock for testing purposes.
 //! - `rayon`: Enable [`rayon`] support (not compatible with `wasm`).
+//! - `serde`: Enable the serialization of the AST to JSON, see [`SymbolJson`](core::ast::SymbolJson).
 //! - `wasm`: Enable wasm support.
 
 #[cfg(doc)]
 use lsp_server;
+/// Generation of the core query and AST definitions from a grammar (enabled with feature `codegen`)
+#[cfg(any(feature = "codegen", test))]
+pub mod codegen;
 /// A mock python workspace used for testing purposes.
 /// This module is only available with the `python_test` feature enabled or during tests.
 #[cfg(any(feature = "python_test", test))]
@@ -90,6 +98,8 @@ pub use lsp_types;
 pub use parking_lot;
 #[cfg(feature = "rayon")]
 pub use rayon;
+#[cfg(feature = "serde")]
+pub use serde_json;
 #[cfg(any(feature = "lsp_server", test))]
 pub use texter;
 pub use tree_sitter;
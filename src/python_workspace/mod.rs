@@ -78,16 +78,16 @@ struct Module {
 
 impl BuildCodeLens for Module {
     fn build_code_lens(&self, doc: &Document, acc: &mut Vec<lsp_types::CodeLens>) {
-        for function in &self.functions {
-            function.read().build_code_lens(doc, acc);
+        for child in self.children() {
+            child.read().build_code_lens(doc, acc);
         }
     }
 }
 
 impl BuildInlayHints for Module {
     fn build_inlay_hint(&self, doc: &Document, acc: &mut Vec<auto_lsp::lsp_types::InlayHint>) {
-        for function in &self.functions {
-            function.read().build_inlay_hint(doc, acc);
+        for child in self.children() {
+            child.read().build_inlay_hint(doc, acc);
         }
     }
 }
@@ -100,8 +100,8 @@ impl BuildDocumentSymbols for Module {
 
 impl BuildSemanticTokens for Module {
     fn build_semantic_tokens(&self, doc: &Document, builder: &mut auto_lsp_core::semantic_tokens::SemanticTokensBuilder) {
-        for function in &self.functions {
-            function.read().build_semantic_tokens(doc, builder);
+        for child in self.children() {
+            child.read().build_semantic_tokens(doc, builder);
         }
     }
 }
@@ -120,8 +120,18 @@ impl BuildSemanticTokens for Module {
             token_type_index = "Function"
         )
     ),
+    lsp_hover_info(code_gen(language = "python")),
     lsp_inlay_hints(user),
     lsp_code_lens(user),
+    lsp_completion_items(
+        code_gen(
+            item(
+                label = self::name,
+                kind = auto_lsp::lsp_types::CompletionItemKind::FUNCTION,
+                snippet(placeholders(self::parameters))
+            )
+        )
+    ),
     comment(user),
     scope(user)
 )))]
@@ -132,7 +142,8 @@ struct Function {
 
 impl Scope for Function {
     fn get_scope_range(&self) -> Vec<[usize; 2]> {
-        vec![]
+        let range = self.get_range();
+        vec![[range.start, range.end]]
     }
 }
 
@@ -205,7 +216,14 @@ enum Parameter {
 struct UntypedParameter {}
 
 #[seq(query_name = "typed_parameter", kind(symbol(
-    
+    lsp_completion_items(
+        code_gen(
+            item(
+                label = self::name,
+                kind = auto_lsp::lsp_types::CompletionItemKind::VARIABLE
+            )
+        )
+    )
 )))]
 struct TypedParameter {
     name: Identifier,
@@ -213,6 +231,15 @@ struct TypedParameter {
 }
 
 #[seq(query_name = "typed_default_parameter", kind(symbol(
+    lsp_completion_items(
+        code_gen(
+            item(
+                label = self::name,
+                kind = auto_lsp::lsp_types::CompletionItemKind::VARIABLE
+            )
+        )
+    ),
+    comment(code_gen(position = "trailing")),
     check(user)
 )))]
 struct TypedDefaultParameter {
@@ -329,6 +356,8 @@ pub fn create_python_workspace(uri: Url, source_code: String) -> Workspace {
     let document = Document {
         document: Text::new(source_code.into()),
         cst: tree,
+        comment_style: parse.tree_sitter.comment_style,
+        position_encoding: auto_lsp::lsp_types::PositionEncodingKind::UTF8,
     };
 
     let mut errors = vec![];
@@ -342,6 +371,8 @@ pub fn create_python_workspace(uri: Url, source_code: String) -> Workspace {
         diagnostics: &mut errors,
         unsolved_checks: &mut unsolved_checks,
         unsolved_references: &mut unsolved_references,
+        changes: vec![],
+        lenient: false,
     };
 
     let ast_parser = parse.ast_parser;
@@ -1,86 +1,433 @@
-use lsp_types::{CompletionParams, CompletionResponse, CompletionTriggerKind};
-use streaming_iterator::StreamingIterator;
-use tree_sitter::{Query, QueryCursor};
+use std::collections::HashSet;
+
+use auto_lsp_core::{ast::DynSymbol, workspace::Workspace};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionParams, CompletionResponse, Documentation,
+    HoverContents, InsertTextFormat, MarkedString, MarkupContent, MarkupKind, Url,
+};
+use serde::{Deserialize, Serialize};
+use tree_sitter::Node;
 
 use crate::server::session::{Session, WORKSPACES};
 
 impl Session {
     /// Get completion items for a document.
     ///
-    /// TODO: Incomplete implementation
+    /// Completion items are collected from the symbols visible at the cursor position,
+    /// then filtered against the word under the cursor, see [`get_completion_items`].
+    ///
+    /// Keywords and operators valid at the cursor position are added afterwards, see [`get_keyword_items`].
     pub fn get_completion_items(
         &mut self,
         params: CompletionParams,
     ) -> anyhow::Result<Option<CompletionResponse>> {
-        let mut results = vec![];
         let uri = &params.text_document_position.text_document.uri;
-        match params.context {
-            Some(context) => match context.trigger_kind {
-                CompletionTriggerKind::INVOKED => {
-                    let workspace = WORKSPACES.lock();
-
-                    let workspace = workspace
-                        .get(uri)
-                        .ok_or(anyhow::anyhow!("Workspace not found"))?;
-
-                    let offset = workspace
-                        .document
-                        .descendant_at_position(params.text_document_position.position)
-                        .unwrap()
-                        .start_byte();
-
-                    let source_code = workspace.document.document.text.as_str();
-                    let content_bytes = source_code.as_bytes();
-
-                    // Find the start of the word at the position
-                    let mut start = offset;
-                    while start > 0 && (content_bytes[start - 1] as char).is_alphanumeric() {
-                        start -= 1;
-                    }
-
-                    // Find the end of the word at the position
-                    let mut end = offset;
-                    while end < content_bytes.len()
-                        && (content_bytes[end] as char).is_alphanumeric()
-                    {
-                        end += 1;
-                    }
-
-                    let word = &source_code[start..end];
-
-                    let query = Query::new(
-                        &workspace.parsers.tree_sitter.language,
-                        &format!("((identifier) @id (#match? @id \"^{}+\"))", word),
-                    )
-                    .unwrap();
 
-                    let mut cursor = QueryCursor::new();
-                    let mut captures = cursor.captures(
-                        &query,
-                        workspace.document.cst.root_node(),
-                        workspace.document.document.text.as_bytes(),
-                    );
-
-                    while let Some((m, capture_index)) = captures.next() {
-                        let capture = m.captures[*capture_index];
-
-                        workspace
-                            .ast
-                            .iter()
-                            .filter_map(|x| x.read().find_at_offset(capture.node.start_byte()))
-                            .for_each(|x| {
-                                x.read()
-                                    .build_completion_items(&mut results, &workspace.document);
-                            });
-                    }
+        let workspace = WORKSPACES.lock();
+
+        let workspace = workspace
+            .get(uri)
+            .ok_or(anyhow::anyhow!("Workspace not found"))?;
+
+        let offset = match workspace
+            .document
+            .offset_at(params.text_document_position.position)
+        {
+            Some(offset) => offset,
+            None => return Ok(None),
+        };
+
+        // Whether the request was invoked, triggered by a character or re-triggered, all candidates are collected.
+        // After a trigger character the word under the cursor is empty, so no filtering happens.
+        let mut items = get_completion_items(workspace, offset);
+
+        let parsers = workspace.parsers;
+        let tokens = self
+            .anonymous_tokens
+            .entry(parsers.name)
+            .or_insert_with(|| get_anonymous_tokens(parsers.tree_sitter.node_types));
+
+        let labels = items
+            .iter()
+            .map(|item| item.label.clone())
+            .collect::<HashSet<_>>();
+        items.extend(
+            get_keyword_items(workspace, offset, tokens)
+                .into_iter()
+                .filter(|item| !labels.contains(&item.label)),
+        );
+
+        let snippet_support = self
+            .client_capabilities
+            .text_document
+            .as_ref()
+            .and_then(|t| t.completion.as_ref())
+            .and_then(|c| c.completion_item.as_ref())
+            .and_then(|c| c.snippet_support)
+            .unwrap_or(false);
+
+        // Snippets are replaced by the label when the client does not support them
+        if !snippet_support {
+            items
+                .iter_mut()
+                .filter(|item| item.insert_text_format == Some(InsertTextFormat::SNIPPET))
+                .for_each(|item| {
+                    item.insert_text = None;
+                    item.insert_text_format = None;
+                });
+        }
+
+        Ok(Some(items.into()))
+    }
+
+    /// Resolve a completion item previously sent to the client.
+    ///
+    /// The documentation of the item is computed from the hover information of the symbol that created it,
+    /// or from its comment if there's no hover information.
+    pub fn resolve_completion_item(
+        &mut self,
+        mut item: CompletionItem,
+    ) -> anyhow::Result<CompletionItem> {
+        let data = match item.data.take() {
+            Some(data) => serde_json::from_value::<CompletionData>(data)?,
+            None => return Ok(item),
+        };
+
+        let workspace = WORKSPACES.lock();
+
+        let workspace = workspace
+            .get(&data.uri)
+            .ok_or(anyhow::anyhow!("Workspace not found"))?;
+
+        if item.documentation.is_none() {
+            item.documentation = get_documentation(workspace, data.offset);
+        }
+        Ok(item)
+    }
+}
+
+/// Data attached to completion items, used to find the symbol back when the item is resolved.
+#[derive(Serialize, Deserialize)]
+struct CompletionData {
+    uri: Url,
+    offset: usize,
+}
+
+/// Collect the completion items visible at the given offset.
+///
+/// Scopes are walked from the innermost one (using [`auto_lsp_core::ast::AstSymbol::get_parent_scope`]) up to the root of the AST,
+/// which acts as the global scope.
+///
+/// In each scope, every symbol declared within [`auto_lsp_core::ast::Scope::get_scope_range`] whose nearest scope is the current one
+/// is asked for completion items with [`auto_lsp_core::ast::BuildCompletionItems`].
+/// These symbols are found by walking the children of the scope in each range without entering nested scopes,
+/// each symbol is asked for completion items once.
+///
+/// Items are then filtered against the word under the cursor:
+/// - Items starting with the word come first.
+/// - Items matching the word as a fuzzy (case insensitive) subsequence come next.
+///
+/// When two items share the same label, the one from the innermost scope wins.
+pub(crate) fn get_completion_items(workspace: &Workspace, offset: usize) -> Vec<CompletionItem> {
+    let root = match &workspace.ast {
+        Some(root) => root,
+        None => return vec![],
+    };
+
+    let document = &workspace.document;
+    let source_code = document.document.text.as_str();
+    let offset = offset.min(source_code.len());
+
+    let word = &source_code[word_start(source_code, offset)..offset];
+
+    // Scopes from the innermost to the outermost one
+    let mut scopes = vec![];
+    if let Some(symbol) = root.read().find_at_offset(offset) {
+        let read = symbol.read();
+        let mut curr = match read.is_scope() {
+            true => Some(symbol.clone()),
+            false => read.get_parent_scope(),
+        };
+        drop(read);
+        while let Some(scope) = curr {
+            curr = scope.read().get_parent_scope();
+            scopes.push(scope);
+        }
+    }
+
+    let mut items = vec![];
+    // Ranges of a scope may overlap, symbols are only asked for items once
+    let mut offered = HashSet::new();
+
+    for scope in scopes.iter().map(Some).chain([None]) {
+        let scope_symbol = scope.unwrap_or(root);
+        let ranges = match scope {
+            Some(scope) => scope.read().get_scope_range(),
+            None => {
+                let range = root.read().get_range();
+                vec![[range.start, range.end]]
+            }
+        };
+
+        for range in ranges {
+            // Children are pushed in reverse order so that symbols are visited in document order
+            let mut symbols = children_in_order(scope_symbol);
+
+            while let Some(symbol) = symbols.pop() {
+                let read = symbol.read();
+                let symbol_range = read.get_range();
+                if symbol_range.end < range[0] || symbol_range.start > range[1] {
+                    continue;
                 }
-                CompletionTriggerKind::TRIGGER_CHARACTER => {
-                    // Todo
+
+                // Symbols declared in nested scopes are not visible from this scope,
+                // but the nested scopes themselves are.
+                if !read.is_scope() {
+                    symbols.extend(children_in_order(&symbol));
                 }
-                _ => {}
-            },
-            None => return Ok(None),
+
+                if symbol_range.start < range[0] {
+                    continue;
+                }
+
+                let is_visible = match (read.get_parent_scope(), scope) {
+                    (Some(parent), Some(scope)) => parent.ptr_eq(scope),
+                    (None, None) => true,
+                    _ => false,
+                };
+                if is_visible && offered.insert(read.get_id()) {
+                    let len = items.len();
+                    read.build_completion_items(&mut items, document);
+
+                    let data = serde_json::to_value(CompletionData {
+                        uri: read.get_url().as_ref().clone(),
+                        offset: symbol_range.start,
+                    })
+                    .unwrap();
+                    items[len..]
+                        .iter_mut()
+                        .filter(|item| item.data.is_none())
+                        .for_each(|item| item.data = Some(data.clone()));
+                }
+            }
+        }
+    }
+
+    let mut labels = HashSet::new();
+    let mut ranked = vec![];
+    for item in items {
+        if labels.contains(&item.label) {
+            continue;
+        }
+        let rank = if item.label.starts_with(word) {
+            0
+        } else if is_fuzzy_match(&item.label, word) {
+            1
+        } else {
+            continue;
+        };
+        labels.insert(item.label.clone());
+        ranked.push((rank, item));
+    }
+    ranked.sort_by_key(|(rank, _)| *rank);
+    ranked.into_iter().map(|(_, item)| item).collect()
+}
+
+/// Get the children of a symbol in reverse document order, to be popped from a stack.
+fn children_in_order(symbol: &DynSymbol) -> Vec<DynSymbol> {
+    let mut children = symbol.read().children().collect::<Vec<_>>();
+    children.sort_by_key(|child| std::cmp::Reverse(child.read().get_range().start));
+    children
+}
+
+/// Find the start of the word ending at the given offset.
+fn word_start(source_code: &str, offset: usize) -> usize {
+    source_code[..offset]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| c.is_alphanumeric() || *c == '_')
+        .last()
+        .map_or(offset, |(index, _)| index)
+}
+
+/// Entry of the `node-types.json` file of a grammar.
+#[derive(Deserialize)]
+struct NodeType {
+    #[serde(rename = "type")]
+    kind: String,
+    named: bool,
+}
+
+/// Parse the `node-types.json` file of a grammar and return the anonymous tokens (keywords, operators, punctuation).
+pub(crate) fn get_anonymous_tokens(node_types: &str) -> HashSet<String> {
+    match serde_json::from_str::<Vec<NodeType>>(node_types) {
+        Ok(node_types) => node_types
+            .into_iter()
+            .filter(|node_type| !node_type.named)
+            .map(|node_type| node_type.kind)
+            .collect(),
+        Err(e) => {
+            log::warn!("Failed to parse node types: {}", e);
+            HashSet::new()
+        }
+    }
+}
+
+/// Collect the keywords and operators that are syntactically valid at the given offset.
+///
+/// The parse state is taken from the last token before the word under the cursor,
+/// or from the first token after it when there is none.
+///
+/// The [`tree_sitter::LookaheadIterator`] of this state lists the valid symbols,
+/// only anonymous tokens found in `tokens` are kept:
+/// - Word-like tokens are offered as keywords if they start with the word under the cursor.
+/// - Other tokens are offered as operators if the word under the cursor is empty.
+pub(crate) fn get_keyword_items(
+    workspace: &Workspace,
+    offset: usize,
+    tokens: &HashSet<String>,
+) -> Vec<CompletionItem> {
+    let document = &workspace.document;
+    let source_code = document.document.text.as_str();
+    let offset = offset.min(source_code.len());
+    let start = word_start(source_code, offset);
+    let word = &source_code[start..offset];
+
+    let (previous, next) = surrounding_leaves(document.cst.root_node(), start);
+    let state = match (previous, next) {
+        (Some(previous), _) if !previous.is_error() && !previous.is_missing() => {
+            previous.next_parse_state()
+        }
+        (_, Some(next)) => next.parse_state(),
+        _ => return vec![],
+    };
+
+    let mut lookahead = match workspace
+        .parsers
+        .tree_sitter
+        .language
+        .lookahead_iterator(state)
+    {
+        Some(lookahead) => lookahead,
+        None => return vec![],
+    };
+
+    let mut items: Vec<CompletionItem> = vec![];
+    let mut labels = HashSet::new();
+    for name in lookahead.iter_names() {
+        if !tokens.contains(name) || labels.contains(name) {
+            continue;
+        }
+
+        let is_keyword = name.starts_with(|c: char| c.is_alphabetic())
+            && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+
+        let kind = match is_keyword {
+            true if name.starts_with(word) => CompletionItemKind::KEYWORD,
+            false if word.is_empty() => CompletionItemKind::OPERATOR,
+            _ => continue,
         };
-        Ok(Some(results.into()))
+
+        labels.insert(name);
+        items.push(CompletionItem {
+            label: name.to_string(),
+            kind: Some(kind),
+            ..Default::default()
+        });
+    }
+    items
+}
+
+/// Find the last leaf ending before `offset` and the first leaf starting at or after `offset`.
+///
+/// Extras (such as comments) are skipped.
+fn surrounding_leaves(root: Node<'_>, offset: usize) -> (Option<Node<'_>>, Option<Node<'_>>) {
+    let mut previous = None;
+    let mut next = None;
+
+    let mut cursor = root.walk();
+    let mut nodes = vec![root];
+    while let Some(node) = nodes.pop() {
+        if next.is_some() {
+            break;
+        }
+        if node.is_extra() {
+            continue;
+        }
+        if node.child_count() == 0 {
+            if node.end_byte() <= offset && node.start_byte() < node.end_byte() {
+                previous = Some(node);
+            } else if node.start_byte() >= offset && next.is_none() {
+                next = Some(node);
+            }
+            continue;
+        }
+        // Children are pushed in reverse order so that nodes are visited in document order
+        let children = node.children(&mut cursor).collect::<Vec<_>>();
+        nodes.extend(children.into_iter().rev());
+    }
+    (previous, next)
+}
+
+/// Get the documentation of the symbol that created a completion item.
+///
+/// The symbol and its parents starting at the same offset are searched for hover information first, then for a comment.
+pub(crate) fn get_documentation(workspace: &Workspace, offset: usize) -> Option<Documentation> {
+    let document = &workspace.document;
+    let source_code = document.document.text.as_bytes();
+
+    let mut symbols = vec![];
+    let mut curr = workspace.ast.as_ref()?.read().find_at_offset(offset);
+    while let Some(symbol) = curr {
+        if symbol.read().get_range().start != offset {
+            break;
+        }
+        curr = symbol
+            .read()
+            .get_parent()
+            .and_then(|parent| parent.to_dyn());
+        symbols.push(symbol);
+    }
+
+    if let Some(hover) = symbols.iter().find_map(|s| s.read().get_hover(document)) {
+        return Some(match hover.contents {
+            HoverContents::Markup(markup) => Documentation::MarkupContent(markup),
+            HoverContents::Scalar(string) => Documentation::MarkupContent(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: marked_string_to_markdown(string),
+            }),
+            HoverContents::Array(strings) => Documentation::MarkupContent(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: strings
+                    .into_iter()
+                    .map(marked_string_to_markdown)
+                    .collect::<Vec<_>>()
+                    .join("\n\n"),
+            }),
+        });
+    }
+
+    symbols
+        .iter()
+        .find_map(|s| {
+            s.read()
+                .get_comment(source_code)
+                .map(|comment| document.comment_style.strip(comment))
+        })
+        .map(Documentation::String)
+}
+
+fn marked_string_to_markdown(string: MarkedString) -> String {
+    match string {
+        MarkedString::String(s) => s,
+        MarkedString::LanguageString(s) => format!("```{}\n{}\n```", s.language, s.value),
     }
 }
+
+/// Check if all characters of `word` appear in `label` in the same order (case insensitive).
+fn is_fuzzy_match(label: &str, word: &str) -> bool {
+    let mut label = label.chars().flat_map(char::to_lowercase);
+    word.chars()
+        .flat_map(char::to_lowercase)
+        .all(|c| label.any(|l| l == c))
+}
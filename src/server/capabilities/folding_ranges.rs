@@ -37,12 +37,15 @@ impl Session {
                 "fold.comment" => FoldingRangeKind::Comment,
                 _ => FoldingRangeKind::Region,
             };
-            let range = capture.node.range();
+            let range = match workspace.document.range_at(capture.node.byte_range()) {
+                Some(range) => range,
+                None => continue,
+            };
             ranges.push(FoldingRange {
-                start_line: range.start_point.row as u32,
-                start_character: Some(range.start_point.column as u32),
-                end_line: range.end_point.row as u32,
-                end_character: Some(range.end_point.column as u32),
+                start_line: range.start.line,
+                start_character: Some(range.start.character),
+                end_line: range.end.line,
+                end_character: Some(range.end.character),
                 kind: Some(kind),
                 collapsed_text: None,
             });
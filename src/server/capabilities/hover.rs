@@ -4,6 +4,10 @@ use crate::server::session::{Session, WORKSPACES};
 
 impl Session {
     /// Request to get hover information for a symbol at a position
+    ///
+    /// Only the symbol at the position is asked, parents are not tried so that hovering
+    /// whitespace or punctuation does not show the hover of the enclosing symbol.
+    /// References forward the request to their target, see the `lsp_hover_info` feature.
     pub fn get_hover(&mut self, params: HoverParams) -> anyhow::Result<Option<Hover>> {
         let uri = &params.text_document_position_params.text_document.uri;
         let workspace = WORKSPACES.lock();
@@ -1,14 +1,22 @@
 use lsp_types::DidOpenTextDocumentParams;
 
-use crate::server::session::Session;
+use crate::server::session::{workspace::get_extension, Session};
 
 impl Session {
     /// Request when a document is opened
     ///
     /// Since auto_lsp already reads all files at initialization and uses [`lsp_types::notification::DidChangeWatchedFiles`] to track changes,
-    /// this request is not necessary.
-    pub fn open_text_document(&mut self, _params: DidOpenTextDocumentParams) -> anyhow::Result<()> {
-        // Todo ?
+    /// this request is only used to build documents served from the index cache.
+    pub fn open_text_document(&mut self, params: DidOpenTextDocumentParams) -> anyhow::Result<()> {
+        let uri = &params.text_document.uri;
+
+        // The deferred entry is removed once the document is added
+        if self.is_deferred(uri) {
+            let extension = get_extension(uri).ok_or_else(|| {
+                anyhow::format_err!("Missing file extension for document {}", uri)
+            })?;
+            self.add_document(uri, &extension, &params.text_document.text)?;
+        }
         Ok(())
     }
 }
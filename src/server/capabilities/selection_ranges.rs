@@ -44,8 +44,8 @@ impl Session {
             }
 
             let mut parent: Option<SelectionRange> = None;
-            for _node in stack {
-                let range = match workspace.document.range_at(offset) {
+            for node in stack {
+                let range = match workspace.document.range_at(node.byte_range()) {
                     Some(range) => range,
                     None => continue,
                 };
@@ -18,7 +18,7 @@ impl Session {
                 let uri = &file.uri;
                 let workspace = WORKSPACES.lock();
 
-                if workspace.contains_key(&uri) {
+                if workspace.contains_key(&uri) || self.is_deferred(uri) {
                     // The file is already in the workspace
                     // We can ignore this change
                     return Ok(());
@@ -33,6 +33,9 @@ impl Session {
             }
             FileChangeType::CHANGED => {
                 let uri = &file.uri;
+                // Documents served from the index cache are built with the new content
+                self.ensure_document(uri)?;
+
                 let mut workspace = WORKSPACES.lock();
                 let file_path = uri.to_file_path().unwrap();
                 let mut open_file = File::open(file_path)?;
@@ -63,6 +66,9 @@ impl Session {
                 let mut workspace = WORKSPACES.lock();
                 let uri = &file.uri;
                 workspace.remove(uri);
+                if let Some(cache) = &mut self.index_cache {
+                    cache.deferred.remove(uri);
+                }
                 Ok(())
             }
             // Should never happen
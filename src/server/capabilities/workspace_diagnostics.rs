@@ -7,16 +7,26 @@ use crate::server::session::{Session, WORKSPACES};
 
 impl Session {
     /// Get diagnostics for all documents.
+    ///
+    /// Documents served from the index cache return their cached diagnostics.
     pub fn get_workspace_diagnostics(
         &mut self,
         _params: WorkspaceDiagnosticParams,
     ) -> anyhow::Result<WorkspaceDiagnosticReport> {
         let workspaces = WORKSPACES.lock();
 
+        let deferred = self.index_cache.iter().flat_map(|cache| {
+            cache
+                .deferred
+                .iter()
+                .map(|(uri, entry)| (uri, entry.diagnostics.clone()))
+        });
+
         let result: Vec<lsp_types::WorkspaceDocumentDiagnosticReport> = workspaces
             .iter()
-            .map(|(uri, workspace)| {
-                let errors = workspace.errors.clone();
+            .map(|(uri, workspace)| (uri, workspace.errors.clone()))
+            .chain(deferred)
+            .map(|(uri, errors)| {
                 WorkspaceDocumentDiagnosticReport::Full(WorkspaceFullDocumentDiagnosticReport {
                     version: None,
                     full_document_diagnostic_report: FullDocumentDiagnosticReport {
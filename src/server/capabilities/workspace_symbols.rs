@@ -1,5 +1,8 @@
 use auto_lsp_core::ast::VecOrSymbol;
-use lsp_types::{Location, OneOf, WorkspaceSymbol, WorkspaceSymbolParams, WorkspaceSymbolResponse};
+use lsp_types::{
+    DocumentSymbol, Location, OneOf, Url, WorkspaceSymbol, WorkspaceSymbolParams,
+    WorkspaceSymbolResponse,
+};
 
 use crate::server::session::{Session, WORKSPACES};
 
@@ -8,6 +11,8 @@ impl Session {
     ///
     /// This function will return all symbols found in the workspace recursively by calling the inner [`Session::get_document_symbols`]
     /// of every documents.
+    ///
+    /// Documents served from the index cache return their cached symbols.
     pub fn get_workspace_symbols(
         &mut self,
         params: WorkspaceSymbolParams,
@@ -27,36 +32,41 @@ impl Session {
                 ast.iter()
                     .filter_map(|p| p.read().get_document_symbols(&v.document))
                     .flat_map(|p| match p {
-                        VecOrSymbol::Symbol(s) => vec![WorkspaceSymbol {
-                            name: s.name,
-                            kind: s.kind,
-                            tags: None,
-                            container_name: None,
-                            location: OneOf::Left(Location {
-                                uri: uri.to_owned(),
-                                range: s.range,
-                            }),
-                            data: None,
-                        }],
+                        VecOrSymbol::Symbol(s) => vec![to_workspace_symbol(uri, s)],
                         VecOrSymbol::Vec(v) => v
                             .into_iter()
-                            .map(|s| WorkspaceSymbol {
-                                name: s.name,
-                                kind: s.kind,
-                                tags: None,
-                                container_name: None,
-                                location: OneOf::Left(Location {
-                                    uri: uri.to_owned(),
-                                    range: s.range,
-                                }),
-                                data: None,
-                            })
+                            .map(|s| to_workspace_symbol(uri, s))
                             .collect::<Vec<_>>(),
                     })
                     .collect::<Vec<_>>(),
             );
         });
 
+        if let Some(cache) = &self.index_cache {
+            cache.deferred.iter().for_each(|(uri, entry)| {
+                symbols.extend(
+                    entry
+                        .document_symbols
+                        .iter()
+                        .map(|s| to_workspace_symbol(uri, s.clone())),
+                );
+            });
+        }
+
         Ok(Some(WorkspaceSymbolResponse::Nested(symbols)))
     }
 }
+
+fn to_workspace_symbol(uri: &Url, symbol: DocumentSymbol) -> WorkspaceSymbol {
+    WorkspaceSymbol {
+        name: symbol.name,
+        kind: symbol.kind,
+        tags: None,
+        container_name: None,
+        location: OneOf::Left(Location {
+            uri: uri.to_owned(),
+            range: symbol.range,
+        }),
+        data: None,
+    }
+}
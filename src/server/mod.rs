@@ -98,5 +98,6 @@ mod session;
 /// Re-implementations of the [`texter`] crate
 pub mod texter_impl;
 
+pub use session::documents::AstChangeEvent;
 pub use session::init::*;
 pub use session::Session;
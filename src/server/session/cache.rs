@@ -0,0 +1,175 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use auto_lsp_core::{ast::VecOrSymbol, workspace::Workspace};
+use lsp_types::{Diagnostic, DocumentSymbol, Url};
+use serde::{Deserialize, Serialize};
+
+/// Hash bytes with the 64 bits FNV-1a algorithm.
+///
+/// Unlike [`std::hash::DefaultHasher`], the result is stable across runs and compiler versions,
+/// which is required for hashes written to disk.
+pub(crate) fn hash_bytes<'a>(chunks: impl IntoIterator<Item = &'a [u8]>) -> u64 {
+    const OFFSET: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    let mut hash = OFFSET;
+    for chunk in chunks {
+        for byte in chunk {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(PRIME);
+        }
+        // Separator, so that ["ab", "c"] and ["a", "bc"] do not collide
+        hash ^= 0xff;
+        hash = hash.wrapping_mul(PRIME);
+    }
+    hash
+}
+
+/// Serialized summary of a document, written to the index cache.
+///
+/// An entry is only valid if both the content hash and the parser hash match the current ones.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct CacheEntry {
+    pub url: Url,
+    pub content_hash: u64,
+    pub parser_hash: u64,
+    pub document_symbols: Vec<DocumentSymbol>,
+    pub diagnostics: Vec<Diagnostic>,
+    /// Names of the top level document symbols.
+    pub exported_names: Vec<String>,
+}
+
+impl CacheEntry {
+    pub(crate) fn new(url: &Url, source_code: &str, workspace: &Workspace) -> Self {
+        let document_symbols = workspace
+            .ast
+            .iter()
+            .filter_map(|ast| ast.read().get_document_symbols(&workspace.document))
+            .flat_map(|symbols| match symbols {
+                VecOrSymbol::Symbol(s) => vec![s],
+                VecOrSymbol::Vec(v) => v,
+            })
+            .collect::<Vec<_>>();
+
+        Self {
+            url: url.clone(),
+            content_hash: hash_bytes([source_code.as_bytes()]),
+            parser_hash: workspace.parsers.tree_sitter.parser_hash,
+            exported_names: document_symbols.iter().map(|s| s.name.clone()).collect(),
+            document_symbols,
+            diagnostics: workspace.errors.clone(),
+        }
+    }
+}
+
+/// Persistent index cache, enabled with [`crate::server::LspOptions::index_cache`].
+///
+/// Documents with a valid cache entry are not built at startup, they are kept as deferred entries
+/// until the document is opened or modified.
+///
+/// Deferred documents are not built during reference resolution, since references are resolved within each document.
+pub(crate) struct IndexCache {
+    pub directory: PathBuf,
+    /// Documents served from the cache whose AST has not been built yet.
+    pub deferred: HashMap<Url, CacheEntry>,
+}
+
+impl IndexCache {
+    pub(crate) fn new(directory: PathBuf) -> Self {
+        Self {
+            directory,
+            deferred: HashMap::new(),
+        }
+    }
+
+    fn entry_path(&self, url: &Url) -> PathBuf {
+        self.directory.join(format!(
+            "{:016x}.json",
+            hash_bytes([url.as_str().as_bytes()])
+        ))
+    }
+
+    /// Load the entry of a document, returns `None` if the entry is missing or outdated.
+    pub(crate) fn load(
+        &self,
+        url: &Url,
+        source_code: &str,
+        parser_hash: u64,
+    ) -> Option<CacheEntry> {
+        let content = fs::read(self.entry_path(url)).ok()?;
+        let entry: CacheEntry = serde_json::from_slice(&content).ok()?;
+
+        if &entry.url != url
+            || entry.parser_hash != parser_hash
+            || entry.content_hash != hash_bytes([source_code.as_bytes()])
+        {
+            return None;
+        }
+        Some(entry)
+    }
+
+    /// Write an entry to the cache directory.
+    pub(crate) fn store(&self, entry: &CacheEntry) -> anyhow::Result<()> {
+        fs::create_dir_all(&self.directory)?;
+        fs::write(self.entry_path(&entry.url), serde_json::to_vec(entry)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(url: &Url, source_code: &str) -> CacheEntry {
+        CacheEntry {
+            url: url.clone(),
+            content_hash: hash_bytes([source_code.as_bytes()]),
+            parser_hash: 42,
+            document_symbols: vec![],
+            diagnostics: vec![],
+            exported_names: vec!["foo".into()],
+        }
+    }
+
+    #[test]
+    fn hash_is_stable() {
+        assert_eq!(hash_bytes([]), 0xcbf29ce484222325);
+        assert_eq!(
+            hash_bytes([b"abc".as_slice()]),
+            hash_bytes([b"abc".as_slice()])
+        );
+        assert_ne!(
+            hash_bytes([b"ab".as_slice(), b"c".as_slice()]),
+            hash_bytes([b"a".as_slice(), b"bc".as_slice()])
+        );
+    }
+
+    #[test]
+    fn roundtrip_and_invalidation() {
+        let directory = std::env::temp_dir().join(format!("auto_lsp_cache_{}", std::process::id()));
+        let cache = IndexCache::new(directory.clone());
+        let url = Url::parse("file:///test.py").unwrap();
+
+        cache.store(&entry(&url, "def foo(): pass")).unwrap();
+
+        assert_eq!(
+            cache.load(&url, "def foo(): pass", 42),
+            Some(entry(&url, "def foo(): pass"))
+        );
+        // Content changed
+        assert_eq!(cache.load(&url, "def bar(): pass", 42), None);
+        // Parser or queries changed
+        assert_eq!(cache.load(&url, "def foo(): pass", 0), None);
+        // Unknown document
+        assert_eq!(
+            cache.load(
+                &Url::parse("file:///other.py").unwrap(),
+                "def foo(): pass",
+                42
+            ),
+            None
+        );
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -1,66 +1,268 @@
-use auto_lsp_core::workspace::Workspace;
+use std::ops::Range;
+
+use auto_lsp_core::{ast::DynSymbol, build::normalize_edits, workspace::Workspace};
 use streaming_iterator::StreamingIterator;
+use tree_sitter::{InputEdit, Node};
 
 use super::Session;
 
 impl Session {
     /// Find all comments in the document and add them to the ast using the comment [`tree_sitter::Query`]
-    ///
-    /// TODO: Add support for incremental updates
     pub(crate) fn add_comments(workspace: &Workspace) -> anyhow::Result<()> {
-        let comments_query = match workspace.parsers.tree_sitter.queries.comments {
-            Some(ref query) => query,
-            None => return Ok(()),
-        };
+        let document = 0..workspace.document.document.text.len();
+        attach_comments(workspace, std::slice::from_ref(&document));
+        Ok(())
+    }
 
+    /// Update comments of the ast after the document was edited.
+    ///
+    /// Only the lines around each edit and the top level symbol containing it are searched,
+    /// comments of symbols located outside of these regions are kept.
+    pub(crate) fn update_comments(
+        workspace: &Workspace,
+        edits: &[(InputEdit, bool)],
+    ) -> anyhow::Result<()> {
         let source_code = workspace.document.document.text.as_bytes();
-        let cst = &workspace.document.cst;
         let ast = match workspace.ast.as_ref() {
             Some(ast) => ast,
             None => return Ok(()),
         };
 
-        let mut cursor = tree_sitter::QueryCursor::new();
-        let mut captures = cursor.captures(comments_query, cst.root_node(), source_code);
+        let regions = normalize_edits(edits)
+            .into_iter()
+            .map(|(range, _)| {
+                // The symbol containing the edit may have been rebuilt, along with its children
+                let range = match top_level_range(ast, range.start) {
+                    Some(symbol) => symbol.start.min(range.start)..symbol.end.max(range.end),
+                    None => range,
+                };
+                expand_to_lines(source_code, range)
+            })
+            .collect::<Vec<_>>();
+
+        attach_comments(workspace, &regions);
+        Ok(())
+    }
+}
+
+/// Get the range of the symbol containing `offset` whose parent is the root symbol.
+fn top_level_range(ast: &DynSymbol, offset: usize) -> Option<Range<usize>> {
+    let mut curr = ast.read().find_at_offset(offset)?;
+    loop {
+        let parent = curr.read().get_parent().and_then(|p| p.to_dyn())?;
+        if parent.ptr_eq(ast) {
+            return Some(curr.read().get_range());
+        }
+        curr = parent;
+    }
+}
+
+/// Expand a range to the line before its start and the line after its end.
+fn expand_to_lines(source_code: &[u8], range: Range<usize>) -> Range<usize> {
+    let start = range.start.min(source_code.len());
+    let end = range.end.clamp(start, source_code.len());
+
+    let mut start = source_code[..start]
+        .iter()
+        .rposition(|b| *b == b'\n')
+        .unwrap_or(0);
+    start = source_code[..start]
+        .iter()
+        .rposition(|b| *b == b'\n')
+        .map_or(0, |p| p + 1);
+
+    let mut end = source_code[end..]
+        .iter()
+        .position(|b| *b == b'\n')
+        .map_or(source_code.len(), |p| end + p + 1);
+    end = source_code[end..]
+        .iter()
+        .position(|b| *b == b'\n')
+        .map_or(source_code.len(), |p| end + p);
+
+    start..end
+}
+
+/// Attach the comments found within the regions to the ast.
+///
+/// Comments previously attached within the regions are removed first.
+fn attach_comments(workspace: &Workspace, regions: &[Range<usize>]) {
+    let comments_query = match workspace.parsers.tree_sitter.queries.comments {
+        Some(ref query) => query,
+        None => return,
+    };
+
+    let ast = match workspace.ast.as_ref() {
+        Some(ast) => ast,
+        None => return,
+    };
+
+    let source_code = workspace.document.document.text.as_bytes();
+    let root = workspace.document.cst.root_node();
+
+    // Find comment nodes, consecutive line comments are merged into a single block
+    let mut blocks: Vec<(Node, Node)> = vec![];
+    let mut cursor = tree_sitter::QueryCursor::new();
+    for region in regions {
+        cursor.set_byte_range(region.clone());
+        let mut captures = cursor.captures(comments_query, root, source_code);
 
         while let Some((m, capture_index)) = captures.next() {
-            let capture = m.captures[*capture_index];
-            // Since a comment is not within a query, we look for the next named sibling
+            let node = m.captures[*capture_index].node;
+            let block = comment_block(node, source_code);
+            if !blocks.iter().any(|(first, _)| first.id() == block.0.id()) {
+                blocks.push(block);
+            }
+        }
+    }
 
-            let next_named_sibling = match capture.node.next_named_sibling() {
-                Some(node) => node,
-                None => continue,
-            };
+    // Remove comments that may no longer be valid
+    for region in regions {
+        reset_comments(ast, root, region);
+    }
+    for (first, last) in &blocks {
+        reset_comments(ast, root, &(first.start_byte()..last.end_byte()));
+    }
+
+    for (first, last) in blocks {
+        let range = first.start_byte()..last.end_byte();
 
-            // We then look if this next sibling exists in the ast
-
-            let node = ast.read().find_at_offset(next_named_sibling.start_byte());
-
-            if let Some(node) = node {
-                let range = capture.node.range();
-                if node.read().is_comment() {
-                    node.write().set_comment(Some(std::ops::Range {
-                        start: range.start_byte,
-                        end: range.end_byte,
-                    }));
-                } else {
-                    match node.read().get_parent() {
-                        Some(parent) => {
-                            let parent = parent.to_dyn().unwrap();
-                            if parent.read().get_range().start == node.read().get_range().start {
-                                if parent.read().is_comment() {
-                                    parent.write().set_comment(Some(std::ops::Range {
-                                        start: range.start_byte,
-                                        end: range.end_byte,
-                                    }));
-                                }
-                            }
-                        }
-                        None => {}
-                    }
+        if !is_first_on_line(first, source_code) {
+            // Trailing comment, attached to the node ending on the same line
+            let previous = match first.prev_named_sibling() {
+                Some(previous) if previous.end_position().row == first.start_position().row => {
+                    previous
                 }
+                _ => continue,
             };
+            if let Some(symbol) = find_commentable(ast, previous.start_byte(), |s| {
+                s.read().comment_position().accepts_trailing()
+            }) {
+                symbol.write().set_comment(Some(range));
+                continue;
+            }
+            // Line comments that follow a trailing comment are not leading comments of the next node
+            continue;
+        }
+
+        // Leading comment, attached to the next named node
+        let next = match last.next_named_sibling() {
+            Some(next) => next,
+            None => continue,
+        };
+
+        if let Some(symbol) = find_commentable(ast, next.start_byte(), |s| {
+            let read = s.read();
+            // A trailing comment takes precedence over a leading one
+            read.comment_position().accepts_leading()
+                && !(read.comment_position().accepts_trailing()
+                    && read.get_data().comment.is_some())
+        }) {
+            symbol.write().set_comment(Some(range));
+        }
+    }
+}
+
+/// Get the first and last comments of the block of consecutive line comments containing `node`.
+///
+/// A comment sharing its line with code is never merged.
+fn comment_block<'a>(node: Node<'a>, source_code: &[u8]) -> (Node<'a>, Node<'a>) {
+    if !is_first_on_line(node, source_code) {
+        return (node, node);
+    }
+
+    let is_consecutive = |above: Node, below: Node| {
+        above.kind_id() == below.kind_id()
+            && above.end_position().row + 1 == below.start_position().row
+            && is_first_on_line(below, source_code)
+            && is_first_on_line(above, source_code)
+    };
+
+    let mut first = node;
+    while let Some(previous) = first.prev_sibling() {
+        if !is_consecutive(previous, first) {
+            break;
+        }
+        first = previous;
+    }
+
+    let mut last = node;
+    while let Some(next) = last.next_sibling() {
+        if !is_consecutive(last, next) {
+            break;
+        }
+        last = next;
+    }
+    (first, last)
+}
+
+/// Check if a node is only preceded by whitespaces on its line.
+fn is_first_on_line(node: Node, source_code: &[u8]) -> bool {
+    let start = node.start_byte();
+    let line_start = start - node.start_position().column;
+    source_code[line_start..start]
+        .iter()
+        .all(|b| b.is_ascii_whitespace())
+}
+
+/// Find the symbol starting at `offset` that accepts the comment.
+///
+/// The deepest symbol at the offset is tried first, then its parents starting at the same offset.
+fn find_commentable(
+    ast: &DynSymbol,
+    offset: usize,
+    accepts: impl Fn(&DynSymbol) -> bool,
+) -> Option<DynSymbol> {
+    let mut curr = ast.read().find_at_offset(offset);
+    while let Some(symbol) = curr {
+        let read = symbol.read();
+        if read.get_range().start != offset {
+            return None;
+        }
+        if read.is_comment() {
+            drop(read);
+            if accepts(&symbol) {
+                return Some(symbol);
+            }
+            curr = symbol.read().get_parent().and_then(|p| p.to_dyn());
+        } else {
+            curr = read.get_parent().and_then(|p| p.to_dyn());
+        }
+    }
+    None
+}
+
+/// Remove comments intersecting the region from the symbols around it.
+fn reset_comments(ast: &DynSymbol, root: Node, region: &Range<usize>) {
+    let intersects = |range: &Range<usize>| range.start <= region.end && region.start <= range.end;
+
+    let mut cursor = root.walk();
+    let mut nodes = vec![root];
+    while let Some(node) = nodes.pop() {
+        if node.end_byte() < region.start {
+            continue;
+        }
+        if node.start_byte() > region.end {
+            // Leading comments belong to the node right after the region
+            if let Some(parent) = node.parent() {
+                if parent.start_byte() > region.end {
+                    continue;
+                }
+            }
+        } else {
+            nodes.extend(node.named_children(&mut cursor));
+        }
+
+        let mut curr = ast.read().find_at_offset(node.start_byte());
+        while let Some(symbol) = curr {
+            if symbol.read().get_range().start != node.start_byte() {
+                break;
+            }
+            let comment = symbol.read().get_data().comment.clone();
+            if comment.is_some_and(|comment| intersects(&comment)) {
+                symbol.write().set_comment(None);
+            }
+            curr = symbol.read().get_parent().and_then(|p| p.to_dyn());
         }
-        Ok(())
     }
 }
@@ -1,18 +1,39 @@
 use std::sync::Arc;
 
 use auto_lsp_core::{
-    build::MainBuilder,
+    build::{AstChange, MainBuilder},
     workspace::{Document, Workspace},
 };
+use crossbeam_channel::Receiver;
 use lsp_types::{DidChangeTextDocumentParams, Url};
 
-use crate::server::session::{lexer::get_tree_sitter_errors, Session};
+use crate::server::session::{cache::CacheEntry, lexer::get_tree_sitter_errors, Session};
 use crate::server::texter_impl::change::WrapChange;
 use crate::server::texter_impl::updateable::WrapTree;
 
 use super::WORKSPACES;
 
+/// Changes made to the AST of a document by an edit, see [`Session::subscribe_ast_changes`]
+#[derive(Clone)]
+pub struct AstChangeEvent {
+    pub url: Url,
+    /// Subtrees replaced by the incremental update, in the order they were rebuilt
+    pub changes: Vec<AstChange>,
+}
+
 impl Session {
+    /// Subscribe to the changes made to the AST of documents after each edit.
+    ///
+    /// An [`AstChangeEvent`] is sent every time an edit replaces parts of an existing AST,
+    /// documents built from scratch do not emit events.
+    ///
+    /// The subscription ends when the receiver is dropped.
+    pub fn subscribe_ast_changes(&mut self) -> Receiver<AstChangeEvent> {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        self.ast_change_subscribers.push(sender);
+        receiver
+    }
+
     /// Add a new document to workspaces
     ///
     /// This will first try to find the correct parser for the language id,
@@ -56,13 +77,21 @@ impl Session {
             .parse(&source_code, None)
             .unwrap();
 
-        get_tree_sitter_errors(&cst.root_node(), source_code, &mut errors);
-
         let document = Document {
             document: text,
             cst,
+            comment_style: tree_sitter.comment_style,
+            position_encoding: self.position_encoding.clone(),
         };
 
+        get_tree_sitter_errors(
+            &document.cst.root_node(),
+            &document,
+            parsers.name,
+            self.init_options.lsp_options.max_syntax_errors,
+            &mut errors,
+        );
+
         let arc_uri = Arc::new(uri.clone());
 
         let mut unsolved_checks = vec![];
@@ -75,6 +104,8 @@ impl Session {
             url: arc_uri.clone(),
             unsolved_checks: &mut unsolved_checks,
             unsolved_references: &mut unsolved_references,
+            changes: vec![],
+            lenient: self.init_options.lsp_options.lenient_build,
         };
         let ast_build = ast_parser(params, None);
 
@@ -112,6 +143,14 @@ impl Session {
 
         Self::add_comments(&workspace)?;
 
+        if let Some(cache) = &mut self.index_cache {
+            cache.deferred.remove(uri);
+            let entry = CacheEntry::new(uri, &workspace.document.document.text, &workspace);
+            if let Err(e) = cache.store(&entry) {
+                log::warn!("Failed to write index cache entry for {}: {}", uri, e);
+            }
+        }
+
         workspaces.insert(uri.to_owned(), workspace);
 
         Ok(())
@@ -132,6 +171,8 @@ impl Session {
     ) -> anyhow::Result<()> {
         let uri = &params.text_document.uri;
 
+        self.ensure_document(uri)?;
+
         let mut workspaces = WORKSPACES.lock();
 
         let workspace = workspaces
@@ -161,14 +202,24 @@ impl Session {
 
         let tree_sitter = &parsers.tree_sitter;
 
-        let mut new_tree = WrapTree::from(&mut workspace.document.cst);
-        for ch in params.content_changes {
+        let mut edits = vec![];
+        for ch in params.content_changes.iter() {
+            // Full text changes are narrowed down to the part of the text that actually changed
+            let change = match ch.range {
+                Some(_) => WrapChange::from(ch),
+                None => match WrapChange::from_full_text(&workspace.document, &ch.text) {
+                    Some(change) => change,
+                    None => continue,
+                },
+            };
+
+            let mut new_tree = WrapTree::from(&mut workspace.document.cst);
             workspace
                 .document
                 .document
-                .update(WrapChange::from(&ch).change, &mut new_tree)?;
+                .update(change.change, &mut new_tree)?;
+            edits.extend(new_tree.get_edits());
         }
-        let edits = new_tree.get_edits();
 
         let new_tree = workspace
             .parsers
@@ -189,7 +240,9 @@ impl Session {
         workspace.errors.clear();
         get_tree_sitter_errors(
             &workspace.document.cst.root_node(),
-            workspace.document.document.text.as_bytes(),
+            &workspace.document,
+            workspace.parsers.name,
+            self.init_options.lsp_options.max_syntax_errors,
             &mut workspace.errors,
         );
 
@@ -200,12 +253,30 @@ impl Session {
             diagnostics: &mut workspace.errors,
             unsolved_checks: &mut workspace.unsolved_checks,
             unsolved_references: &mut workspace.unsolved_references,
+            changes: vec![],
+            lenient: self.init_options.lsp_options.lenient_build,
         };
+        // Comments only need to be updated around the edits if the root symbol was kept
+        let mut incremental = false;
         if let Some(ast) = &mut workspace.ast {
-            builder_params
-                .swap_ast(ast, &edits, &parsers.ast_parser)
-                .resolve_references()
-                .resolve_checks();
+            let previous_root = ast.clone();
+            let changes = std::mem::take(
+                &mut builder_params
+                    .swap_ast(ast, &edits, &parsers.ast_parser)
+                    .resolve_references()
+                    .resolve_checks()
+                    .changes,
+            );
+            incremental = ast.ptr_eq(&previous_root);
+
+            if !changes.is_empty() {
+                let event = AstChangeEvent {
+                    url: uri.clone(),
+                    changes,
+                };
+                self.ast_change_subscribers
+                    .retain(|sender| sender.send(event.clone()).is_ok());
+            }
         } else {
             let ast_parser = &workspace.parsers.ast_parser;
             let ast_build = ast_parser(&mut builder_params, None);
@@ -232,7 +303,11 @@ impl Session {
             );
         }
 
-        Self::add_comments(&workspace)?;
+        if incremental {
+            Self::update_comments(workspace, &edits)?;
+        } else {
+            Self::add_comments(workspace)?;
+        }
 
         Ok(())
     }
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 #[cfg(target_arch = "wasm32")]
 use std::fs;
@@ -6,17 +7,17 @@ use std::fs;
 use auto_lsp_core::workspace::{Parsers, TreeSitter};
 use lsp_server::{Connection, IoThreads};
 use lsp_types::{
-    CodeLensOptions, DocumentLinkOptions, InitializeParams, InitializeResult, PositionEncodingKind,
-    SelectionRangeProviderCapability, SemanticTokensFullOptions, SemanticTokensLegend,
-    SemanticTokensOptions, ServerCapabilities, WorkspaceFoldersServerCapabilities,
-    WorkspaceServerCapabilities,
+    ClientCapabilities, CodeLensOptions, DocumentLinkOptions, InitializeParams, InitializeResult,
+    PositionEncodingKind, SelectionRangeProviderCapability, SemanticTokensFullOptions,
+    SemanticTokensLegend, SemanticTokensOptions, ServerCapabilities,
+    WorkspaceFoldersServerCapabilities, WorkspaceServerCapabilities,
 };
 use lsp_types::{DiagnosticOptions, DiagnosticServerCapabilities};
 use lsp_types::{DocumentLink, OneOf};
 use regex::{Match, Regex};
 use texter::core::text::Text;
 
-use super::Session;
+use super::{cache::hash_bytes, cache::IndexCache, Session};
 
 /// Lists of semantic token types and modifiers
 ///
@@ -40,6 +41,17 @@ pub struct DocumentLinksOption {
     pub with_regex: RegexToDocumentLink,
 }
 
+/// Options used when the server provides completion items, see [`LspOptions::completion_options`]
+///
+/// **trigger_characters** are the characters that trigger completion automatically (e.g. `.`)
+///
+/// **resolve_provider** defers the documentation of an item until the client resolves it
+#[derive(Default)]
+pub struct CompletionsOption {
+    pub trigger_characters: Option<&'static [&'static str]>,
+    pub resolve_provider: bool,
+}
+
 /// List of options for the LSP server capabilties [`lsp_types::ServerCapabilities`]
 ///
 /// Use `..Default::default()` to set the rest of the options to false
@@ -56,6 +68,8 @@ pub struct DocumentLinksOption {
 #[derive(Default)]
 pub struct LspOptions {
     pub completions: bool,
+    /// Trigger characters and resolve support of completion items, used when `completions` is enabled.
+    pub completion_options: CompletionsOption,
     pub diagnostics: bool,
     pub document_symbols: bool,
     pub definition_provider: bool,
@@ -69,6 +83,28 @@ pub struct LspOptions {
     pub workspace_symbols: bool,
     pub inlay_hints: bool,
     pub code_lens: bool,
+    /// Advertise [`lsp_types::TextDocumentSyncKind::FULL`] instead of incremental sync.
+    ///
+    /// The client then sends the whole text on every change, the server compares it with the current text
+    /// so that only the parts of the tree and AST that differ are updated.
+    pub full_sync: bool,
+    /// Build the AST of documents containing invalid symbols.
+    ///
+    /// Invalid symbols are left out of the AST and reported as diagnostics,
+    /// so capabilities keep working on the rest of the document, see [`auto_lsp_core::build::MainBuilder::lenient`].
+    pub lenient_build: bool,
+    /// Maximum number of syntax errors reported per document, all errors are reported if `None`.
+    pub max_syntax_errors: Option<usize>,
+    /// Directory of the on-disk index cache.
+    ///
+    /// When set, document symbols, diagnostics and exported names of every document are written to this directory.
+    /// At startup, unchanged documents are served from the cache and their AST is only built once they are opened or modified.
+    /// Deferred documents are not built when another document references them:
+    /// capabilities looking up symbols across documents do not see them until they are opened.
+    ///
+    /// Entries are invalidated when the content of the document, the node types or the queries change.
+    /// Changes to the AST definitions alone are not detected, so the directory should be cleared when they change.
+    pub index_cache: Option<PathBuf>,
 }
 
 /// Initialization options for the LSP server
@@ -80,6 +116,9 @@ pub struct InitOptions {
 /// Function to create a new [`Text`] from a [`String`]
 pub(crate) type TextFn = fn(String) -> Text;
 
+/// Pick the first position encoding supported by both the client and texter, in the client's order of preference.
+///
+/// Defaults to UTF-16, which every client must support.
 fn decide_encoding(encs: Option<&[PositionEncodingKind]>) -> (TextFn, PositionEncodingKind) {
     const DEFAULT: (TextFn, PositionEncodingKind) = (Text::new_utf16, PositionEncodingKind::UTF16);
     let Some(encs) = encs else {
@@ -91,6 +130,8 @@ fn decide_encoding(encs: Option<&[PositionEncodingKind]>) -> (TextFn, PositionEn
             return (Text::new_utf16, enc.clone());
         } else if *enc == PositionEncodingKind::UTF8 {
             return (Text::new, enc.clone());
+        } else if *enc == PositionEncodingKind::UTF32 {
+            return (Text::new_utf32, enc.clone());
         }
     }
 
@@ -103,13 +144,25 @@ impl Session {
         connection: Connection,
         io_threads: IoThreads,
         text_fn: TextFn,
+        position_encoding: PositionEncodingKind,
+        client_capabilities: ClientCapabilities,
     ) -> Self {
+        let index_cache = init_options
+            .lsp_options
+            .index_cache
+            .clone()
+            .map(IndexCache::new);
         Self {
             init_options,
             connection,
             io_threads,
             text_fn,
+            position_encoding,
+            client_capabilities,
             extensions: HashMap::new(),
+            anonymous_tokens: HashMap::new(),
+            index_cache,
+            ast_change_subscribers: vec![],
         }
     }
 
@@ -165,9 +218,12 @@ impl Session {
 
         let server_capabilities = serde_json::to_value(&InitializeResult {
             capabilities: ServerCapabilities {
-                position_encoding: Some(enc),
+                position_encoding: Some(enc.clone()),
                 text_document_sync: Some(lsp_types::TextDocumentSyncCapability::Kind(
-                    lsp_types::TextDocumentSyncKind::INCREMENTAL,
+                    match init_options.lsp_options.full_sync {
+                        true => lsp_types::TextDocumentSyncKind::FULL,
+                        false => lsp_types::TextDocumentSyncKind::INCREMENTAL,
+                    },
                 )),
                 diagnostic_provider: match init_options.lsp_options.diagnostics {
                     true => Some(DiagnosticServerCapabilities::Options(DiagnosticOptions {
@@ -244,11 +300,16 @@ impl Session {
                     false => None,
                 },
                 completion_provider: match init_options.lsp_options.completions {
-                    true => Some(lsp_types::CompletionOptions {
-                        trigger_characters: None,
-                        resolve_provider: Some(false),
-                        ..Default::default()
-                    }),
+                    true => {
+                        let options = &init_options.lsp_options.completion_options;
+                        Some(lsp_types::CompletionOptions {
+                            trigger_characters: options.trigger_characters.map(|characters| {
+                                characters.iter().map(|c| c.to_string()).collect()
+                            }),
+                            resolve_provider: Some(options.resolve_provider),
+                            ..Default::default()
+                        })
+                    }
                     false => None,
                 },
                 definition_provider: match init_options.lsp_options.definition_provider {
@@ -271,7 +332,14 @@ impl Session {
 
         connection.initialize_finish(id, server_capabilities)?;
 
-        let mut session = Session::new(init_options, connection, io_threads, t_fn);
+        let mut session = Session::new(
+            init_options,
+            connection,
+            io_threads,
+            t_fn,
+            enc,
+            params.capabilities.clone(),
+        );
 
         // Initialize the session with the client's initialization options.
         // This will also add all documents, parse and send diagnostics.
@@ -287,6 +355,16 @@ impl Session {
 ///
 /// To determine which parser to use for a document, the server will check the file extension against the keys in the `PARSERS` map generated by this macro
 ///
+/// An optional `comment_style` ([`crate::core::workspace::CommentStyle`]) can be provided after `highlights` to tell how comments are stripped,
+/// otherwise the default style (`//`, `#` and `/* */`) is used.
+///
+/// When `core` is omitted, the core query is derived from the symbols reachable from `ast_root`,
+/// which must all be given a `node_kind` (see [`crate::seq`]).
+///
+/// With the `assertions` feature, a test is generated to check that the core query of each parser
+/// has a capture for every query name reachable from `ast_root`, and that every capture is consumed by a symbol.
+/// It also fails when the core query can't be derived from `ast_root`, the server instead logs the error and falls back to an empty core query.
+///
 /// # Example
 /// ```rust
 /// # use auto_lsp::configure_parsers;
@@ -327,13 +405,61 @@ macro_rules! configure_parsers {
             comment: $comment: expr,
             fold: $fold: expr,
             highlights: $highlights: expr
+            $(, comment_style: $comment_style: expr)?
         }),*) => {
+        $crate::configure_parsers!(@parsers $($extension => {
+            language: $language,
+            node_types: $node_types,
+            ast_root: $root,
+            core: $core,
+            comment: $comment,
+            fold: $fold,
+            highlights: $highlights
+            $(, comment_style: $comment_style)?
+        }, derived: false),*);
+    };
+    ($($extension: expr => {
+            language: $language: path,
+            node_types: $node_types: path,
+            ast_root: $root: ident,
+            comment: $comment: expr,
+            fold: $fold: expr,
+            highlights: $highlights: expr
+            $(, comment_style: $comment_style: expr)?
+        }),*) => {
+        $crate::configure_parsers!(@parsers $($extension => {
+            language: $language,
+            node_types: $node_types,
+            ast_root: $root,
+            core: &$crate::server::derive_core_query::<$root>($extension),
+            comment: $comment,
+            fold: $fold,
+            highlights: $highlights
+            $(, comment_style: $comment_style)?
+        }, derived: true),*);
+    };
+    (@parsers $($extension: expr => {
+            language: $language: path,
+            node_types: $node_types: path,
+            ast_root: $root: ident,
+            core: $core: expr,
+            comment: $comment: expr,
+            fold: $fold: expr,
+            highlights: $highlights: expr
+            $(, comment_style: $comment_style: expr)?
+        }, derived: $derived: expr),*) => {
         pub static PARSERS: std::sync::LazyLock<std::collections::HashMap<&str, $crate::core::workspace::Parsers>> =
             std::sync::LazyLock::new(|| {
                 let mut map = std::collections::HashMap::new();
                 map.insert(
                     $($extension, $crate::core::workspace::Parsers {
-                        tree_sitter: $crate::server::create_parser($language, $node_types, $core, $comment, $fold, $highlights),
+                        name: $extension,
+                        tree_sitter: {
+                            #[allow(unused_mut)]
+                            let mut tree_sitter = $crate::server::create_parser($language, $node_types, $core, $comment, $fold, $highlights);
+                            $(tree_sitter.comment_style = $comment_style;)?
+                            tree_sitter
+                        },
                         ast_parser: |params: &mut $crate::core::build::MainBuilder<'_>, range: Option<std::ops::Range<usize>>| {
                             use $crate::core::build::StaticBuildable;
 
@@ -345,18 +471,80 @@ macro_rules! configure_parsers {
                 );
                 map
             });
+
+        $crate::check_query_names!($($extension => $root, derived: $derived),*);
     };
 }
 
+/// Generate a test comparing the core queries with the symbols of each parser, see [`configure_parsers`]
+#[cfg(feature = "assertions")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! check_query_names {
+    ($($extension: expr => $root: ident, derived: $derived: expr),*) => {
+        #[cfg(test)]
+        mod auto_lsp_query_names {
+            use super::*;
+
+            #[test]
+            fn check_query_names() {
+                $(
+                    if $derived {
+                        if let Err(err) = $crate::core::build::derive_query::<$root>() {
+                            panic!("Failed to derive the core query for {:?}\n{}", $extension, err);
+                        }
+                    }
+                    let parsers = PARSERS.get($extension).unwrap();
+                    if let Err(err) = $crate::core::build::check_query_names::<$root>(&parsers.tree_sitter.queries.core) {
+                        panic!("Invalid core query for {:?}\n{}", $extension, err);
+                    }
+                )*
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "assertions"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! check_query_names {
+    ($($extension: expr => $root: ident, derived: $derived: expr),*) => {};
+}
+
+/// Derive the core query of a parser, see [`configure_parsers`].
+///
+/// An error is logged and an empty query is returned when the query can't be derived,
+/// so the server keeps running and documents of this parser are reported as failing to build.
+#[doc(hidden)]
+pub fn derive_core_query<T: crate::core::build::DeriveQuery>(extension: &str) -> String {
+    crate::core::build::derive_query::<T>().unwrap_or_else(|err| {
+        log::error!(
+            "Failed to derive the core query for {:?}\n{}",
+            extension,
+            err
+        );
+        String::new()
+    })
+}
+
 #[doc(hidden)]
 pub fn create_parser(
     language: tree_sitter_language::LanguageFn,
     node_types: &'static str,
-    core: &'static str,
+    core: &str,
     comments: Option<&'static str>,
     fold: Option<&'static str>,
     highlights: Option<&'static str>,
 ) -> TreeSitter {
+    let parser_hash = hash_bytes([
+        env!("CARGO_PKG_VERSION").as_bytes(),
+        node_types.as_bytes(),
+        core.as_bytes(),
+        comments.unwrap_or_default().as_bytes(),
+        fold.unwrap_or_default().as_bytes(),
+        highlights.unwrap_or_default().as_bytes(),
+    ]);
+
     let mut parser = crate::tree_sitter::Parser::new();
     parser.set_language(&language.into()).unwrap();
 
@@ -377,5 +565,55 @@ pub fn create_parser(
             highlights,
             core,
         },
+        comment_style: Default::default(),
+        parser_hash,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negotiate_encoding() {
+        assert_eq!(decide_encoding(None).1, PositionEncodingKind::UTF16);
+        assert_eq!(
+            decide_encoding(Some(&[PositionEncodingKind::UTF32])).1,
+            PositionEncodingKind::UTF32
+        );
+        // The client's preference comes first
+        assert_eq!(
+            decide_encoding(Some(&[
+                PositionEncodingKind::UTF32,
+                PositionEncodingKind::UTF8
+            ]))
+            .1,
+            PositionEncodingKind::UTF32
+        );
+        assert_eq!(
+            decide_encoding(Some(&[
+                PositionEncodingKind::new("utf-7"),
+                PositionEncodingKind::UTF8
+            ]))
+            .1,
+            PositionEncodingKind::UTF8
+        );
+        assert_eq!(
+            decide_encoding(Some(&[PositionEncodingKind::new("utf-7")])).1,
+            PositionEncodingKind::UTF16
+        );
+
+        // Texts are created with the negotiated encoding
+        let (text_fn, _) = decide_encoding(Some(&[PositionEncodingKind::UTF32]));
+        let mut text = text_fn("é😀".into());
+        text.update(
+            texter::change::Change::Insert {
+                at: texter::change::GridIndex { row: 0, col: 2 },
+                text: "a".into(),
+            },
+            &mut (),
+        )
+        .unwrap();
+        assert_eq!(text.text, "é😀a");
     }
 }
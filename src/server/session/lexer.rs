@@ -1,61 +1,249 @@
-use lsp_types::{Diagnostic, Position, Range};
+use auto_lsp_core::workspace::Document;
+use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString};
 use tree_sitter::Node;
 
+/// Code of diagnostics reported for `ERROR` nodes.
+pub(crate) const SYNTAX_ERROR_CODE: &str = "syntax-error";
+/// Code of diagnostics reported for `MISSING` nodes.
+pub(crate) const MISSING_NODE_CODE: &str = "missing-node";
+
+/// Maximum number of expected tokens listed in a message.
+const MAX_EXPECTED: usize = 8;
+/// Maximum number of characters of the unexpected text quoted in a message.
+const MAX_UNEXPECTED_LEN: usize = 32;
+
 /// Traverse a tree-sitter syntax tree to collect error nodes.
 ///
 /// This function traverses the syntax tree in a depth-first manner to find error nodes:
 /// - If a node `has_error()` but none of its children have errors, it is collected
 /// - If a node `has_error()` and some children have errors, traverse those children
+///
+/// `source` is the name of the parser and `limit` the maximum number of errors collected, if any.
 pub(crate) fn get_tree_sitter_errors(
     node: &Node,
-    source_code: &[u8],
+    document: &Document,
+    source: &str,
+    limit: Option<usize>,
     errors: &mut Vec<Diagnostic>,
 ) {
+    let mut count = 0;
+    collect_errors(node, document, source, limit, &mut count, errors);
+}
+
+fn collect_errors(
+    node: &Node,
+    document: &Document,
+    source: &str,
+    limit: Option<usize>,
+    count: &mut usize,
+    errors: &mut Vec<Diagnostic>,
+) {
+    if limit.is_some_and(|limit| *count >= limit) {
+        return;
+    }
+
     let mut cursor = node.walk();
 
     if node.has_error() {
         if node.children(&mut cursor).any(|f| f.has_error()) {
             for child in node.children(&mut cursor) {
-                get_tree_sitter_errors(&child, &source_code, errors);
+                collect_errors(&child, document, source, limit, count, errors);
             }
         } else {
-            errors.push(format_error(node, source_code));
+            *count += 1;
+            errors.push(format_error(node, document, source));
         }
     }
 }
 
-fn format_error(node: &Node, source_code: &[u8]) -> Diagnostic {
-    let start_position = node.start_position();
-    let end_position = node.end_position();
-    let range = Range {
-        start: Position {
-            line: start_position.row as u32,
-            character: start_position.column as u32,
-        },
-        end: Position {
-            line: end_position.row as u32,
-            character: end_position.column as u32,
-        },
-    };
+fn format_error(node: &Node, document: &Document, source: &str) -> Diagnostic {
+    let source_code = document.document.text.as_bytes();
+    let range = document.range_at(node.byte_range()).unwrap();
+
+    let expected = expected_tokens(node);
 
-    let message = if node.is_missing() {
-        format!("Syntax error: Missing {:?}", node.grammar_name())
+    let (code, message) = if node.is_missing() {
+        let missing = display_kind(node.kind(), node.is_named());
+        let mut message = format!("Syntax error: missing {}", missing);
+        if expected.iter().any(|kind| *kind != missing) {
+            message.push_str(&format!(", expected {}", join_expected(&expected)));
+        }
+        (MISSING_NODE_CODE, message)
     } else {
-        let children_text: Vec<String> = (0..node.child_count())
-            .map(|i| {
-                node.child(i)
-                    .unwrap()
-                    .utf8_text(source_code)
-                    .unwrap()
-                    .to_string()
-            })
-            .collect();
-        format!("Unexpected token(s): '{}'", children_text.join(" "))
+        let mut message = format!(
+            "Syntax error: unexpected {}",
+            unexpected_text(node, source_code)
+        );
+        if !expected.is_empty() {
+            message.push_str(&format!(", expected {}", join_expected(&expected)));
+        }
+        (SYNTAX_ERROR_CODE, message)
     };
 
     Diagnostic {
         range,
+        severity: Some(DiagnosticSeverity::ERROR),
+        code: Some(NumberOrString::String(code.into())),
+        source: Some(source.into()),
         message,
         ..Default::default()
     }
 }
+
+/// Get the visible tokens the parser accepted where the error node starts.
+///
+/// Error nodes are in the error state, the state is taken from their first descendant
+/// or from the last leaf preceding them.
+fn expected_tokens(node: &Node) -> Vec<String> {
+    // 0 is the error state
+    let language = node.language();
+    let state = std::iter::successors(Some(*node), |node| node.child(0))
+        .map(|node| node.parse_state())
+        .chain(previous_leaf(node).map(|leaf| leaf.next_parse_state()))
+        .find(|state| *state != 0);
+    let state = match state {
+        Some(state) => state,
+        None => return vec![],
+    };
+
+    let mut lookahead = match language.lookahead_iterator(state) {
+        Some(lookahead) => lookahead,
+        None => return vec![],
+    };
+
+    let mut expected = vec![];
+    for id in &mut lookahead {
+        if !language.node_kind_is_visible(id) {
+            continue;
+        }
+        let kind = match language.node_kind_for_id(id) {
+            Some(kind) if !kind.is_empty() && kind != "ERROR" => kind,
+            _ => continue,
+        };
+        let kind = display_kind(kind, language.node_kind_is_named(id));
+        if !expected.contains(&kind) {
+            expected.push(kind);
+        }
+    }
+    expected
+}
+
+/// Find the last leaf located before the node.
+fn previous_leaf<'a>(node: &Node<'a>) -> Option<Node<'a>> {
+    let mut curr = *node;
+    let mut previous = loop {
+        match curr.prev_sibling() {
+            Some(sibling) => break sibling,
+            None => curr = curr.parent()?,
+        }
+    };
+    while previous.child_count() > 0 {
+        previous = previous.child(previous.child_count() - 1)?;
+    }
+    Some(previous)
+}
+
+/// Named kinds are displayed as is, anonymous ones are quoted.
+fn display_kind(kind: &str, is_named: bool) -> String {
+    match is_named {
+        true => kind.to_string(),
+        false => format!("{:?}", kind),
+    }
+}
+
+fn join_expected(expected: &[String]) -> String {
+    match expected.len() {
+        1 => expected[0].clone(),
+        len if len > MAX_EXPECTED => format!("one of {}, ...", expected[..MAX_EXPECTED].join(", ")),
+        _ => format!("one of {}", expected.join(", ")),
+    }
+}
+
+/// Quote the first line of the error node, truncated to [`MAX_UNEXPECTED_LEN`] characters.
+fn unexpected_text(node: &Node, source_code: &[u8]) -> String {
+    let text = node.utf8_text(source_code).unwrap_or_default();
+    let line = text.lines().next().unwrap_or_default().trim();
+    if line.is_empty() {
+        return format!("{:?}", node.kind());
+    }
+    match line.char_indices().nth(MAX_UNEXPECTED_LEN) {
+        Some((end, _)) => format!("{:?}...", &line[..end]),
+        None => format!("{:?}", line),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use texter::core::text::Text;
+
+    fn errors(source_code: &str, limit: Option<usize>) -> Vec<Diagnostic> {
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_python::LANGUAGE.into())
+            .unwrap();
+        let document = Document {
+            document: Text::new(source_code.into()),
+            cst: parser.parse(source_code, None).unwrap(),
+            comment_style: Default::default(),
+            position_encoding: lsp_types::PositionEncodingKind::UTF8,
+        };
+        let mut errors = vec![];
+        get_tree_sitter_errors(
+            &document.cst.root_node(),
+            &document,
+            "python",
+            limit,
+            &mut errors,
+        );
+        errors
+    }
+
+    #[test]
+    fn unexpected_token() {
+        let errors = errors("def foo(param1, $param2):\n    pass\n", None);
+        assert_eq!(errors.len(), 1);
+
+        let error = &errors[0];
+        assert_eq!(error.severity, Some(DiagnosticSeverity::ERROR));
+        assert_eq!(error.source.as_deref(), Some("python"));
+        assert_eq!(
+            error.code,
+            Some(NumberOrString::String(SYNTAX_ERROR_CODE.into()))
+        );
+        assert_eq!(error.range.start.character, 16);
+        assert_eq!(error.range.end.character, 17);
+        assert!(error
+            .message
+            .starts_with("Syntax error: unexpected \"$\", expected one of identifier"));
+    }
+
+    #[test]
+    fn missing_token() {
+        let errors = errors("def foo(:\n    pass\n", None);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].code,
+            Some(NumberOrString::String(MISSING_NODE_CODE.into()))
+        );
+        assert!(errors[0].message.starts_with("Syntax error: missing \")\""));
+    }
+
+    #[test]
+    fn long_error_text() {
+        let source_code = format!("{}\n", "$ ".repeat(50));
+        let errors = errors(&source_code, None);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.starts_with(&format!(
+            "Syntax error: unexpected {:?}...",
+            &source_code[..32]
+        )));
+    }
+
+    #[test]
+    fn errors_limit() {
+        let source_code = "$ $ $\nx = 1\ny = $\n";
+        assert_eq!(errors(source_code, None).len(), 2);
+        assert_eq!(errors(source_code, Some(1)).len(), 1);
+    }
+}
@@ -6,8 +6,9 @@ use lsp_types::{
     request::{
         CodeLensRequest, Completion, DocumentDiagnosticRequest, DocumentLinkRequest,
         DocumentSymbolRequest, FoldingRangeRequest, GotoDeclaration, GotoDefinition, HoverRequest,
-        InlayHintRequest, References, SelectionRangeRequest, SemanticTokensFullRequest,
-        SemanticTokensRangeRequest, WorkspaceDiagnosticRequest, WorkspaceSymbolRequest,
+        InlayHintRequest, References, ResolveCompletionItem, SelectionRangeRequest,
+        SemanticTokensFullRequest, SemanticTokensRangeRequest, WorkspaceDiagnosticRequest,
+        WorkspaceSymbolRequest,
     },
 };
 use serde::Serialize;
@@ -39,6 +40,7 @@ impl Session {
                                 .on::<InlayHintRequest, _>(Self::get_inlay_hints)?
                                 .on::<CodeLensRequest, _>(Self::get_code_lens)?
                                 .on::<Completion, _>(Self::get_completion_items)?
+                                .on::<ResolveCompletionItem, _>(Self::resolve_completion_item)?
                                 .on::<GotoDefinition, _>(Self::go_to_definition)?
                                 .on::<GotoDeclaration, _>(Self::go_to_declaration)?
                                 .on::<References, _>(Self::get_references)?;
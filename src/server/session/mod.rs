@@ -1,12 +1,19 @@
-use std::{collections::HashMap, sync::LazyLock};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::LazyLock,
+};
 
+use crate::server::session::cache::IndexCache;
+use crate::server::session::documents::AstChangeEvent;
 use crate::server::session::init::TextFn;
 use auto_lsp_core::workspace::Workspace;
+use crossbeam_channel::Sender;
 use init::InitOptions;
 use lsp_server::{Connection, IoThreads};
-use lsp_types::Url;
+use lsp_types::{ClientCapabilities, PositionEncodingKind, Url};
 use parking_lot::Mutex;
 
+pub(crate) mod cache;
 pub mod comment;
 pub mod documents;
 pub mod init;
@@ -33,6 +40,18 @@ pub struct Session {
     ///
     /// The client is responsible for providing the encoding at initialization (UTF-8, 16 or 32).
     pub text_fn: TextFn,
+    /// Position encoding negotiated with the client, matches the encoding of [`Session::text_fn`].
+    pub position_encoding: PositionEncodingKind,
+    /// Capabilities advertised by the client at initialization.
+    pub client_capabilities: ClientCapabilities,
     /// Language extensions to parser mappings.
     pub extensions: HashMap<String, String>,
+    /// Anonymous tokens of each parser, parsed from `node_types` when completion is first requested.
+    ///
+    /// Keys are the names of the parsers, as given to [`crate::configure_parsers`].
+    pub(crate) anonymous_tokens: HashMap<&'static str, HashSet<String>>,
+    /// Index cache, only present if [`init::LspOptions::index_cache`] is set.
+    pub(crate) index_cache: Option<IndexCache>,
+    /// Channels registered with [`Session::subscribe_ast_changes`].
+    pub(crate) ast_change_subscribers: Vec<Sender<AstChangeEvent>>,
 }
@@ -4,7 +4,7 @@ use lsp_types::{InitializeParams, Url, WorkspaceFolder};
 use serde::Deserialize;
 use walkdir::WalkDir;
 
-use super::Session;
+use super::{cache::CacheEntry, Session};
 
 #[allow(non_snake_case, reason = "JSON")]
 #[derive(Debug, Deserialize)]
@@ -12,9 +12,10 @@ struct InitializationOptions {
     perFileParser: HashMap<String, String>,
 }
 
+/// Get the extension of a file URL, returns `None` if the URL is not a file path or has no extension.
 pub fn get_extension(url: &Url) -> Option<String> {
-    let path = url.to_file_path().unwrap();
-    let extension = path.extension().unwrap();
+    let path = url.to_file_path().ok()?;
+    let extension = path.extension()?;
     extension.to_str().map(|s| s.to_string())
 }
 
@@ -51,11 +52,61 @@ impl Session {
                 open_file.read_to_string(&mut buffer).unwrap();
 
                 let extension = get_extension(&file).unwrap();
+
+                if let Some(entry) = self.load_cache_entry(&file, &extension, &buffer) {
+                    // Unchanged document, the AST will be built once the document is opened
+                    self.index_cache
+                        .as_mut()
+                        .unwrap()
+                        .deferred
+                        .insert(file, entry);
+                    return;
+                }
                 self.add_document(&file, &extension, &buffer).unwrap();
             });
 
         Ok(())
     }
+
+    /// Find a valid index cache entry for a document.
+    ///
+    /// Returns `None` if the cache is disabled or if the entry is missing or outdated.
+    fn load_cache_entry(
+        &self,
+        uri: &Url,
+        language_id: &str,
+        source_code: &str,
+    ) -> Option<CacheEntry> {
+        let cache = self.index_cache.as_ref()?;
+        let parsers = self
+            .init_options
+            .parsers
+            .get(self.extensions.get(language_id)?.as_str())?;
+        cache.load(uri, source_code, parsers.tree_sitter.parser_hash)
+    }
+
+    /// Returns `true` if the document is served from the index cache and its AST is not built yet.
+    pub(crate) fn is_deferred(&self, uri: &Url) -> bool {
+        self.index_cache
+            .as_ref()
+            .is_some_and(|cache| cache.deferred.contains_key(uri))
+    }
+
+    /// Build the AST of a document previously served from the index cache.
+    ///
+    /// Does nothing if the document is not deferred.
+    /// The document stays deferred if it can't be read or built.
+    pub(crate) fn ensure_document(&mut self, uri: &Url) -> anyhow::Result<()> {
+        if !self.is_deferred(uri) {
+            return Ok(());
+        }
+
+        let extension = get_extension(uri)
+            .ok_or_else(|| anyhow::format_err!("Missing file extension for document {}", uri))?;
+        let source_code = std::fs::read_to_string(uri.to_file_path().unwrap())?;
+        // The deferred entry is removed once the document is added
+        self.add_document(uri, &extension, &source_code)
+    }
 }
 
 fn convert_workspace_folders_to_urls(
@@ -1,3 +1,4 @@
+use auto_lsp_core::workspace::Document;
 use lsp_types::{Position, TextDocumentContentChangeEvent};
 use texter::change::{Change, GridIndex};
 
@@ -10,6 +11,64 @@ impl<'a> WrapChange<'a> {
     pub fn new(change: Change<'a>) -> Self {
         Self { change }
     }
+
+    /// Convert a full text change into the smallest change turning the text of `document` into `text`.
+    ///
+    /// The common prefix and suffix of both texts are kept, so that tree-sitter and the AST are only updated where the text differs.
+    ///
+    /// Returns `None` if both texts are equal.
+    pub fn from_full_text(document: &Document, text: &'a str) -> Option<Self> {
+        let old = document.document.text.as_str();
+        if old == text {
+            return None;
+        }
+
+        let mut prefix = old
+            .bytes()
+            .zip(text.bytes())
+            .take_while(|(a, b)| a == b)
+            .count();
+        while !old.is_char_boundary(prefix) || is_within_crlf(old, prefix) {
+            prefix -= 1;
+        }
+
+        let max_suffix = old.len().min(text.len()) - prefix;
+        let mut suffix = old
+            .bytes()
+            .rev()
+            .zip(text.bytes().rev())
+            .take(max_suffix)
+            .take_while(|(a, b)| a == b)
+            .count();
+        while !old.is_char_boundary(old.len() - suffix) || is_within_crlf(old, old.len() - suffix) {
+            suffix -= 1;
+        }
+
+        let range = document.range_at(prefix..old.len() - suffix)?;
+        let start = grid_into_position(range.start);
+        let end = grid_into_position(range.end);
+        let text = &text[prefix..text.len() - suffix];
+
+        Some(WrapChange::new(if text.is_empty() {
+            Change::Delete { start, end }
+        } else if start == end {
+            Change::Insert {
+                at: start,
+                text: text.into(),
+            }
+        } else {
+            Change::Replace {
+                start,
+                end,
+                text: text.into(),
+            }
+        }))
+    }
+}
+
+/// Check if `offset` is between a `\r` and a `\n`, which have the same position.
+fn is_within_crlf(text: &str, offset: usize) -> bool {
+    offset > 0 && text[..offset].ends_with('\r') && text[offset..].starts_with('\n')
 }
 
 /// Convert a `Position` into a [`GridIndex`].
@@ -0,0 +1,213 @@
+use crate::core::ast::{AstSymbol, GetSymbolData, Symbol};
+use crate::core::build::MainBuilder;
+use crate::core::workspace::Workspace;
+use crate::seq;
+use crate::server::texter_impl::updateable::WrapTree;
+use lsp_types::Url;
+use std::sync::Arc;
+use texter::change::{Change, GridIndex};
+
+use crate as auto_lsp;
+
+// Identifiers are captured as names or parameters, never as `identifier`
+static CORE_QUERY: &str = "
+(module) @module
+
+(function_definition
+  name: (identifier) @name) @function
+
+(parameters
+  (identifier) @parameter)
+
+(function_definition
+  return_type: (type) @return_type)
+
+(function_definition
+  return_type: (type (identifier) @type_name))
+";
+
+configure_python_test!(Module, CORE_QUERY);
+
+#[seq(query_name = "module", kind(symbol()))]
+struct Module {
+    functions: Vec<Function>,
+}
+
+#[seq(query_name = "function", kind(symbol()))]
+struct Function {
+    #[ast(capture = "name")]
+    name: Identifier,
+    #[ast(capture = "parameter", min = 1, max = 2)]
+    parameters: Vec<Identifier>,
+    #[ast(text = "return_type", default)]
+    return_type: String,
+    #[ast(capture = "type_name", default)]
+    type_name: Identifier,
+}
+
+#[seq(query_name = "identifier", kind(symbol()))]
+struct Identifier {}
+
+fn apply_change(workspace: &mut Workspace, change: Change) {
+    let mut tree = WrapTree::from(&mut workspace.document.cst);
+    workspace
+        .document
+        .document
+        .update(change, &mut tree)
+        .unwrap();
+    let edits = tree.get_edits();
+
+    workspace.document.cst = workspace
+        .parsers
+        .tree_sitter
+        .parser
+        .write()
+        .parse(
+            workspace.document.document.text.as_bytes(),
+            Some(&workspace.document.cst),
+        )
+        .unwrap();
+
+    MainBuilder {
+        query: &workspace.parsers.tree_sitter.queries.core,
+        document: &workspace.document,
+        url: Arc::new(Url::parse("file:///test.py").unwrap()),
+        diagnostics: &mut workspace.errors,
+        unsolved_checks: &mut workspace.unsolved_checks,
+        unsolved_references: &mut workspace.unsolved_references,
+        changes: vec![],
+        lenient: false,
+    }
+    .swap_ast(
+        workspace.ast.as_mut().unwrap(),
+        &edits,
+        &workspace.parsers.ast_parser,
+    );
+}
+
+/// Name and parameters of each function
+fn signatures(workspace: &Workspace) -> Vec<(String, Vec<String>, String)> {
+    let source = workspace.document.document.text.as_bytes();
+    let ast = workspace.ast.as_ref().unwrap().read();
+    let module = ast.downcast_ref::<Module>().unwrap();
+    module
+        .functions
+        .iter()
+        .map(|function| {
+            let function = function.read();
+            let name = function.name.read().get_text(source).unwrap().to_string();
+            let parameters = function
+                .parameters
+                .iter()
+                .map(|p| p.read().get_text(source).unwrap().to_string())
+                .collect();
+            (name, parameters, function.return_type.clone())
+        })
+        .collect()
+}
+
+static SOURCE: &str = r#"def foo(a, b) -> int:
+    pass
+
+def bar(c):
+    pass
+"#;
+
+#[test]
+fn capture_and_default() {
+    let workspace = create_workspace(SOURCE);
+    assert!(workspace.errors.is_empty());
+
+    assert_eq!(
+        signatures(&workspace),
+        vec![
+            ("foo".into(), vec!["a".into(), "b".into()], "int".into()),
+            ("bar".into(), vec!["c".into()], "".into()),
+        ]
+    );
+}
+
+#[test]
+fn min_parameters() {
+    let workspace = create_workspace("def foo():\n    pass\n");
+
+    assert!(workspace.ast.is_none());
+    assert_eq!(workspace.errors.len(), 1);
+    assert_eq!(
+        workspace.errors[0].message,
+        "Invalid \"parameters\" for \"Function\", expected at least 1 but received 0"
+    );
+}
+
+#[test]
+fn max_parameters() {
+    let workspace = create_workspace("def foo(a, b, c):\n    pass\n");
+
+    assert!(workspace.ast.is_none());
+    assert_eq!(workspace.errors.len(), 1);
+    assert_eq!(
+        workspace.errors[0].message,
+        "Invalid \"parameters\" for \"Function\", expected at most 2 but received 3"
+    );
+}
+
+#[test]
+fn edit_captured_field() {
+    let mut workspace = create_workspace(SOURCE);
+
+    // Rename `a` to `ab`, the parameter is rebuilt by foo
+    apply_change(
+        &mut workspace,
+        Change::Insert {
+            at: GridIndex { row: 0, col: 9 },
+            text: "b".into(),
+        },
+    );
+    assert!(workspace.errors.is_empty());
+
+    // Rename `bar` to `baz`
+    apply_change(
+        &mut workspace,
+        Change::Replace {
+            start: GridIndex { row: 3, col: 6 },
+            end: GridIndex { row: 3, col: 7 },
+            text: "z".into(),
+        },
+    );
+    assert!(workspace.errors.is_empty());
+
+    assert_eq!(
+        signatures(&workspace),
+        signatures(&create_workspace(&workspace.document.document.text))
+    );
+    assert_eq!(
+        signatures(&workspace),
+        vec![
+            ("foo".into(), vec!["ab".into(), "b".into()], "int".into()),
+            ("baz".into(), vec!["c".into()], "".into()),
+        ]
+    );
+}
+
+#[test]
+fn default_symbol() {
+    let workspace = create_workspace(SOURCE);
+    assert!(workspace.errors.is_empty());
+
+    let source = workspace.document.document.text.as_bytes();
+    let ast = workspace.ast.as_ref().unwrap().read();
+    let module = ast.downcast_ref::<Module>().unwrap();
+
+    let foo = module.functions[0].read();
+    assert_eq!(foo.type_name.read().get_text(source), Some("int"));
+
+    // The placeholder is empty and placed at the end of the function
+    let bar = module.functions[1].read();
+    let type_name = bar.type_name.read();
+    assert_eq!(
+        type_name.get_range(),
+        bar.get_range().end..bar.get_range().end
+    );
+    assert_eq!(type_name.get_text(source), Some(""));
+    assert!(type_name.get_parent().is_some());
+}
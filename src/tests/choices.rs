@@ -0,0 +1,122 @@
+use crate::core::ast::{AstSymbol, GetHover, GetSymbolData, Symbol};
+use crate::core::workspace::Document;
+use crate::{choice, seq};
+use lsp_types::HoverContents;
+
+use crate as auto_lsp;
+
+// Booleans are keywords without a symbol of their own
+static CORE_QUERY: &str = "
+(module) @module
+
+(assignment
+  left: (identifier) @name) @assignment
+
+(true) @true
+(false) @false
+(integer) @integer @digits
+";
+
+configure_python_test!(Module, CORE_QUERY);
+
+#[seq(query_name = "module", kind(symbol()))]
+struct Module {
+    statements: Vec<Statement>,
+}
+
+#[choice(lsp_hover_info(code_gen(language = "python")))]
+enum Statement {
+    Assignment(Assignment),
+}
+
+#[seq(query_name = "assignment", kind(symbol()))]
+struct Assignment {
+    name: Name,
+    value: Value,
+}
+
+#[seq(query_name = "name", kind(symbol()))]
+struct Name {}
+
+// Variants accept qualified paths and boxes, unit variants take the visibility of the enum
+#[choice(lsp_hover_info(user))]
+pub(crate) enum Value {
+    #[ast(query_name = "true")]
+    True,
+    #[ast(query_name = "false")]
+    False,
+    Integer(Box<crate::tests::choices::Integer>),
+}
+
+impl GetHover for Value {
+    fn get_hover(&self, _doc: &Document) -> Option<lsp_types::Hover> {
+        let value = match self {
+            Value::True(_) | Value::False(_) => "bool".to_string(),
+            Value::Integer(integer) => format!("int = {}", integer.0),
+        };
+        Some(lsp_types::Hover {
+            contents: HoverContents::Scalar(lsp_types::MarkedString::String(value)),
+            range: None,
+        })
+    }
+}
+
+#[seq(query_name = "integer", kind(symbol()))]
+struct Integer(#[ast(text = "digits")] i64);
+
+fn hover_text(hover: lsp_types::Hover) -> String {
+    match hover.contents {
+        HoverContents::Scalar(lsp_types::MarkedString::String(value)) => value,
+        HoverContents::Markup(markup) => markup.value,
+        _ => panic!("Unexpected hover contents"),
+    }
+}
+
+static SOURCE: &str = "x = True\ny = 42\nz = False\n";
+
+#[test]
+fn unit_variants() {
+    let workspace = create_workspace(SOURCE);
+    assert!(workspace.errors.is_empty());
+
+    let ast = workspace.ast.as_ref().unwrap().read();
+    let module = ast.downcast_ref::<Module>().unwrap();
+
+    let values = module
+        .statements
+        .iter()
+        .map(|statement| match &*statement.read() {
+            Statement::Assignment(assignment) => match &*assignment.value.read() {
+                Value::True(_) => "true".to_string(),
+                Value::False(_) => "false".to_string(),
+                Value::Integer(integer) => integer.0.to_string(),
+            },
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(values, vec!["true", "42", "false"]);
+}
+
+#[test]
+fn choice_features() {
+    let workspace = create_workspace(SOURCE);
+    let document = &workspace.document;
+
+    let ast = workspace.ast.as_ref().unwrap().read();
+    let module = ast.downcast_ref::<Module>().unwrap();
+
+    // Generated from the text of the enum instead of the variant
+    let hover = module.statements[1].read().get_hover(document).unwrap();
+    assert_eq!(hover_text(hover), "```python\ny = 42\n```");
+
+    // Implemented by the user instead of the variant
+    let hovers = module
+        .statements
+        .iter()
+        .map(|statement| match &*statement.read() {
+            Statement::Assignment(assignment) => {
+                hover_text(assignment.value.read().get_hover(document).unwrap())
+            }
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(hovers, vec!["bool", "int = 42", "bool"]);
+}
@@ -0,0 +1,79 @@
+use crate::codegen::generate;
+use crate::core::ast::AstSymbol;
+
+// Generated from the node types of tree-sitter-python, see `up_to_date`
+#[allow(clippy::enum_variant_names)]
+mod ast {
+    use crate as auto_lsp;
+
+    include!("codegen/python.rs");
+}
+
+use ast::*;
+
+static CORE_QUERY: &str = include_str!("codegen/python.scm");
+
+configure_python_test!(Module, CORE_QUERY);
+
+#[test]
+fn up_to_date() {
+    let generated = generate(tree_sitter_python::NODE_TYPES, "module").unwrap();
+
+    assert_eq!(generated.ast, include_str!("codegen/python.rs"));
+    assert_eq!(generated.query, CORE_QUERY);
+}
+
+#[test]
+fn binary_operator() {
+    let workspace = create_workspace("a + b\n");
+    assert!(workspace.errors.is_empty(), "{:?}", workspace.errors);
+
+    let source = workspace.document.document.text.as_bytes();
+    let ast = workspace.ast.as_ref().unwrap().read();
+    let module = ast.downcast_ref::<Module>().unwrap();
+
+    let ModuleChildren::SimpleStatement(SimpleStatement::ExpressionStatement(statement)) =
+        &*module.children[0].read()
+    else {
+        panic!("Expected an expression statement");
+    };
+    let ExpressionStatementChildren::Expression(Expression::PrimaryExpression(
+        PrimaryExpression::BinaryOperator(operator),
+    )) = &*statement.children[0].read()
+    else {
+        panic!("Expected a binary operator");
+    };
+
+    // Both operands share their query names, they are told apart by their tree-sitter field
+    assert_eq!(operator.left.read().get_text(source), Some("a"));
+    assert_eq!(operator.right.read().get_text(source), Some("b"));
+}
+
+#[test]
+fn assignment() {
+    let workspace = create_workspace("x: int = a * 2\n");
+    assert!(workspace.errors.is_empty(), "{:?}", workspace.errors);
+
+    let source = workspace.document.document.text.as_bytes();
+    let ast = workspace.ast.as_ref().unwrap().read();
+    let module = ast.downcast_ref::<Module>().unwrap();
+
+    let ModuleChildren::SimpleStatement(SimpleStatement::ExpressionStatement(statement)) =
+        &*module.children[0].read()
+    else {
+        panic!("Expected an expression statement");
+    };
+    let ExpressionStatementChildren::Assignment(assignment) = &*statement.children[0].read() else {
+        panic!("Expected an assignment");
+    };
+
+    assert_eq!(assignment.left.read().get_text(source), Some("x"));
+    assert_eq!(
+        assignment.r#type.as_ref().unwrap().read().get_text(source),
+        Some("int")
+    );
+    assert_eq!(
+        assignment.right.as_ref().unwrap().read().get_text(source),
+        Some("a * 2")
+    );
+}
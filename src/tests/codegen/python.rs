@@ -0,0 +1,1280 @@
+use auto_lsp::core::ast::*;
+use auto_lsp::{choice, seq};
+
+#[choice]
+pub enum ModuleChildren {
+    CompoundStatement(CompoundStatement),
+    SimpleStatement(SimpleStatement),
+}
+
+#[seq(node_kind = "module", kind(symbol()))]
+pub struct Module {
+    pub children: Vec<ModuleChildren>,
+}
+
+#[choice]
+pub enum CompoundStatement {
+    ClassDefinition(ClassDefinition),
+    DecoratedDefinition(DecoratedDefinition),
+    ForStatement(ForStatement),
+    FunctionDefinition(FunctionDefinition),
+    IfStatement(IfStatement),
+    MatchStatement(MatchStatement),
+    TryStatement(TryStatement),
+    WhileStatement(WhileStatement),
+    WithStatement(WithStatement),
+}
+
+#[choice]
+pub enum SimpleStatement {
+    AssertStatement(AssertStatement),
+    BreakStatement(BreakStatement),
+    ContinueStatement(ContinueStatement),
+    DeleteStatement(DeleteStatement),
+    ExecStatement(ExecStatement),
+    ExpressionStatement(ExpressionStatement),
+    FutureImportStatement(FutureImportStatement),
+    GlobalStatement(GlobalStatement),
+    ImportFromStatement(ImportFromStatement),
+    ImportStatement(ImportStatement),
+    NonlocalStatement(NonlocalStatement),
+    PassStatement(PassStatement),
+    PrintStatement(PrintStatement),
+    RaiseStatement(RaiseStatement),
+    ReturnStatement(ReturnStatement),
+    TypeAliasStatement(TypeAliasStatement),
+}
+
+#[seq(node_kind = "class_definition", kind(symbol()))]
+pub struct ClassDefinition {
+    #[ast(field = "body")]
+    pub body: Block,
+    #[ast(field = "name")]
+    pub name: Identifier,
+    #[ast(field = "superclasses")]
+    pub superclasses: Option<ArgumentList>,
+    #[ast(field = "type_parameters")]
+    pub type_parameters: Option<TypeParameter>,
+}
+
+#[choice]
+pub enum DecoratedDefinitionDefinition {
+    ClassDefinition(ClassDefinition),
+    FunctionDefinition(FunctionDefinition),
+}
+
+#[seq(node_kind = "decorated_definition", kind(symbol()))]
+pub struct DecoratedDefinition {
+    #[ast(field = "definition")]
+    pub definition: DecoratedDefinitionDefinition,
+    pub children: Vec<Decorator>,
+}
+
+#[choice]
+pub enum ForStatementLeft {
+    Pattern(Pattern),
+    PatternList(PatternList),
+}
+
+#[choice]
+pub enum ForStatementRight {
+    Expression(Expression),
+    ExpressionList(ExpressionList),
+}
+
+#[seq(node_kind = "for_statement", kind(symbol()))]
+pub struct ForStatement {
+    #[ast(field = "alternative")]
+    pub alternative: Option<ElseClause>,
+    #[ast(field = "body")]
+    pub body: Block,
+    #[ast(field = "left")]
+    pub left: ForStatementLeft,
+    #[ast(field = "right")]
+    pub right: ForStatementRight,
+}
+
+#[seq(node_kind = "function_definition", kind(symbol()))]
+pub struct FunctionDefinition {
+    #[ast(field = "body")]
+    pub body: Block,
+    #[ast(field = "name")]
+    pub name: Identifier,
+    #[ast(field = "parameters")]
+    pub parameters: Parameters,
+    #[ast(field = "return_type")]
+    pub return_type: Option<Type>,
+    #[ast(field = "type_parameters")]
+    pub type_parameters: Option<TypeParameter>,
+}
+
+#[choice]
+pub enum IfStatementAlternative {
+    ElifClause(ElifClause),
+    ElseClause(ElseClause),
+}
+
+#[seq(node_kind = "if_statement", kind(symbol()))]
+pub struct IfStatement {
+    #[ast(field = "alternative")]
+    pub alternative: Vec<IfStatementAlternative>,
+    #[ast(field = "condition")]
+    pub condition: Expression,
+    #[ast(field = "consequence")]
+    pub consequence: Block,
+}
+
+#[seq(node_kind = "match_statement", kind(symbol()))]
+pub struct MatchStatement {
+    #[ast(field = "body")]
+    pub body: Block,
+    #[ast(field = "subject")]
+    pub subject: Vec<Expression>,
+}
+
+#[choice]
+pub enum TryStatementChildren {
+    ElseClause(ElseClause),
+    ExceptClause(ExceptClause),
+    ExceptGroupClause(ExceptGroupClause),
+    FinallyClause(FinallyClause),
+}
+
+#[seq(node_kind = "try_statement", kind(symbol()))]
+pub struct TryStatement {
+    #[ast(field = "body")]
+    pub body: Block,
+    pub children: Vec<TryStatementChildren>,
+}
+
+#[seq(node_kind = "while_statement", kind(symbol()))]
+pub struct WhileStatement {
+    #[ast(field = "alternative")]
+    pub alternative: Option<ElseClause>,
+    #[ast(field = "body")]
+    pub body: Block,
+    #[ast(field = "condition")]
+    pub condition: Expression,
+}
+
+#[seq(node_kind = "with_statement", kind(symbol()))]
+pub struct WithStatement {
+    #[ast(field = "body")]
+    pub body: Block,
+    pub children: WithClause,
+}
+
+#[seq(node_kind = "assert_statement", kind(symbol()))]
+pub struct AssertStatement {
+    pub children: Vec<Expression>,
+}
+
+#[seq(node_kind = "break_statement", kind(symbol()))]
+pub struct BreakStatement {}
+
+#[seq(node_kind = "continue_statement", kind(symbol()))]
+pub struct ContinueStatement {}
+
+#[choice]
+pub enum DeleteStatementChildren {
+    Expression(Expression),
+    ExpressionList(ExpressionList),
+}
+
+#[seq(node_kind = "delete_statement", kind(symbol()))]
+pub struct DeleteStatement {
+    pub children: DeleteStatementChildren,
+}
+
+#[choice]
+pub enum ExecStatementCode {
+    Identifier(Identifier),
+    StringNode(StringNode),
+}
+
+#[seq(node_kind = "exec_statement", kind(symbol()))]
+pub struct ExecStatement {
+    #[ast(field = "code")]
+    pub code: ExecStatementCode,
+    pub children: Vec<Expression>,
+}
+
+#[choice]
+pub enum ExpressionStatementChildren {
+    Assignment(Assignment),
+    AugmentedAssignment(AugmentedAssignment),
+    Expression(Expression),
+    Yield(Yield),
+}
+
+#[seq(node_kind = "expression_statement", kind(symbol()))]
+pub struct ExpressionStatement {
+    pub children: Vec<ExpressionStatementChildren>,
+}
+
+#[choice]
+pub enum FutureImportStatementName {
+    AliasedImport(AliasedImport),
+    DottedName(DottedName),
+}
+
+#[seq(node_kind = "future_import_statement", kind(symbol()))]
+pub struct FutureImportStatement {
+    #[ast(field = "name")]
+    pub name: Vec<FutureImportStatementName>,
+}
+
+#[seq(node_kind = "global_statement", kind(symbol()))]
+pub struct GlobalStatement {
+    pub children: Vec<Identifier>,
+}
+
+#[choice]
+pub enum ImportFromStatementModuleName {
+    DottedName(DottedName),
+    RelativeImport(RelativeImport),
+}
+
+#[choice]
+pub enum ImportFromStatementName {
+    AliasedImport(AliasedImport),
+    DottedName(DottedName),
+}
+
+#[seq(node_kind = "import_from_statement", kind(symbol()))]
+pub struct ImportFromStatement {
+    #[ast(field = "module_name")]
+    pub module_name: ImportFromStatementModuleName,
+    #[ast(field = "name")]
+    pub name: Vec<ImportFromStatementName>,
+    pub children: Option<WildcardImport>,
+}
+
+#[choice]
+pub enum ImportStatementName {
+    AliasedImport(AliasedImport),
+    DottedName(DottedName),
+}
+
+#[seq(node_kind = "import_statement", kind(symbol()))]
+pub struct ImportStatement {
+    #[ast(field = "name")]
+    pub name: Vec<ImportStatementName>,
+}
+
+#[seq(node_kind = "nonlocal_statement", kind(symbol()))]
+pub struct NonlocalStatement {
+    pub children: Vec<Identifier>,
+}
+
+#[seq(node_kind = "pass_statement", kind(symbol()))]
+pub struct PassStatement {}
+
+#[seq(node_kind = "print_statement", kind(symbol()))]
+pub struct PrintStatement {
+    #[ast(field = "argument")]
+    pub argument: Vec<Expression>,
+    pub children: Option<Chevron>,
+}
+
+#[choice]
+pub enum RaiseStatementChildren {
+    Expression(Expression),
+    ExpressionList(ExpressionList),
+}
+
+#[seq(node_kind = "raise_statement", kind(symbol()))]
+pub struct RaiseStatement {
+    #[ast(field = "cause")]
+    pub cause: Option<Expression>,
+    pub children: Option<RaiseStatementChildren>,
+}
+
+#[choice]
+pub enum ReturnStatementChildren {
+    Expression(Expression),
+    ExpressionList(ExpressionList),
+}
+
+#[seq(node_kind = "return_statement", kind(symbol()))]
+pub struct ReturnStatement {
+    pub children: Option<ReturnStatementChildren>,
+}
+
+#[seq(node_kind = "type_alias_statement", kind(symbol()))]
+pub struct TypeAliasStatement {
+    #[ast(field = "left")]
+    pub left: Type,
+    #[ast(field = "right")]
+    pub right: Type,
+}
+
+#[choice]
+pub enum BlockChildren {
+    CompoundStatement(CompoundStatement),
+    SimpleStatement(SimpleStatement),
+}
+
+#[seq(node_kind = "block", kind(symbol()))]
+pub struct Block {
+    #[ast(field = "alternative")]
+    pub alternative: Vec<CaseClause>,
+    pub children: Vec<BlockChildren>,
+}
+
+#[seq(node_kind = "identifier", kind(symbol()))]
+pub struct Identifier {}
+
+#[choice]
+pub enum ArgumentListChildren {
+    DictionarySplat(DictionarySplat),
+    Expression(Expression),
+    KeywordArgument(KeywordArgument),
+}
+
+#[seq(node_kind = "argument_list", kind(symbol()))]
+pub struct ArgumentList {
+    pub children: Vec<ArgumentListChildren>,
+}
+
+#[seq(node_kind = "type_parameter", kind(symbol()))]
+pub struct TypeParameter {
+    pub children: Vec<Type>,
+}
+
+#[seq(node_kind = "decorator", kind(symbol()))]
+pub struct Decorator {
+    pub children: Expression,
+}
+
+#[seq(node_kind = "else_clause", kind(symbol()))]
+pub struct ElseClause {
+    #[ast(field = "body")]
+    pub body: Block,
+}
+
+#[choice]
+pub enum Pattern {
+    Attribute(Attribute),
+    Identifier(Identifier),
+    ListPattern(ListPattern),
+    ListSplatPattern(ListSplatPattern),
+    Subscript(Subscript),
+    TuplePattern(TuplePattern),
+}
+
+#[seq(node_kind = "pattern_list", kind(symbol()))]
+pub struct PatternList {
+    pub children: Vec<Pattern>,
+}
+
+#[choice]
+pub enum Expression {
+    AsPattern(AsPattern),
+    BooleanOperator(BooleanOperator),
+    ComparisonOperator(ComparisonOperator),
+    ConditionalExpression(ConditionalExpression),
+    Lambda(Lambda),
+    NamedExpression(NamedExpression),
+    NotOperator(NotOperator),
+    PrimaryExpression(PrimaryExpression),
+}
+
+#[seq(node_kind = "expression_list", kind(symbol()))]
+pub struct ExpressionList {
+    pub children: Vec<Expression>,
+}
+
+#[seq(node_kind = "parameters", kind(symbol()))]
+pub struct Parameters {
+    pub children: Vec<Parameter>,
+}
+
+#[choice]
+pub enum TypeChildren {
+    ConstrainedType(ConstrainedType),
+    Expression(Expression),
+    GenericType(GenericType),
+    MemberType(MemberType),
+    SplatType(SplatType),
+    UnionType(UnionType),
+}
+
+#[seq(node_kind = "type", kind(symbol()))]
+pub struct Type {
+    pub children: TypeChildren,
+}
+
+#[seq(node_kind = "elif_clause", kind(symbol()))]
+pub struct ElifClause {
+    #[ast(field = "condition")]
+    pub condition: Expression,
+    #[ast(field = "consequence")]
+    pub consequence: Block,
+}
+
+#[seq(node_kind = "except_clause", kind(symbol()))]
+pub struct ExceptClause {
+    #[ast(field = "alias")]
+    pub alias: Option<Expression>,
+    #[ast(field = "value")]
+    pub value: Option<Expression>,
+    pub children: Block,
+}
+
+#[choice]
+pub enum ExceptGroupClauseChildren {
+    Block(Block),
+    Expression(Expression),
+}
+
+#[seq(node_kind = "except_group_clause", kind(symbol()))]
+pub struct ExceptGroupClause {
+    pub children: Vec<ExceptGroupClauseChildren>,
+}
+
+#[seq(node_kind = "finally_clause", kind(symbol()))]
+pub struct FinallyClause {
+    pub children: Block,
+}
+
+#[seq(node_kind = "with_clause", kind(symbol()))]
+pub struct WithClause {
+    pub children: Vec<WithItem>,
+}
+
+#[choice]
+pub enum StringNodeChildren {
+    Interpolation(Interpolation),
+    StringContent(StringContent),
+    StringEnd(StringEnd),
+    StringStart(StringStart),
+}
+
+#[seq(node_kind = "string", kind(symbol()))]
+pub struct StringNode {
+    pub children: Vec<StringNodeChildren>,
+}
+
+#[choice]
+pub enum AssignmentLeft {
+    Pattern(Pattern),
+    PatternList(PatternList),
+}
+
+#[choice]
+pub enum AssignmentRight {
+    Assignment(Assignment),
+    AugmentedAssignment(AugmentedAssignment),
+    Expression(Expression),
+    ExpressionList(ExpressionList),
+    PatternList(PatternList),
+    Yield(Yield),
+}
+
+#[seq(node_kind = "assignment", kind(symbol()))]
+pub struct Assignment {
+    #[ast(field = "left")]
+    pub left: AssignmentLeft,
+    #[ast(field = "right")]
+    pub right: Option<AssignmentRight>,
+    #[ast(field = "type")]
+    pub r#type: Option<Type>,
+}
+
+#[choice]
+pub enum AugmentedAssignmentLeft {
+    Pattern(Pattern),
+    PatternList(PatternList),
+}
+
+#[choice]
+pub enum AugmentedAssignmentRight {
+    Assignment(Assignment),
+    AugmentedAssignment(AugmentedAssignment),
+    Expression(Expression),
+    ExpressionList(ExpressionList),
+    PatternList(PatternList),
+    Yield(Yield),
+}
+
+#[seq(node_kind = "augmented_assignment", kind(symbol()))]
+pub struct AugmentedAssignment {
+    #[ast(field = "left")]
+    pub left: AugmentedAssignmentLeft,
+    #[ast(field = "right")]
+    pub right: AugmentedAssignmentRight,
+}
+
+#[choice]
+pub enum YieldChildren {
+    Expression(Expression),
+    ExpressionList(ExpressionList),
+}
+
+#[seq(node_kind = "yield", kind(symbol()))]
+pub struct Yield {
+    pub children: Option<YieldChildren>,
+}
+
+#[seq(node_kind = "aliased_import", kind(symbol()))]
+pub struct AliasedImport {
+    #[ast(field = "alias")]
+    pub alias: Identifier,
+    #[ast(field = "name")]
+    pub name: DottedName,
+}
+
+#[seq(node_kind = "dotted_name", kind(symbol()))]
+pub struct DottedName {
+    pub children: Vec<Identifier>,
+}
+
+#[choice]
+pub enum RelativeImportChildren {
+    DottedName(DottedName),
+    ImportPrefix(ImportPrefix),
+}
+
+#[seq(node_kind = "relative_import", kind(symbol()))]
+pub struct RelativeImport {
+    pub children: Vec<RelativeImportChildren>,
+}
+
+#[seq(node_kind = "wildcard_import", kind(symbol()))]
+pub struct WildcardImport {}
+
+#[seq(node_kind = "chevron", kind(symbol()))]
+pub struct Chevron {
+    pub children: Expression,
+}
+
+#[seq(node_kind = "case_clause", kind(symbol()))]
+pub struct CaseClause {
+    #[ast(field = "consequence")]
+    pub consequence: Block,
+    #[ast(field = "guard")]
+    pub guard: Option<IfClause>,
+    pub children: Vec<CasePattern>,
+}
+
+#[seq(node_kind = "dictionary_splat", kind(symbol()))]
+pub struct DictionarySplat {
+    pub children: Expression,
+}
+
+#[seq(node_kind = "keyword_argument", kind(symbol()))]
+pub struct KeywordArgument {
+    #[ast(field = "name")]
+    pub name: Identifier,
+    #[ast(field = "value")]
+    pub value: Expression,
+}
+
+#[seq(node_kind = "attribute", kind(symbol()))]
+pub struct Attribute {
+    #[ast(field = "attribute")]
+    pub attribute: Identifier,
+    #[ast(field = "object")]
+    pub object: PrimaryExpression,
+}
+
+#[choice]
+pub enum ListPatternChildren {
+    CasePattern(CasePattern),
+    Pattern(Pattern),
+}
+
+#[seq(node_kind = "list_pattern", kind(symbol()))]
+pub struct ListPattern {
+    pub children: Vec<ListPatternChildren>,
+}
+
+#[choice]
+pub enum ListSplatPatternChildren {
+    Attribute(Attribute),
+    Identifier(Identifier),
+    Subscript(Subscript),
+}
+
+#[seq(node_kind = "list_splat_pattern", kind(symbol()))]
+pub struct ListSplatPattern {
+    pub children: ListSplatPatternChildren,
+}
+
+#[choice]
+pub enum SubscriptSubscript {
+    Expression(Expression),
+    Slice(Slice),
+}
+
+#[seq(node_kind = "subscript", kind(symbol()))]
+pub struct Subscript {
+    #[ast(field = "subscript")]
+    pub subscript: Vec<SubscriptSubscript>,
+    #[ast(field = "value")]
+    pub value: PrimaryExpression,
+}
+
+#[choice]
+pub enum TuplePatternChildren {
+    CasePattern(CasePattern),
+    Pattern(Pattern),
+}
+
+#[seq(node_kind = "tuple_pattern", kind(symbol()))]
+pub struct TuplePattern {
+    pub children: Vec<TuplePatternChildren>,
+}
+
+#[choice]
+pub enum AsPatternChildren {
+    CasePattern(CasePattern),
+    Expression(Expression),
+}
+
+#[seq(node_kind = "as_pattern", kind(symbol()))]
+pub struct AsPattern {
+    #[ast(field = "alias")]
+    pub alias: Option<AsPatternTarget>,
+    pub children: Vec<AsPatternChildren>,
+}
+
+#[seq(node_kind = "boolean_operator", kind(symbol()))]
+pub struct BooleanOperator {
+    #[ast(field = "left")]
+    pub left: Expression,
+    #[ast(field = "right")]
+    pub right: Expression,
+}
+
+#[seq(node_kind = "comparison_operator", kind(symbol()))]
+pub struct ComparisonOperator {
+    pub children: Vec<PrimaryExpression>,
+}
+
+#[seq(node_kind = "conditional_expression", kind(symbol()))]
+pub struct ConditionalExpression {
+    pub children: Vec<Expression>,
+}
+
+#[seq(node_kind = "lambda", kind(symbol()))]
+pub struct Lambda {
+    #[ast(field = "body")]
+    pub body: Expression,
+    #[ast(field = "parameters")]
+    pub parameters: Option<LambdaParameters>,
+}
+
+#[seq(node_kind = "named_expression", kind(symbol()))]
+pub struct NamedExpression {
+    #[ast(field = "name")]
+    pub name: Identifier,
+    #[ast(field = "value")]
+    pub value: Expression,
+}
+
+#[seq(node_kind = "not_operator", kind(symbol()))]
+pub struct NotOperator {
+    #[ast(field = "argument")]
+    pub argument: Expression,
+}
+
+#[choice]
+pub enum PrimaryExpression {
+    Attribute(Attribute),
+    Await(Await),
+    BinaryOperator(BinaryOperator),
+    Call(Call),
+    ConcatenatedString(ConcatenatedString),
+    Dictionary(Dictionary),
+    DictionaryComprehension(DictionaryComprehension),
+    Ellipsis(Ellipsis),
+    False(False),
+    Float(Float),
+    GeneratorExpression(GeneratorExpression),
+    Identifier(Identifier),
+    Integer(Integer),
+    List(List),
+    ListComprehension(ListComprehension),
+    ListSplat(ListSplat),
+    NoneNode(NoneNode),
+    ParenthesizedExpression(ParenthesizedExpression),
+    Set(Set),
+    SetComprehension(SetComprehension),
+    StringNode(StringNode),
+    Subscript(Subscript),
+    True(True),
+    Tuple(Tuple),
+    UnaryOperator(UnaryOperator),
+}
+
+#[choice]
+pub enum Parameter {
+    DefaultParameter(DefaultParameter),
+    DictionarySplatPattern(DictionarySplatPattern),
+    Identifier(Identifier),
+    KeywordSeparator(KeywordSeparator),
+    ListSplatPattern(ListSplatPattern),
+    PositionalSeparator(PositionalSeparator),
+    TuplePattern(TuplePattern),
+    TypedDefaultParameter(TypedDefaultParameter),
+    TypedParameter(TypedParameter),
+}
+
+#[seq(node_kind = "constrained_type", kind(symbol()))]
+pub struct ConstrainedType {
+    pub children: Vec<Type>,
+}
+
+#[choice]
+pub enum GenericTypeChildren {
+    Identifier(Identifier),
+    TypeParameter(TypeParameter),
+}
+
+#[seq(node_kind = "generic_type", kind(symbol()))]
+pub struct GenericType {
+    pub children: Vec<GenericTypeChildren>,
+}
+
+#[choice]
+pub enum MemberTypeChildren {
+    Identifier(Identifier),
+    Type(Type),
+}
+
+#[seq(node_kind = "member_type", kind(symbol()))]
+pub struct MemberType {
+    pub children: Vec<MemberTypeChildren>,
+}
+
+#[seq(node_kind = "splat_type", kind(symbol()))]
+pub struct SplatType {
+    pub children: Identifier,
+}
+
+#[seq(node_kind = "union_type", kind(symbol()))]
+pub struct UnionType {
+    pub children: Vec<Type>,
+}
+
+#[seq(node_kind = "with_item", kind(symbol()))]
+pub struct WithItem {
+    #[ast(field = "value")]
+    pub value: Expression,
+}
+
+#[choice]
+pub enum InterpolationExpression {
+    Expression(Expression),
+    ExpressionList(ExpressionList),
+    PatternList(PatternList),
+    Yield(Yield),
+}
+
+#[seq(node_kind = "interpolation", kind(symbol()))]
+pub struct Interpolation {
+    #[ast(field = "expression")]
+    pub expression: InterpolationExpression,
+    #[ast(field = "format_specifier")]
+    pub format_specifier: Option<FormatSpecifier>,
+    #[ast(field = "type_conversion")]
+    pub type_conversion: Option<TypeConversion>,
+}
+
+#[choice]
+pub enum StringContentChildren {
+    EscapeInterpolation(EscapeInterpolation),
+    EscapeSequence(EscapeSequence),
+}
+
+#[seq(node_kind = "string_content", kind(symbol()))]
+pub struct StringContent {
+    pub children: Vec<StringContentChildren>,
+}
+
+#[seq(node_kind = "string_end", kind(symbol()))]
+pub struct StringEnd {}
+
+#[seq(node_kind = "string_start", kind(symbol()))]
+pub struct StringStart {}
+
+#[seq(node_kind = "import_prefix", kind(symbol()))]
+pub struct ImportPrefix {}
+
+#[seq(node_kind = "if_clause", kind(symbol()))]
+pub struct IfClause {
+    pub children: Expression,
+}
+
+#[choice]
+pub enum CasePatternChildren {
+    AsPattern(AsPattern),
+    ClassPattern(ClassPattern),
+    ComplexPattern(ComplexPattern),
+    ConcatenatedString(ConcatenatedString),
+    DictPattern(DictPattern),
+    DottedName(DottedName),
+    False(False),
+    Float(Float),
+    Integer(Integer),
+    KeywordPattern(KeywordPattern),
+    ListPattern(ListPattern),
+    NoneNode(NoneNode),
+    SplatPattern(SplatPattern),
+    StringNode(StringNode),
+    True(True),
+    TuplePattern(TuplePattern),
+    UnionPattern(UnionPattern),
+}
+
+#[seq(node_kind = "case_pattern", kind(symbol()))]
+pub struct CasePattern {
+    pub children: Option<CasePatternChildren>,
+}
+
+#[seq(node_kind = "slice", kind(symbol()))]
+pub struct Slice {
+    pub children: Vec<Expression>,
+}
+
+#[seq(node_kind = "as_pattern_target", kind(symbol()))]
+pub struct AsPatternTarget {}
+
+#[seq(node_kind = "lambda_parameters", kind(symbol()))]
+pub struct LambdaParameters {
+    pub children: Vec<Parameter>,
+}
+
+#[seq(node_kind = "await", kind(symbol()))]
+pub struct Await {
+    pub children: PrimaryExpression,
+}
+
+#[seq(node_kind = "binary_operator", kind(symbol()))]
+pub struct BinaryOperator {
+    #[ast(field = "left")]
+    pub left: PrimaryExpression,
+    #[ast(field = "right")]
+    pub right: PrimaryExpression,
+}
+
+#[choice]
+pub enum CallArguments {
+    ArgumentList(ArgumentList),
+    GeneratorExpression(GeneratorExpression),
+}
+
+#[seq(node_kind = "call", kind(symbol()))]
+pub struct Call {
+    #[ast(field = "arguments")]
+    pub arguments: CallArguments,
+    #[ast(field = "function")]
+    pub function: PrimaryExpression,
+}
+
+#[seq(node_kind = "concatenated_string", kind(symbol()))]
+pub struct ConcatenatedString {
+    pub children: Vec<StringNode>,
+}
+
+#[choice]
+pub enum DictionaryChildren {
+    DictionarySplat(DictionarySplat),
+    Pair(Pair),
+}
+
+#[seq(node_kind = "dictionary", kind(symbol()))]
+pub struct Dictionary {
+    pub children: Vec<DictionaryChildren>,
+}
+
+#[choice]
+pub enum DictionaryComprehensionChildren {
+    ForInClause(ForInClause),
+    IfClause(IfClause),
+}
+
+#[seq(node_kind = "dictionary_comprehension", kind(symbol()))]
+pub struct DictionaryComprehension {
+    #[ast(field = "body")]
+    pub body: Pair,
+    pub children: Vec<DictionaryComprehensionChildren>,
+}
+
+#[seq(node_kind = "ellipsis", kind(symbol()))]
+pub struct Ellipsis {}
+
+#[seq(node_kind = "false", kind(symbol()))]
+pub struct False {}
+
+#[seq(node_kind = "float", kind(symbol()))]
+pub struct Float {}
+
+#[choice]
+pub enum GeneratorExpressionChildren {
+    ForInClause(ForInClause),
+    IfClause(IfClause),
+}
+
+#[seq(node_kind = "generator_expression", kind(symbol()))]
+pub struct GeneratorExpression {
+    #[ast(field = "body")]
+    pub body: Expression,
+    pub children: Vec<GeneratorExpressionChildren>,
+}
+
+#[seq(node_kind = "integer", kind(symbol()))]
+pub struct Integer {}
+
+#[choice]
+pub enum ListChildren {
+    Expression(Expression),
+    ParenthesizedListSplat(ParenthesizedListSplat),
+    Yield(Yield),
+}
+
+#[seq(node_kind = "list", kind(symbol()))]
+pub struct List {
+    pub children: Vec<ListChildren>,
+}
+
+#[choice]
+pub enum ListComprehensionChildren {
+    ForInClause(ForInClause),
+    IfClause(IfClause),
+}
+
+#[seq(node_kind = "list_comprehension", kind(symbol()))]
+pub struct ListComprehension {
+    #[ast(field = "body")]
+    pub body: Expression,
+    pub children: Vec<ListComprehensionChildren>,
+}
+
+#[choice]
+pub enum ListSplatChildren {
+    Attribute(Attribute),
+    AsPattern(AsPattern),
+    BooleanOperator(BooleanOperator),
+    ComparisonOperator(ComparisonOperator),
+    ConditionalExpression(ConditionalExpression),
+    Lambda(Lambda),
+    NamedExpression(NamedExpression),
+    NotOperator(NotOperator),
+    Await(Await),
+    BinaryOperator(BinaryOperator),
+    Call(Call),
+    ConcatenatedString(ConcatenatedString),
+    Dictionary(Dictionary),
+    DictionaryComprehension(DictionaryComprehension),
+    Ellipsis(Ellipsis),
+    False(False),
+    Float(Float),
+    GeneratorExpression(GeneratorExpression),
+    Identifier(Identifier),
+    Integer(Integer),
+    List(List),
+    ListComprehension(ListComprehension),
+    ListSplat(ListSplat),
+    NoneNode(NoneNode),
+    ParenthesizedExpression(ParenthesizedExpression),
+    Set(Set),
+    SetComprehension(SetComprehension),
+    StringNode(StringNode),
+    Subscript(Subscript),
+    True(True),
+    Tuple(Tuple),
+    UnaryOperator(UnaryOperator),
+}
+
+#[seq(node_kind = "list_splat", kind(symbol()))]
+pub struct ListSplat {
+    pub children: ListSplatChildren,
+}
+
+#[seq(node_kind = "none", kind(symbol()))]
+pub struct NoneNode {}
+
+#[choice]
+pub enum ParenthesizedExpressionChildren {
+    Expression(Expression),
+    Yield(Yield),
+}
+
+#[seq(node_kind = "parenthesized_expression", kind(symbol()))]
+pub struct ParenthesizedExpression {
+    pub children: ParenthesizedExpressionChildren,
+}
+
+#[choice]
+pub enum SetChildren {
+    Expression(Expression),
+    ParenthesizedListSplat(ParenthesizedListSplat),
+    Yield(Yield),
+}
+
+#[seq(node_kind = "set", kind(symbol()))]
+pub struct Set {
+    pub children: Vec<SetChildren>,
+}
+
+#[choice]
+pub enum SetComprehensionChildren {
+    ForInClause(ForInClause),
+    IfClause(IfClause),
+}
+
+#[seq(node_kind = "set_comprehension", kind(symbol()))]
+pub struct SetComprehension {
+    #[ast(field = "body")]
+    pub body: Expression,
+    pub children: Vec<SetComprehensionChildren>,
+}
+
+#[seq(node_kind = "true", kind(symbol()))]
+pub struct True {}
+
+#[choice]
+pub enum TupleChildren {
+    Expression(Expression),
+    ParenthesizedListSplat(ParenthesizedListSplat),
+    Yield(Yield),
+}
+
+#[seq(node_kind = "tuple", kind(symbol()))]
+pub struct Tuple {
+    pub children: Vec<TupleChildren>,
+}
+
+#[seq(node_kind = "unary_operator", kind(symbol()))]
+pub struct UnaryOperator {
+    #[ast(field = "argument")]
+    pub argument: PrimaryExpression,
+}
+
+#[choice]
+pub enum DefaultParameterName {
+    Identifier(Identifier),
+    TuplePattern(TuplePattern),
+}
+
+#[seq(node_kind = "default_parameter", kind(symbol()))]
+pub struct DefaultParameter {
+    #[ast(field = "name")]
+    pub name: DefaultParameterName,
+    #[ast(field = "value")]
+    pub value: Expression,
+}
+
+#[choice]
+pub enum DictionarySplatPatternChildren {
+    Attribute(Attribute),
+    Identifier(Identifier),
+    Subscript(Subscript),
+}
+
+#[seq(node_kind = "dictionary_splat_pattern", kind(symbol()))]
+pub struct DictionarySplatPattern {
+    pub children: DictionarySplatPatternChildren,
+}
+
+#[seq(node_kind = "keyword_separator", kind(symbol()))]
+pub struct KeywordSeparator {}
+
+#[seq(node_kind = "positional_separator", kind(symbol()))]
+pub struct PositionalSeparator {}
+
+#[seq(node_kind = "typed_default_parameter", kind(symbol()))]
+pub struct TypedDefaultParameter {
+    #[ast(field = "name")]
+    pub name: Identifier,
+    #[ast(field = "type")]
+    pub r#type: Type,
+    #[ast(field = "value")]
+    pub value: Expression,
+}
+
+#[choice]
+pub enum TypedParameterChildren {
+    DictionarySplatPattern(DictionarySplatPattern),
+    Identifier(Identifier),
+    ListSplatPattern(ListSplatPattern),
+}
+
+#[seq(node_kind = "typed_parameter", kind(symbol()))]
+pub struct TypedParameter {
+    #[ast(field = "type")]
+    pub r#type: Type,
+    pub children: TypedParameterChildren,
+}
+
+#[seq(node_kind = "format_specifier", kind(symbol()))]
+pub struct FormatSpecifier {
+    pub children: Vec<FormatExpression>,
+}
+
+#[seq(node_kind = "type_conversion", kind(symbol()))]
+pub struct TypeConversion {}
+
+#[seq(node_kind = "escape_interpolation", kind(symbol()))]
+pub struct EscapeInterpolation {}
+
+#[seq(node_kind = "escape_sequence", kind(symbol()))]
+pub struct EscapeSequence {}
+
+#[choice]
+pub enum ClassPatternChildren {
+    CasePattern(CasePattern),
+    DottedName(DottedName),
+}
+
+#[seq(node_kind = "class_pattern", kind(symbol()))]
+pub struct ClassPattern {
+    pub children: Vec<ClassPatternChildren>,
+}
+
+#[choice]
+pub enum ComplexPatternChildren {
+    Float(Float),
+    Integer(Integer),
+}
+
+#[seq(node_kind = "complex_pattern", kind(symbol()))]
+pub struct ComplexPattern {
+    pub children: Vec<ComplexPatternChildren>,
+}
+
+#[choice]
+pub enum DictPatternKey {
+    ClassPattern(ClassPattern),
+    ComplexPattern(ComplexPattern),
+    ConcatenatedString(ConcatenatedString),
+    DictPattern(DictPattern),
+    DottedName(DottedName),
+    False(False),
+    Float(Float),
+    Integer(Integer),
+    ListPattern(ListPattern),
+    NoneNode(NoneNode),
+    SplatPattern(SplatPattern),
+    StringNode(StringNode),
+    True(True),
+    TuplePattern(TuplePattern),
+    UnionPattern(UnionPattern),
+}
+
+#[seq(node_kind = "dict_pattern", kind(symbol()))]
+pub struct DictPattern {
+    #[ast(field = "key")]
+    pub key: Vec<DictPatternKey>,
+    #[ast(field = "value")]
+    pub value: Vec<CasePattern>,
+    pub children: Vec<SplatPattern>,
+}
+
+#[choice]
+pub enum KeywordPatternChildren {
+    ClassPattern(ClassPattern),
+    ComplexPattern(ComplexPattern),
+    ConcatenatedString(ConcatenatedString),
+    DictPattern(DictPattern),
+    DottedName(DottedName),
+    False(False),
+    Float(Float),
+    Identifier(Identifier),
+    Integer(Integer),
+    ListPattern(ListPattern),
+    NoneNode(NoneNode),
+    SplatPattern(SplatPattern),
+    StringNode(StringNode),
+    True(True),
+    TuplePattern(TuplePattern),
+    UnionPattern(UnionPattern),
+}
+
+#[seq(node_kind = "keyword_pattern", kind(symbol()))]
+pub struct KeywordPattern {
+    pub children: Vec<KeywordPatternChildren>,
+}
+
+#[seq(node_kind = "splat_pattern", kind(symbol()))]
+pub struct SplatPattern {
+    pub children: Option<Identifier>,
+}
+
+#[choice]
+pub enum UnionPatternChildren {
+    ClassPattern(ClassPattern),
+    ComplexPattern(ComplexPattern),
+    ConcatenatedString(ConcatenatedString),
+    DictPattern(DictPattern),
+    DottedName(DottedName),
+    False(False),
+    Float(Float),
+    Integer(Integer),
+    ListPattern(ListPattern),
+    NoneNode(NoneNode),
+    SplatPattern(SplatPattern),
+    StringNode(StringNode),
+    True(True),
+    TuplePattern(TuplePattern),
+    UnionPattern(UnionPattern),
+}
+
+#[seq(node_kind = "union_pattern", kind(symbol()))]
+pub struct UnionPattern {
+    pub children: Vec<UnionPatternChildren>,
+}
+
+#[seq(node_kind = "pair", kind(symbol()))]
+pub struct Pair {
+    #[ast(field = "key")]
+    pub key: Expression,
+    #[ast(field = "value")]
+    pub value: Expression,
+}
+
+#[choice]
+pub enum ForInClauseLeft {
+    Pattern(Pattern),
+    PatternList(PatternList),
+}
+
+#[seq(node_kind = "for_in_clause", kind(symbol()))]
+pub struct ForInClause {
+    #[ast(field = "left")]
+    pub left: ForInClauseLeft,
+    #[ast(field = "right")]
+    pub right: Vec<Expression>,
+}
+
+#[choice]
+pub enum ParenthesizedListSplatChildren {
+    ListSplat(ListSplat),
+    ParenthesizedExpression(ParenthesizedExpression),
+}
+
+#[seq(node_kind = "parenthesized_list_splat", kind(symbol()))]
+pub struct ParenthesizedListSplat {
+    pub children: ParenthesizedListSplatChildren,
+}
+
+#[choice]
+pub enum FormatExpressionExpression {
+    Expression(Expression),
+    ExpressionList(ExpressionList),
+    PatternList(PatternList),
+    Yield(Yield),
+}
+
+#[seq(node_kind = "format_expression", kind(symbol()))]
+pub struct FormatExpression {
+    #[ast(field = "expression")]
+    pub expression: FormatExpressionExpression,
+    #[ast(field = "format_specifier")]
+    pub format_specifier: Option<FormatSpecifier>,
+    #[ast(field = "type_conversion")]
+    pub type_conversion: Option<TypeConversion>,
+}
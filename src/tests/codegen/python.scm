@@ -0,0 +1,122 @@
+(module) @module
+(class_definition) @class_definition
+(decorated_definition) @decorated_definition
+(for_statement) @for_statement
+(function_definition) @function_definition
+(if_statement) @if_statement
+(match_statement) @match_statement
+(try_statement) @try_statement
+(while_statement) @while_statement
+(with_statement) @with_statement
+(assert_statement) @assert_statement
+(break_statement) @break_statement
+(continue_statement) @continue_statement
+(delete_statement) @delete_statement
+(exec_statement) @exec_statement
+(expression_statement) @expression_statement
+(future_import_statement) @future_import_statement
+(global_statement) @global_statement
+(import_from_statement) @import_from_statement
+(import_statement) @import_statement
+(nonlocal_statement) @nonlocal_statement
+(pass_statement) @pass_statement
+(print_statement) @print_statement
+(raise_statement) @raise_statement
+(return_statement) @return_statement
+(type_alias_statement) @type_alias_statement
+(block) @block
+(identifier) @identifier
+(argument_list) @argument_list
+(type_parameter) @type_parameter
+(decorator) @decorator
+(else_clause) @else_clause
+(pattern_list) @pattern_list
+(expression_list) @expression_list
+(parameters) @parameters
+(type) @type
+(elif_clause) @elif_clause
+(except_clause) @except_clause
+(except_group_clause) @except_group_clause
+(finally_clause) @finally_clause
+(with_clause) @with_clause
+(string) @string
+(assignment) @assignment
+(augmented_assignment) @augmented_assignment
+(yield) @yield
+(aliased_import) @aliased_import
+(dotted_name) @dotted_name
+(relative_import) @relative_import
+(wildcard_import) @wildcard_import
+(chevron) @chevron
+(case_clause) @case_clause
+(dictionary_splat) @dictionary_splat
+(keyword_argument) @keyword_argument
+(attribute) @attribute
+(list_pattern) @list_pattern
+(list_splat_pattern) @list_splat_pattern
+(subscript) @subscript
+(tuple_pattern) @tuple_pattern
+(as_pattern) @as_pattern
+(boolean_operator) @boolean_operator
+(comparison_operator) @comparison_operator
+(conditional_expression) @conditional_expression
+(lambda) @lambda
+(named_expression) @named_expression
+(not_operator) @not_operator
+(constrained_type) @constrained_type
+(generic_type) @generic_type
+(member_type) @member_type
+(splat_type) @splat_type
+(union_type) @union_type
+(with_item) @with_item
+(interpolation) @interpolation
+(string_content) @string_content
+(string_end) @string_end
+(string_start) @string_start
+(import_prefix) @import_prefix
+(if_clause) @if_clause
+(case_pattern) @case_pattern
+(slice) @slice
+(as_pattern_target) @as_pattern_target
+(lambda_parameters) @lambda_parameters
+(await) @await
+(binary_operator) @binary_operator
+(call) @call
+(concatenated_string) @concatenated_string
+(dictionary) @dictionary
+(dictionary_comprehension) @dictionary_comprehension
+(ellipsis) @ellipsis
+(false) @false
+(float) @float
+(generator_expression) @generator_expression
+(integer) @integer
+(list) @list
+(list_comprehension) @list_comprehension
+(list_splat) @list_splat
+(none) @none
+(parenthesized_expression) @parenthesized_expression
+(set) @set
+(set_comprehension) @set_comprehension
+(true) @true
+(tuple) @tuple
+(unary_operator) @unary_operator
+(default_parameter) @default_parameter
+(dictionary_splat_pattern) @dictionary_splat_pattern
+(keyword_separator) @keyword_separator
+(positional_separator) @positional_separator
+(typed_default_parameter) @typed_default_parameter
+(typed_parameter) @typed_parameter
+(format_specifier) @format_specifier
+(type_conversion) @type_conversion
+(escape_interpolation) @escape_interpolation
+(escape_sequence) @escape_sequence
+(class_pattern) @class_pattern
+(complex_pattern) @complex_pattern
+(dict_pattern) @dict_pattern
+(keyword_pattern) @keyword_pattern
+(splat_pattern) @splat_pattern
+(union_pattern) @union_pattern
+(pair) @pair
+(for_in_clause) @for_in_clause
+(parenthesized_list_splat) @parenthesized_list_splat
+(format_expression) @format_expression
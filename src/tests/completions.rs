@@ -0,0 +1,81 @@
+use crate::core::ast::{AstSymbol, GetSymbolData, Scope, Symbol};
+use crate::seq;
+use crate::server::capabilities::completion_items::get_completion_items;
+
+use crate as auto_lsp;
+
+static CORE_QUERY: &str = "
+(module) @module
+
+(function_definition
+  name: (identifier) @name
+  body: (block) @body) @function
+
+(assignment
+  left: (identifier) @name) @assignment
+";
+
+configure_python_test!(Module, CORE_QUERY);
+
+#[seq(query_name = "module", kind(symbol()))]
+struct Module {
+    functions: Vec<Function>,
+}
+
+#[seq(query_name = "function", kind(symbol(
+    scope(user),
+    lsp_completion_items(code_gen(item(
+        label = self::name,
+        kind = auto_lsp::lsp_types::CompletionItemKind::FUNCTION,
+    )))
+)))]
+struct Function {
+    name: Name,
+    body: Body,
+}
+
+// The scope is made of the first and the last statements of the body
+impl Scope for Function {
+    fn get_scope_range(&self) -> Vec<[usize; 2]> {
+        let body = self.body.read();
+        let first = body.assignments.first().unwrap().read().get_range();
+        let last = body.assignments.last().unwrap().read().get_range();
+        vec![[first.start, first.end], [last.start, last.end]]
+    }
+}
+
+#[seq(query_name = "body", kind(symbol()))]
+struct Body {
+    assignments: Vec<Assignment>,
+}
+
+#[seq(query_name = "assignment", kind(symbol(lsp_completion_items(code_gen(item(
+    label = self::name,
+    kind = auto_lsp::lsp_types::CompletionItemKind::VARIABLE,
+))))))]
+struct Assignment {
+    name: Name,
+}
+
+#[seq(query_name = "name", kind(symbol()))]
+struct Name {}
+
+static SOURCE: &str = r#"def foo():
+    a = 1
+    b = 2
+    c = 3
+"#;
+
+#[test]
+fn scope_ranges() {
+    let workspace = create_workspace(SOURCE);
+    assert!(workspace.errors.is_empty());
+
+    let labels = get_completion_items(&workspace, SOURCE.find('3').unwrap())
+        .into_iter()
+        .map(|item| item.label)
+        .collect::<Vec<_>>();
+
+    // The body spans both ranges, its declarations are collected from each of them
+    assert_eq!(labels, vec!["a", "c", "foo"]);
+}
@@ -0,0 +1,130 @@
+use crate::core::ast::{AstSymbol, Symbol};
+use crate::core::build::derive_query;
+use crate::core::workspace::Parsers;
+use crate::seq;
+use crate::server::{create_parser, derive_core_query};
+
+use crate as auto_lsp;
+
+// The core query is derived from the symbols
+configure_python_test!(Module);
+
+#[seq(node_kind = "module", kind(symbol()))]
+struct Module {
+    functions: Vec<Function>,
+}
+
+#[seq(
+    node_kind = "function_definition",
+    query_name = "function",
+    kind(symbol())
+)]
+struct Function {
+    #[ast(field = "name")]
+    name: Identifier,
+    #[ast(field = "parameters")]
+    parameters: Parameters,
+    #[ast(text = "return_type", field = "return_type")]
+    return_type: Option<String>,
+}
+
+#[seq(node_kind = "parameters", kind(symbol()))]
+struct Parameters {
+    #[ast(capture = "parameter")]
+    names: Vec<Identifier>,
+}
+
+#[seq(node_kind = "identifier", kind(symbol()))]
+struct Identifier {}
+
+#[seq(query_name = "orphan", kind(symbol()))]
+struct Orphan {}
+
+#[seq(node_kind = "module", kind(symbol()))]
+struct Broken {
+    orphan: Option<Orphan>,
+    #[ast(text = "comment")]
+    comments: Vec<String>,
+}
+
+#[test]
+fn derived_query() {
+    assert_eq!(
+        derive_query::<Module>().unwrap(),
+        "(module) @module
+(function_definition) @function
+(function_definition name: (identifier) @identifier)
+(function_definition parameters: (parameters) @parameters)
+(parameters (identifier) @parameter)
+(function_definition return_type: (_) @return_type)
+"
+    );
+}
+
+#[test]
+fn build_with_derived_query() {
+    let workspace = create_workspace(
+        r#"def foo(a, b=1) -> int:
+    pass
+
+def bar(c):
+    pass
+"#,
+    );
+    assert!(workspace.errors.is_empty());
+    let source = workspace.document.document.text.as_bytes();
+
+    let ast = workspace.ast.as_ref().unwrap().read();
+    let module = ast.downcast_ref::<Module>().unwrap();
+    assert_eq!(module.functions.len(), 2);
+
+    let foo = module.functions[0].read();
+    assert_eq!(foo.name.read().get_text(source), Some("foo"));
+    assert_eq!(foo.return_type.as_deref(), Some("int"));
+
+    // Default parameters are not direct children of the parameters
+    let parameters = foo.parameters.read();
+    assert_eq!(parameters.names.len(), 1);
+    assert_eq!(parameters.names[0].read().get_text(source), Some("a"));
+
+    let bar = module.functions[1].read();
+    assert_eq!(bar.name.read().get_text(source), Some("bar"));
+    assert_eq!(bar.return_type, None);
+}
+
+#[test]
+fn invalid_derived_query() {
+    assert_eq!(
+        derive_query::<Broken>().unwrap_err(),
+        "Orphan has no node_kind
+Value field \"comment\" of \"module\" needs a tree-sitter field"
+    );
+}
+
+// The server falls back to an empty core query instead of panicking
+#[allow(clippy::result_large_err)]
+static FALLBACK: std::sync::LazyLock<Parsers> = std::sync::LazyLock::new(|| Parsers {
+    name: "python",
+    tree_sitter: create_parser(
+        tree_sitter_python::LANGUAGE,
+        tree_sitter_python::NODE_TYPES,
+        &derive_core_query::<Broken>("python"),
+        None,
+        None,
+        None,
+    ),
+    ast_parser: |params, range| {
+        use crate::core::build::StaticBuildable;
+
+        Ok(Symbol::new_and_check(Broken::static_build(params, range)?, params).to_dyn())
+    },
+});
+
+#[test]
+fn invalid_derived_query_fallback() {
+    assert_eq!(FALLBACK.tree_sitter.queries.core.pattern_count(), 0);
+
+    let workspace = super::create_workspace(&FALLBACK, "file:///test.py", "def foo(): pass", false);
+    assert!(workspace.ast.is_none());
+    assert_eq!(workspace.errors.len(), 1);
+}
@@ -75,6 +75,8 @@ fn create_html_workspace(uri: Url, source_code: String) -> Workspace {
     let document = Document {
         document: Text::new(source_code.into()),
         cst: tree,
+        comment_style: parse.tree_sitter.comment_style,
+        position_encoding: lsp_types::PositionEncodingKind::UTF8,
     };
 
     let mut diagnostics = vec![];
@@ -88,6 +90,8 @@ fn create_html_workspace(uri: Url, source_code: String) -> Workspace {
         diagnostics: &mut diagnostics,
         unsolved_checks: &mut unsolved_checks,
         unsolved_references: &mut unsolved_references,
+        changes: vec![],
+        lenient: false,
     };
 
     let ast_parser = parse.ast_parser;
@@ -0,0 +1,185 @@
+use crate::core::ast::{AstSymbol, BuildDocumentSymbols, GetSymbolData, Symbol, VecOrSymbol};
+use crate::core::workspace::Document;
+use crate::seq;
+
+use crate as auto_lsp;
+
+// Functions without a return type are missing a mandatory field
+static CORE_QUERY: &str = "
+(module) @module
+
+(function_definition
+  name: (identifier) @name) @function
+
+(function_definition
+  return_type: (type) @return_type)
+";
+
+configure_python_test!(Module, CORE_QUERY);
+
+#[seq(query_name = "module", kind(symbol(lsp_document_symbols(user))))]
+struct Module {
+    functions: Vec<Function>,
+}
+
+impl BuildDocumentSymbols for Module {
+    fn get_document_symbols(&self, doc: &Document) -> Option<VecOrSymbol> {
+        self.functions.get_document_symbols(doc)
+    }
+}
+
+#[seq(query_name = "function", kind(symbol(lsp_document_symbols(code_gen(
+    name = self::name,
+    kind = auto_lsp::lsp_types::SymbolKind::FUNCTION,
+)))))]
+struct Function {
+    name: Name,
+    return_type: ReturnType,
+}
+
+#[seq(query_name = "name", kind(symbol()))]
+struct Name {}
+
+#[seq(query_name = "return_type", kind(symbol()))]
+struct ReturnType {}
+
+static SOURCE: &str = r#"def foo() -> int:
+    pass
+
+def bar():
+    pass
+
+def baz() -> str:
+    pass
+"#;
+
+#[test]
+fn strict_build() {
+    let workspace = create_workspace(SOURCE);
+
+    // bar has no return type, so the whole module fails
+
+    assert!(workspace.ast.is_none());
+    assert_eq!(workspace.errors.len(), 1);
+}
+
+#[test]
+fn lenient_build() {
+    let parsers = PARSERS.get("python").unwrap();
+    let workspace = super::create_workspace(parsers, "file:///test.py", SOURCE, true);
+    let source = workspace.document.document.text.as_bytes();
+
+    // bar is reported and built with a placeholder return type
+
+    let ast = workspace.ast.as_ref().unwrap().read();
+    let module = ast.downcast_ref::<Module>().unwrap();
+    let names = module
+        .functions
+        .iter()
+        .map(|f| f.read().name.read().get_text(source).unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["foo", "bar", "baz"]);
+
+    let return_type = module.functions[1].read().return_type.read().get_range();
+    assert!(return_type.is_empty());
+
+    assert_eq!(workspace.errors.len(), 1);
+    assert_eq!(workspace.errors[0].range.start.line, 3);
+
+    // Capabilities work on the built symbols
+
+    let symbols = match module.get_document_symbols(&workspace.document) {
+        Some(VecOrSymbol::Vec(symbols)) => symbols,
+        _ => panic!("Expected document symbols"),
+    };
+    assert_eq!(
+        symbols.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(),
+        vec!["foo", "bar", "baz"]
+    );
+}
+
+// The root symbol misses a mandatory field
+mod missing_root_field {
+    use crate::core::ast::{AstSymbol, BuildDocumentSymbols, GetSymbolData, Symbol, VecOrSymbol};
+    use crate::core::workspace::Document;
+    use crate::seq;
+
+    use crate as auto_lsp;
+
+    static CORE_QUERY: &str = "
+(module) @module
+
+(function_definition
+  name: (identifier) @name) @function
+
+(assignment
+  left: (identifier) @name) @assignment
+";
+
+    configure_python_test!(Module, CORE_QUERY);
+
+    #[seq(query_name = "module", kind(symbol(lsp_document_symbols(user))))]
+    struct Module {
+        main: Function,
+        assignments: Vec<Assignment>,
+    }
+
+    impl BuildDocumentSymbols for Module {
+        fn get_document_symbols(&self, doc: &Document) -> Option<VecOrSymbol> {
+            self.assignments.get_document_symbols(doc)
+        }
+    }
+
+    #[seq(query_name = "function", kind(symbol()))]
+    struct Function {
+        name: Name,
+    }
+
+    #[seq(query_name = "assignment", kind(symbol(lsp_document_symbols(code_gen(
+        name = self::name,
+        kind = auto_lsp::lsp_types::SymbolKind::VARIABLE,
+    )))))]
+    struct Assignment {
+        name: Name,
+    }
+
+    #[seq(query_name = "name", kind(symbol()))]
+    struct Name {}
+
+    static SOURCE: &str = "x = 1\ny = 2\n";
+
+    #[test]
+    fn strict_build() {
+        let workspace = create_workspace(SOURCE);
+
+        assert!(workspace.ast.is_none());
+        assert_eq!(workspace.errors.len(), 1);
+    }
+
+    #[test]
+    fn lenient_build() {
+        let parsers = PARSERS.get("python").unwrap();
+        let workspace = crate::tests::create_workspace(parsers, "file:///test.py", SOURCE, true);
+
+        // The root is built with a placeholder function
+
+        let ast = workspace.ast.as_ref().unwrap().read();
+        let module = ast.downcast_ref::<Module>().unwrap();
+
+        let main = module.main.read();
+        assert!(main.get_range().is_empty());
+        assert!(main.name.read().get_range().is_empty());
+        assert_eq!(module.assignments.len(), 2);
+
+        assert_eq!(workspace.errors.len(), 1);
+
+        let symbols = match module.get_document_symbols(&workspace.document) {
+            Some(VecOrSymbol::Vec(symbols)) => symbols,
+            _ => panic!("Expected document symbols"),
+        };
+        assert_eq!(
+            symbols.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(),
+            vec!["x", "y"]
+        );
+    }
+}
@@ -1,2 +1,103 @@
+use crate::core::build::MainBuilder;
+use crate::core::workspace::{Document, Parsers, Workspace};
+use lsp_types::Url;
+use std::sync::Arc;
+use texter::core::text::Text;
+
+/// Configure the `python` parser of a test AST with [`crate::configure_parsers`],
+/// and a `create_workspace` function building a document with it, see [`create_workspace`].
+///
+/// The core query is derived from the root when it is omitted.
+macro_rules! configure_python_test {
+    ($root: ident $(, $core: path)?) => {
+        crate::configure_parsers!(
+            "python" => {
+                language: tree_sitter_python::LANGUAGE,
+                node_types: tree_sitter_python::NODE_TYPES,
+                ast_root: $root,
+                $(core: $core,)?
+                comment: None,
+                fold: None,
+                highlights: None
+            }
+        );
+
+        fn create_workspace(source_code: &str) -> crate::core::workspace::Workspace {
+            crate::tests::create_workspace(
+                PARSERS.get("python").unwrap(),
+                "file:///test.py",
+                source_code,
+                false,
+            )
+        }
+    };
+}
+
+pub mod attributes;
+pub mod choices;
+pub mod codegen;
+pub mod completions;
+pub mod derived;
 pub mod html;
+pub mod lenient;
 pub mod python;
+pub mod queries;
+pub mod shapes;
+pub mod values;
+pub mod visitor;
+
+/// Parse `source_code` as the document at `url` and build its AST with the given parsers.
+///
+/// Build errors are kept in [`Workspace::errors`], the AST is `None` if the root failed to build.
+pub fn create_workspace(
+    parsers: &'static Parsers,
+    url: &str,
+    source_code: &str,
+    lenient: bool,
+) -> Workspace {
+    let tree = parsers
+        .tree_sitter
+        .parser
+        .write()
+        .parse(source_code.as_bytes(), None)
+        .unwrap();
+
+    let document = Document {
+        document: Text::new(source_code.into()),
+        cst: tree,
+        comment_style: parsers.tree_sitter.comment_style,
+        position_encoding: lsp_types::PositionEncodingKind::UTF8,
+    };
+
+    let mut diagnostics = vec![];
+    let mut unsolved_checks = vec![];
+    let mut unsolved_references = vec![];
+
+    let mut params = MainBuilder {
+        query: &parsers.tree_sitter.queries.core,
+        document: &document,
+        url: Arc::new(Url::parse(url).unwrap()),
+        diagnostics: &mut diagnostics,
+        unsolved_checks: &mut unsolved_checks,
+        unsolved_references: &mut unsolved_references,
+        changes: vec![],
+        lenient,
+    };
+
+    let ast = match (parsers.ast_parser)(&mut params, None) {
+        Ok(ast) => Some(ast),
+        Err(err) => {
+            diagnostics.push(err);
+            None
+        }
+    };
+
+    Workspace {
+        parsers,
+        document,
+        errors: diagnostics,
+        ast,
+        unsolved_checks,
+        unsolved_references,
+    }
+}
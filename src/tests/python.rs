@@ -1,10 +1,21 @@
-use crate::core::ast::{AstSymbol, BuildInlayHints, GetSymbolData, IsComment, VecOrSymbol};
+use crate::core::ast::{dump_ast, DumpFormat, SymbolId};
+use crate::core::ast::{
+    AstSymbol, BuildInlayHints, CollectSymbols, GetSymbolData, IsComment, VecOrSymbol,
+};
+use crate::core::build::{AstChange, MainBuilder};
 use crate::core::workspace::Workspace;
 use auto_lsp_core::ast::{BuildCodeLens, GetHover};
 use lsp_types::Url;
 use rstest::{fixture, rstest};
+use std::sync::Arc;
 
 use crate::python_workspace::*;
+use crate::server::capabilities::completion_items::{
+    get_anonymous_tokens, get_completion_items, get_documentation, get_keyword_items,
+};
+use crate::server::texter_impl::{change::WrapChange, updateable::WrapTree};
+use crate::server::Session;
+use texter::change::{Change, GridIndex};
 
 #[fixture]
 fn foo_bar() -> Workspace {
@@ -75,6 +86,441 @@ fn check_ast(foo_bar: Workspace) {
     assert!(parent.to_dyn().unwrap().read().is::<Function>());
 }
 
+/// Apply a change to the workspace the same way [`Session`] does when a document is edited
+fn apply_change(workspace: &mut Workspace, change: Change) -> Vec<AstChange> {
+    apply_changes(workspace, vec![change])
+}
+
+/// Apply changes sent in a single notification, each change being relative to the previous one
+fn apply_changes(workspace: &mut Workspace, changes: Vec<Change>) -> Vec<AstChange> {
+    let mut edits = vec![];
+    for change in changes {
+        let mut tree = WrapTree::from(&mut workspace.document.cst);
+        workspace
+            .document
+            .document
+            .update(change, &mut tree)
+            .unwrap();
+        edits.extend(tree.get_edits());
+    }
+
+    workspace.document.cst = workspace
+        .parsers
+        .tree_sitter
+        .parser
+        .write()
+        .parse(
+            workspace.document.document.text.as_bytes(),
+            Some(&workspace.document.cst),
+        )
+        .unwrap();
+
+    let mut builder = MainBuilder {
+        query: &workspace.parsers.tree_sitter.queries.core,
+        document: &workspace.document,
+        url: Arc::new(Url::parse("file:///test.py").unwrap()),
+        diagnostics: &mut workspace.errors,
+        unsolved_checks: &mut workspace.unsolved_checks,
+        unsolved_references: &mut workspace.unsolved_references,
+        changes: vec![],
+        lenient: false,
+    };
+    std::mem::take(
+        &mut builder
+            .swap_ast(
+                workspace.ast.as_mut().unwrap(),
+                &edits,
+                &workspace.parsers.ast_parser,
+            )
+            .resolve_references()
+            .resolve_checks()
+            .changes,
+    )
+}
+
+#[rstest]
+fn check_stable_ids(mut foo_bar: Workspace) {
+    let ids = |workspace: &Workspace| {
+        let ast = workspace.ast.as_ref().unwrap().read();
+        let module = ast.downcast_ref::<Module>().unwrap();
+        module
+            .functions
+            .iter()
+            .map(|function| {
+                let function = function.read();
+                (
+                    function.get_id(),
+                    function
+                        .parameters
+                        .iter()
+                        .map(|p| p.read().get_id())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    let before = ids(&foo_bar);
+
+    // Rename param1 to param10
+
+    let param1_end = foo_bar.document.document.text.find("param1").unwrap() + 6;
+    let old_param1 = {
+        let ast = foo_bar.ast.as_ref().unwrap().read();
+        let module = ast.downcast_ref::<Module>().unwrap();
+        let function = module.functions[0].read();
+        function.parameters[0].to_weak()
+    };
+    apply_change(
+        &mut foo_bar,
+        Change::Insert {
+            at: GridIndex {
+                row: 1,
+                col: param1_end - "# foo comment\n".len(),
+            },
+            text: "0".into(),
+        },
+    );
+
+    // The parameter was rebuilt, but kept its id
+
+    assert!(old_param1.to_dyn().is_none());
+    assert_eq!(ids(&foo_bar), before);
+
+    let param10 = foo_bar.get_symbol(before[0].1[0]).unwrap();
+    assert_eq!(
+        param10
+            .read()
+            .get_text(foo_bar.document.document.text.as_bytes())
+            .unwrap(),
+        "param10"
+    );
+    assert!(foo_bar.get_symbol(SymbolId(u64::MAX)).is_none());
+}
+
+/// Type and range of all the symbols of the ast, in depth-first order
+fn ast_shape(workspace: &Workspace) -> Vec<(std::any::TypeId, std::ops::Range<usize>)> {
+    let mut symbols = vec![];
+    workspace
+        .ast
+        .as_ref()
+        .unwrap()
+        .collect_symbols(&mut symbols);
+    symbols
+        .iter()
+        .map(|symbol| {
+            let read = symbol.read();
+            (read.as_any().type_id(), read.get_range())
+        })
+        .collect()
+}
+
+#[rstest]
+fn check_random_edits(mut foo_bar: Workspace) {
+    // Xorshift, so failures can be reproduced
+    let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+    let mut random = |max: usize| {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        (seed % max as u64) as usize
+    };
+
+    // `int` is matched by a predicate of the query, editing it changes which patterns match
+    const KEYWORDS: &[&str] = &["as", "def", "if", "in", "int", "is", "or", "pass"];
+
+    for _ in 0..50 {
+        let text = foo_bar.document.document.text.clone();
+
+        // Identifiers outside of the comment
+        let code_start = text.find('\n').unwrap() + 1;
+        let mut identifiers = vec![];
+        let mut start = None;
+        for (i, c) in text.char_indices().skip(code_start) {
+            match (start, c.is_ascii_alphanumeric() || c == '_') {
+                (None, true) => start = Some(i),
+                (Some(s), false) => {
+                    let word = &text[s..i];
+                    if !KEYWORDS.contains(&word) && !word.starts_with(|c: char| c.is_ascii_digit())
+                    {
+                        identifiers.push(s..i);
+                    }
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+
+        // Edit up to 3 identifiers at once, as a client with multiple cursors would do
+        let mut cursors = (0..1 + random(3))
+            .map(|_| identifiers[random(identifiers.len())].clone())
+            .collect::<Vec<_>>();
+        cursors.sort_by_key(|range| range.start);
+        cursors.dedup();
+
+        let mut changes = vec![];
+        let mut edited = text.clone();
+        let mut shift = 0isize;
+        for identifier in cursors {
+            // The first character is kept so identifiers remain valid
+            let at = identifier.start + 1 + random(identifier.len());
+            let deleted = match random(2) {
+                0 if at < identifier.end => 1,
+                _ => 0,
+            };
+            let inserted = ["_", "1", "x2", ""][random(4)];
+
+            let mut word = text[identifier.start..at].to_string();
+            word.push_str(inserted);
+            word.push_str(&text[at + deleted..identifier.end]);
+            if KEYWORDS.contains(&word.as_str()) || (deleted == 0 && inserted.is_empty()) {
+                continue;
+            }
+
+            // Changes are expressed in the coordinates of the text produced by the previous changes
+            let start = at.saturating_add_signed(shift);
+            let row = edited[..start].matches('\n').count();
+            let col = start - edited[..start].rfind('\n').map_or(0, |i| i + 1);
+            let index = GridIndex { row, col };
+            changes.push(match deleted {
+                0 => Change::Insert {
+                    at: index,
+                    text: inserted.into(),
+                },
+                _ => Change::Replace {
+                    start: index,
+                    end: GridIndex { row, col: col + 1 },
+                    text: inserted.into(),
+                },
+            });
+            edited.replace_range(start..start + deleted, inserted);
+            shift += inserted.len() as isize - deleted as isize;
+        }
+
+        apply_changes(&mut foo_bar, changes);
+        assert_eq!(foo_bar.document.document.text, edited);
+
+        let rebuilt = create_python_workspace(Url::parse("file:///test.py").unwrap(), edited);
+        assert_eq!(
+            ast_shape(&foo_bar),
+            ast_shape(&rebuilt),
+            "{}",
+            rebuilt.document.document.text
+        );
+    }
+}
+
+#[rstest]
+fn check_error_recovery(mut foo_bar: Workspace) {
+    let functions = |workspace: &Workspace| {
+        let ast = workspace.ast.as_ref().unwrap().read();
+        let module = ast.downcast_ref::<Module>().unwrap();
+        module
+            .functions
+            .iter()
+            .map(|function| {
+                let function = function.read();
+                (function.is_partial(), function.get_range())
+            })
+            .collect::<Vec<_>>()
+    };
+    let before = functions(&foo_bar);
+    assert!(before.iter().all(|(partial, _)| !partial));
+
+    // Insert an invalid character in foo's parameters
+
+    let param2 = GridIndex {
+        row: 1,
+        col: "def foo(param1, ".len(),
+    };
+    let changes = apply_change(
+        &mut foo_bar,
+        Change::Insert {
+            at: param2,
+            text: "$".into(),
+        },
+    );
+    assert!(!changes.is_empty());
+
+    // foo was rebuilt and is partial, bar is only shifted
+
+    let after = functions(&foo_bar);
+    assert!(after[0].0);
+    assert!(!after[1].0);
+    assert_eq!(after[1].1, before[1].1.start + 1..before[1].1.end + 1);
+    {
+        let ast = foo_bar.ast.as_ref().unwrap().read();
+        let module = ast.downcast_ref::<Module>().unwrap();
+        assert_eq!(module.functions[0].read().parameters.len(), 3);
+    }
+
+    // Fix the error
+
+    apply_change(
+        &mut foo_bar,
+        Change::Delete {
+            start: param2,
+            end: GridIndex {
+                row: 1,
+                col: param2.col + 1,
+            },
+        },
+    );
+    assert_eq!(functions(&foo_bar), before);
+
+    let rebuilt = create_python_workspace(
+        Url::parse("file:///test.py").unwrap(),
+        foo_bar.document.document.text.clone(),
+    );
+    assert_eq!(ast_shape(&foo_bar), ast_shape(&rebuilt));
+}
+
+#[rstest]
+fn check_ast_changes(mut foo_bar: Workspace) {
+    let param1_start = foo_bar.document.document.text.find("param1").unwrap();
+    let param1_id = {
+        let ast = foo_bar.ast.as_ref().unwrap().read();
+        let module = ast.downcast_ref::<Module>().unwrap();
+        let function = module.functions[0].read();
+        let id = function.parameters[0].read().get_id();
+        id
+    };
+
+    // Rename param1 to param10
+
+    let changes = apply_change(
+        &mut foo_bar,
+        Change::Insert {
+            at: GridIndex {
+                row: 1,
+                col: param1_start + 6 - "# foo comment\n".len(),
+            },
+            text: "0".into(),
+        },
+    );
+
+    // Only the parameter was replaced
+
+    assert_eq!(changes.len(), 1);
+    let change = &changes[0];
+    assert_eq!(change.old_range, param1_start..param1_start + 6);
+    assert_eq!(change.new_range, param1_start..param1_start + 7);
+    assert_eq!(change.symbol.read().get_id(), param1_id);
+    assert!(change.updated.contains(&param1_id));
+    assert!(change.added.is_empty());
+    assert!(change.removed.is_empty());
+}
+
+#[test]
+fn check_position_encoding() {
+    let mut workspace = create_python_workspace(
+        Url::parse("file:///test.py").unwrap(),
+        "x = \"é😀\"\r\ndef foo(): pass\n".into(),
+    );
+    let document = &mut workspace.document;
+    let position = |line, character| lsp_types::Position { line, character };
+
+    // UTF-8 columns are byte offsets
+
+    let quote = "x = \"é😀".len();
+    assert_eq!(document.position_at(quote), Some(position(0, 11)));
+    assert_eq!(document.offset_at(position(0, 11)), Some(quote));
+
+    // UTF-16 columns count surrogate pairs as two units
+
+    document.position_encoding = lsp_types::PositionEncodingKind::UTF16;
+    assert_eq!(document.position_at(quote), Some(position(0, 8)));
+    assert_eq!(document.offset_at(position(0, 8)), Some(quote));
+    // An offset within a character is moved back to the start of the character
+    assert_eq!(document.position_at(quote - 1), Some(position(0, 6)));
+    assert_eq!(document.offset_at(position(0, 7)), Some(quote - 4));
+
+    // UTF-32 columns count characters
+
+    document.position_encoding = lsp_types::PositionEncodingKind::UTF32;
+    assert_eq!(document.position_at(quote), Some(position(0, 7)));
+    assert_eq!(document.offset_at(position(0, 7)), Some(quote));
+
+    // Line breaks are not part of the line, characters past the end are moved back to the end of the line
+
+    let line_end = quote + 1;
+    assert_eq!(document.position_at(line_end), Some(position(0, 8)));
+    assert_eq!(document.offset_at(position(0, 100)), Some(line_end));
+    assert_eq!(document.offset_at(position(1, 4)), Some(line_end + 6));
+    assert_eq!(
+        document.range_at(line_end + 6..line_end + 9),
+        Some(lsp_types::Range {
+            start: position(1, 4),
+            end: position(1, 7)
+        })
+    );
+    assert_eq!(document.offset_at(position(3, 0)), None);
+}
+
+#[test]
+fn check_full_text_change() {
+    let mut workspace = create_python_workspace(
+        Url::parse("file:///test.py").unwrap(),
+        "def foo(param1):\r\n    pass\r\n".into(),
+    );
+    let grid = |row, col| GridIndex { row, col };
+
+    // Unchanged text
+
+    assert!(
+        WrapChange::from_full_text(&workspace.document, "def foo(param1):\r\n    pass\r\n")
+            .is_none()
+    );
+
+    // Only the differing part of the text is replaced
+
+    let new_text = "def foo(param1, param2):\r\n    pass\r\n";
+    let change = WrapChange::from_full_text(&workspace.document, new_text).unwrap();
+    assert_eq!(
+        change.change,
+        Change::Insert {
+            at: grid(0, 14),
+            text: ", param2".into()
+        }
+    );
+
+    let change =
+        WrapChange::from_full_text(&workspace.document, "def bar(param1):\r\n    pass\r\n")
+            .unwrap();
+    assert_eq!(
+        change.change,
+        Change::Replace {
+            start: grid(0, 4),
+            end: grid(0, 7),
+            text: "bar".into()
+        }
+    );
+
+    // Line breaks are never split
+
+    let change = WrapChange::from_full_text(&workspace.document, "def foo(param1):\r\n").unwrap();
+    assert_eq!(
+        change.change,
+        Change::Delete {
+            start: grid(1, 0),
+            end: grid(2, 0)
+        }
+    );
+
+    // Edits sent to tree-sitter match the change
+
+    let document = &mut workspace.document;
+    let change = WrapChange::from_full_text(document, new_text).unwrap();
+    let mut tree = WrapTree::from(&mut document.cst);
+    document.document.update(change.change, &mut tree).unwrap();
+    let edits = tree.get_edits();
+
+    assert_eq!(document.document.text, new_text);
+    assert_eq!(edits.len(), 1);
+    assert_eq!(edits[0].0.start_byte, 14);
+    assert_eq!(edits[0].0.old_end_byte, 14);
+    assert_eq!(edits[0].0.new_end_byte, 22);
+}
+
 #[rstest]
 fn check_foo_parameters(foo_bar: Workspace) {
     let ast = foo_bar.ast.as_ref().unwrap();
@@ -190,6 +636,84 @@ fn check_comment(foo_bar: Workspace) {
     );
 }
 
+#[test]
+fn check_merged_and_trailing_comments() {
+    let workspace = create_python_workspace(
+        Url::parse("file:///test.py").unwrap(),
+        r#"# first line
+# second line
+def foo(param1, param3: int = 5,  # default value
+):
+    pass
+"#
+        .into(),
+    );
+    let source_code = workspace.document.document.text.as_bytes();
+    let ast = workspace.ast.as_ref().unwrap();
+    let module = ast.read();
+    let module = module.downcast_ref::<Module>().unwrap();
+
+    // Consecutive line comments are merged
+
+    let function = module.functions[0].read();
+    assert_eq!(
+        function.get_comment(source_code),
+        Some("# first line\n# second line")
+    );
+
+    // Trailing comments are attached to symbols accepting them
+
+    let parameters = &function.parameters;
+    assert_eq!(parameters[0].read().get_comment(source_code), None);
+    assert_eq!(
+        parameters[1].read().get_comment(source_code),
+        Some("# default value")
+    );
+}
+
+#[test]
+fn check_incremental_comments() {
+    let workspace = create_python_workspace(
+        Url::parse("file:///test.py").unwrap(),
+        r#"# foo comment
+def foo():
+    pass
+
+# bar comment
+def bar():
+    pass
+"#
+        .into(),
+    );
+    let source_code = workspace.document.document.text.as_bytes();
+    let ast = workspace.ast.as_ref().unwrap();
+    let module = ast.read();
+    let module = module.downcast_ref::<Module>().unwrap();
+
+    for function in &module.functions {
+        function.write().set_comment(None);
+    }
+
+    // Only comments around the edit are attached again
+
+    let start_byte = source_code.len() - "# bar comment\ndef bar():\n    pass\n".len();
+    let edit = tree_sitter::InputEdit {
+        start_byte,
+        old_end_byte: start_byte + 1,
+        new_end_byte: start_byte + 1,
+        start_position: tree_sitter::Point::new(4, 0),
+        old_end_position: tree_sitter::Point::new(4, 1),
+        new_end_position: tree_sitter::Point::new(4, 1),
+    };
+    Session::update_comments(&workspace, &[(edit, false)]).unwrap();
+
+    assert_eq!(module.functions[0].read().get_comment(source_code), None);
+    assert_eq!(
+        module.functions[1].read().get_comment(source_code),
+        Some("# bar comment")
+    );
+}
+
 #[rstest]
 fn check_document_symbols(foo_bar: Workspace) {
     let ast = foo_bar.ast.as_ref().unwrap();
@@ -280,6 +804,43 @@ fn check_hover(foo_bar: Workspace) {
     );
 }
 
+#[rstest]
+fn check_markdown_hover(foo_bar: Workspace) {
+    let ast = foo_bar.ast.as_ref().unwrap();
+
+    let module = ast.read();
+    let module = module.downcast_ref::<Module>().unwrap();
+
+    // Comment markers are stripped and the declaration is shown in a code block
+    let foo_hover = module.functions[0]
+        .read()
+        .get_hover(&foo_bar.document)
+        .unwrap();
+
+    assert_eq!(
+        foo_hover.contents,
+        lsp_types::HoverContents::Markup(lsp_types::MarkupContent {
+            kind: lsp_types::MarkupKind::Markdown,
+            value:
+                "```python\ndef foo(param1, param2: int, param3: int = 5):\n```\n---\nfoo comment"
+                    .into(),
+        })
+    );
+
+    let bar_hover = module.functions[1]
+        .read()
+        .get_hover(&foo_bar.document)
+        .unwrap();
+
+    assert_eq!(
+        bar_hover.contents,
+        lsp_types::HoverContents::Markup(lsp_types::MarkupContent {
+            kind: lsp_types::MarkupKind::Markdown,
+            value: "```python\ndef bar():\n```".into(),
+        })
+    );
+}
+
 #[rstest]
 fn check_inlay_hints(foo_bar: Workspace) {
     let ast = foo_bar.ast.as_ref().unwrap();
@@ -318,3 +879,221 @@ fn check_code_lens(foo_bar: Workspace) {
     assert_eq!(code_lens[1].range.end.line, 4);
     assert_eq!(code_lens[1].range.end.character, 7);
 }
+
+#[rstest]
+fn check_completion_items(foo_bar: Workspace) {
+    let source_code = foo_bar.document.document.text.as_str();
+
+    let labels = |offset: usize| {
+        get_completion_items(&foo_bar, offset)
+            .into_iter()
+            .map(|item| item.label)
+            .collect::<Vec<_>>()
+    };
+
+    // Inside foo, parameters are visible before global functions
+    let foo_body = source_code.find("pass").unwrap();
+    assert_eq!(labels(foo_body), vec!["param2", "param3", "foo", "bar"]);
+
+    // Inside bar, foo's parameters are not visible
+    let bar_body = source_code.rfind("pass").unwrap();
+    assert_eq!(labels(bar_body), vec!["foo", "bar"]);
+
+    // Items are filtered by the word under the cursor
+    let param = source_code.find("param2").unwrap() + "par".len();
+    assert_eq!(labels(param), vec!["param2", "param3"]);
+}
+
+#[rstest]
+fn check_completion_snippets(foo_bar: Workspace) {
+    let items = get_completion_items(&foo_bar, 0);
+
+    let foo = items.iter().find(|item| item.label == "foo").unwrap();
+    assert_eq!(
+        foo.insert_text.as_deref(),
+        Some("foo(${1:param1}, ${2:param2}, ${3:param3})")
+    );
+    assert_eq!(
+        foo.insert_text_format,
+        Some(lsp_types::InsertTextFormat::SNIPPET)
+    );
+
+    let bar = items.iter().find(|item| item.label == "bar").unwrap();
+    assert_eq!(bar.insert_text.as_deref(), Some("bar()"));
+}
+
+#[rstest]
+fn check_completion_documentation(foo_bar: Workspace) {
+    let items = get_completion_items(&foo_bar, 0);
+
+    let offset = |label: &str| {
+        let item = items.iter().find(|item| item.label == label).unwrap();
+        item.data.as_ref().unwrap()["offset"].as_u64().unwrap() as usize
+    };
+
+    // Documentation is taken from the hover information
+    assert_eq!(
+        get_documentation(&foo_bar, offset("foo")),
+        Some(lsp_types::Documentation::MarkupContent(
+            lsp_types::MarkupContent {
+                kind: lsp_types::MarkupKind::Markdown,
+                value: "```python\ndef foo(param1, param2: int, param3: int = 5):\n```\n---\nfoo comment"
+                    .into(),
+            }
+        ))
+    );
+    assert_eq!(
+        get_documentation(&foo_bar, offset("bar")),
+        Some(lsp_types::Documentation::MarkupContent(
+            lsp_types::MarkupContent {
+                kind: lsp_types::MarkupKind::Markdown,
+                value: "```python\ndef bar():\n```".into(),
+            }
+        ))
+    );
+}
+
+#[rstest]
+fn check_keyword_completion_items(foo_bar: Workspace) {
+    let tokens = get_anonymous_tokens(foo_bar.parsers.tree_sitter.node_types);
+    assert!(tokens.contains("def"));
+    assert!(tokens.contains("("));
+
+    // At the start of a module, statements keywords are valid
+    let items = get_keyword_items(&foo_bar, 0, &tokens);
+    let labels = items.iter().map(|i| i.label.as_str()).collect::<Vec<_>>();
+    assert!(labels.contains(&"def"));
+    assert!(labels.contains(&"class"));
+    assert!(labels.contains(&"import"));
+    // Only valid tokens are offered
+    assert!(!labels.contains(&"else"));
+
+    let def = items.iter().find(|i| i.label == "def").unwrap();
+    assert_eq!(def.kind, Some(lsp_types::CompletionItemKind::KEYWORD));
+
+    // Keywords are filtered by the word under the cursor
+    let workspace = create_python_workspace(Url::parse("file:///de.py").unwrap(), "de".into());
+    let items = get_keyword_items(&workspace, 2, &tokens);
+    let mut labels = items.iter().map(|i| i.label.as_str()).collect::<Vec<_>>();
+    labels.sort();
+    assert_eq!(labels, vec!["def", "del"]);
+}
+
+#[rstest]
+fn check_dump_ast(foo_bar: Workspace) {
+    assert_eq!(
+        dump_ast(&foo_bar, DumpFormat::Tree),
+        r#"Module 0..93
+  Function 14..69
+    Identifier 18..21 "foo"
+    Parameter(UntypedParameter) 22..28 "param1"
+    Parameter(TypedParameter) 30..41
+      Identifier 30..36 "param2"
+      Type(Int) 38..41 "int"
+    Parameter(TypedDefaultParameter) 43..58
+      Identifier 43..49 "param3"
+      Type(Int) 51..54 "int"
+      Any 57..58 "5"
+  Function 71..90
+    Identifier 75..78 "bar"
+"#
+    );
+
+    assert_eq!(
+        dump_ast(&foo_bar, DumpFormat::SExpression),
+        r#"(Module 0..93 (Function 14..69 (Identifier 18..21 "foo") (Parameter(UntypedParameter) 22..28 "param1") (Parameter(TypedParameter) 30..41 (Identifier 30..36 "param2") (Type(Int) 38..41 "int")) (Parameter(TypedDefaultParameter) 43..58 (Identifier 43..49 "param3") (Type(Int) 51..54 "int") (Any 57..58 "5"))) (Function 71..90 (Identifier 75..78 "bar")))"#
+    );
+}
+
+#[rstest]
+fn check_debug(foo_bar: Workspace) {
+    let ast = foo_bar.ast.as_ref().unwrap();
+    assert_eq!(ast.to_string(), "Module 0..93");
+
+    // Ids are unique within the process, only the shape of the output is checked
+    let debug = format!("{:?}", ast);
+    assert!(
+        debug.starts_with("Module { functions: [Function { name: Identifier { _data: SymbolData {")
+    );
+    assert!(debug.contains("parameters: [Untyped(UntypedParameter {"));
+    assert!(debug.contains("parameter_type: Int(Int {"));
+    // Parents are printed as ids instead of symbols
+    assert!(debug.contains("parent: Some(WeakSymbol(SymbolId("));
+
+    // A symbol locked for writing is not printed
+    let _write = ast.write();
+    assert_eq!(format!("{:?}", ast), "<locked>");
+    assert_eq!(ast.to_string(), "<locked>");
+}
+
+#[cfg(feature = "serde")]
+#[rstest]
+fn check_serialize(foo_bar: Workspace) {
+    let json = serde_json::to_value(&foo_bar).unwrap();
+    assert_eq!(json["errors"], serde_json::json!([]));
+
+    let module = &json["ast"];
+    assert_eq!(module["type"], "Module");
+    assert_eq!(
+        module["range"],
+        serde_json::json!({ "start": 0, "end": 93 })
+    );
+
+    let foo = &module["fields"]["functions"][0];
+    assert_eq!(foo["type"], "Function");
+    assert_eq!(foo["comment"], "# foo comment");
+    assert_eq!(foo["target"], serde_json::Value::Null);
+
+    let name = &foo["fields"]["name"];
+    assert_eq!(name["type"], "Identifier");
+    assert_eq!(name["range"], serde_json::json!({ "start": 18, "end": 21 }));
+    assert_eq!(
+        name["lsp_range"],
+        serde_json::json!({
+            "start": { "line": 1, "character": 4 },
+            "end": { "line": 1, "character": 7 }
+        })
+    );
+    assert_eq!(name["fields"], serde_json::json!({}));
+
+    // Choices are serialized with the fields of their variant
+    let param2 = &foo["fields"]["parameters"][1];
+    assert_eq!(param2["type"], "Parameter");
+    assert_eq!(param2["variant"], "TypedParameter");
+    assert_eq!(param2["fields"]["name"]["type"], "Identifier");
+    assert_eq!(param2["fields"]["parameter_type"]["type"], "Type");
+    assert_eq!(param2["fields"]["parameter_type"]["variant"], "Int");
+    assert_eq!(foo["variant"], serde_json::Value::Null);
+
+    let bar = &module["fields"]["functions"][1];
+    assert_eq!(bar["fields"]["parameters"], serde_json::json!([]));
+}
+
+#[cfg(feature = "serde")]
+#[rstest]
+fn check_serialize_target(foo_bar: Workspace) {
+    // bar's name targets foo's name
+    let ast = foo_bar.ast.as_ref().unwrap();
+    let module = ast.read();
+    let module = module.downcast_ref::<Module>().unwrap();
+    let foo_name = module.functions[0].read().name.to_dyn();
+    module.functions[1]
+        .read()
+        .name
+        .write()
+        .set_target_reference(crate::core::ast::WeakSymbol::new(&foo_name));
+
+    let json = serde_json::to_value(&foo_bar).unwrap();
+    let bar_name = &json["ast"]["fields"]["functions"][1]["fields"]["name"];
+    assert_eq!(
+        bar_name["target"],
+        serde_json::json!({
+            "uri": "file:///test.py",
+            "range": { "start": 18, "end": 21 },
+            "lsp_range": {
+                "start": { "line": 1, "character": 4 },
+                "end": { "line": 1, "character": 7 }
+            }
+        })
+    );
+}
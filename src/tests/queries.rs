@@ -0,0 +1,41 @@
+use super::visitor::{Module, CORE_QUERY};
+use crate::core::build::check_query_names;
+use tree_sitter::Query;
+
+fn check(query: &str) -> Result<(), String> {
+    let query = Query::new(&tree_sitter_python::LANGUAGE.into(), query).unwrap();
+    check_query_names::<Module>(&query)
+}
+
+#[test]
+fn missing_query_names() {
+    let err = check(
+        "
+(module) @module
+
+(function_definition
+  name: (identifier) @name) @fucntion
+",
+    )
+    .unwrap_err();
+
+    assert!(err.contains(r#"Query names missing from the query: ["class", "function"]"#));
+    assert!(err.contains(r#"Captures not consumed by any symbol: ["fucntion"]"#));
+}
+
+#[test]
+fn unused_captures() {
+    let err = check(&format!("{}\n(decorator) @decorator", CORE_QUERY)).unwrap_err();
+
+    assert_eq!(err, r#"Captures not consumed by any symbol: ["decorator"]"#);
+}
+
+#[test]
+fn private_captures() {
+    let query = format!(
+        "{}\n((identifier) @_self (#eq? @_self \"self\"))",
+        CORE_QUERY
+    );
+
+    assert_eq!(check(&query), Ok(()));
+}
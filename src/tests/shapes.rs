@@ -0,0 +1,66 @@
+use crate::core::ast::{GetSymbolData, Locator, Symbol};
+use crate::seq;
+
+use crate as auto_lsp;
+
+// Function names are captured both as a symbol and as text
+static CORE_QUERY: &str = "
+(module) @module
+
+(function_definition
+  name: (identifier) @name @identifier) @function
+";
+
+configure_python_test!(Module, CORE_QUERY);
+
+mod ast {
+    use crate as auto_lsp;
+    use crate::seq;
+
+    #[seq(query_name = "name", kind(symbol()))]
+    pub struct Name(#[ast(text = "identifier")] String);
+}
+
+#[seq(query_name = "module", kind(symbol()))]
+struct Module {
+    functions: Vec<Box<Function>>,
+}
+
+#[seq(query_name = "function", kind(symbol()))]
+struct Function(ast::Name);
+
+#[test]
+fn tuple_and_boxed_fields() {
+    let workspace = create_workspace(
+        r#"def foo():
+    pass
+
+def bar():
+    pass
+"#,
+    );
+    assert!(workspace.errors.is_empty());
+
+    let ast = workspace.ast.as_ref().unwrap().read();
+    let module = ast.downcast_ref::<Module>().unwrap();
+
+    let names = module
+        .functions
+        .iter()
+        .map(|function| function.read().0.read().0.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["foo", "bar"]);
+
+    // Tuple structs implement the same traits as other symbols
+    let bar = &module.functions[1];
+    assert_eq!(bar.read().get_range(), 21..40);
+    let found = module.find_at_offset(26).unwrap();
+    assert_eq!(found.read().get_range(), 25..28);
+    assert!(found
+        .read()
+        .get_parent()
+        .unwrap()
+        .to_dyn()
+        .unwrap()
+        .ptr_eq(&bar.to_dyn()));
+}
@@ -0,0 +1,161 @@
+use crate::seq;
+
+use crate as auto_lsp;
+
+static CORE_QUERY: &str = "
+(module) @module
+
+(function_definition
+  name: (identifier) @name) @function
+
+(default_parameter
+  value: (integer) @default)
+
+(function_definition
+  return_type: (type) @return_type)
+";
+
+configure_python_test!(Module, CORE_QUERY);
+
+#[seq(query_name = "module", kind(symbol()))]
+struct Module {
+    functions: Vec<Function>,
+}
+
+#[seq(query_name = "function", kind(symbol()))]
+struct Function {
+    #[ast(text = "name")]
+    name: String,
+    #[ast(text = "default")]
+    defaults: Vec<i64>,
+    #[ast(text = "return_type")]
+    return_type: Option<String>,
+}
+
+#[test]
+fn parse_values() {
+    let workspace = create_workspace(
+        r#"def foo(a = 1, b = 20) -> int:
+    pass
+
+def bar():
+    pass
+"#,
+    );
+    assert!(workspace.errors.is_empty());
+
+    let ast = workspace.ast.as_ref().unwrap().read();
+    let module = ast.downcast_ref::<Module>().unwrap();
+    assert_eq!(module.functions.len(), 2);
+
+    let foo = module.functions[0].read();
+    assert_eq!(foo.name, "foo");
+    assert_eq!(foo.defaults, vec![1, 20]);
+    assert_eq!(foo.return_type.as_deref(), Some("int"));
+
+    let bar = module.functions[1].read();
+    assert_eq!(bar.name, "bar");
+    assert!(bar.defaults.is_empty());
+    assert!(bar.return_type.is_none());
+}
+
+#[test]
+fn invalid_value() {
+    // 0x10 is a valid integer literal but not a valid i64
+    let workspace = create_workspace(
+        r#"def foo(a = 0x10):
+    pass
+"#,
+    );
+
+    assert!(workspace.ast.is_none());
+    assert_eq!(workspace.errors.len(), 1);
+    assert_eq!(workspace.errors[0].range.start.character, 12);
+    assert_eq!(
+        workspace.errors[0].message,
+        "Invalid \"defaults\" for \"Function\", \"0x10\" is not a valid i64"
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialize_values() {
+    use crate::core::ast::SymbolJson;
+
+    let workspace = create_workspace(
+        r#"def foo(a = 1, b = 20) -> int:
+    pass
+
+def bar():
+    pass
+"#,
+    );
+    assert!(workspace.errors.is_empty());
+
+    let ast = workspace.ast.as_ref().unwrap();
+    let json = serde_json::to_value(SymbolJson::new(ast, &workspace.document)).unwrap();
+
+    let functions = &json["fields"]["functions"];
+    assert_eq!(
+        functions[0]["fields"],
+        serde_json::json!({ "name": "foo", "defaults": [1, 20], "return_type": "int" })
+    );
+    assert_eq!(
+        functions[1]["fields"],
+        serde_json::json!({ "name": "bar", "defaults": [], "return_type": null })
+    );
+}
+
+// Errors of value fields are propagated to the serializer
+#[cfg(feature = "serde")]
+mod serialize_error {
+    use crate::seq;
+
+    use crate as auto_lsp;
+
+    static CORE_QUERY: &str = "
+(module) @module
+
+(function_definition
+  name: (identifier) @name) @function
+";
+
+    configure_python_test!(Module, CORE_QUERY);
+
+    #[seq(query_name = "module", kind(symbol()))]
+    struct Module {
+        functions: Vec<Function>,
+    }
+
+    #[seq(query_name = "function", kind(symbol()))]
+    struct Function {
+        #[ast(text = "name")]
+        name: Secret,
+    }
+
+    #[derive(Debug, Clone)]
+    struct Secret;
+
+    impl std::str::FromStr for Secret {
+        type Err = ();
+
+        fn from_str(_s: &str) -> Result<Self, Self::Err> {
+            Ok(Secret)
+        }
+    }
+
+    impl serde::Serialize for Secret {
+        fn serialize<S: serde::Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+            Err(serde::ser::Error::custom("secrets are not serialized"))
+        }
+    }
+
+    #[test]
+    fn serialize_error() {
+        let workspace = create_workspace("def foo():\n    pass\n");
+        assert!(workspace.errors.is_empty());
+
+        let err = serde_json::to_string(&workspace).unwrap_err();
+        assert_eq!(err.to_string(), "secrets are not serialized");
+    }
+}
@@ -0,0 +1,172 @@
+use crate::core::ast::{
+    AstSymbol, BuildCompletionItems, GetSymbolData, Symbol, Visit, VisitMut, Walk, WalkMut,
+};
+use crate::{choice, seq, visitor};
+
+use crate as auto_lsp;
+
+pub(super) static CORE_QUERY: &str = "
+(module) @module
+
+(function_definition
+  name: (identifier) @name) @function
+
+(class_definition
+  name: (identifier) @name) @class
+";
+
+configure_python_test!(Module, CORE_QUERY);
+
+#[seq(query_name = "module", kind(symbol()))]
+pub(super) struct Module {
+    definitions: Vec<Definition>,
+}
+
+#[choice]
+enum Definition {
+    Function(Function),
+    Class(Class),
+}
+
+#[seq(query_name = "function", kind(symbol()))]
+struct Function {
+    name: Name,
+}
+
+#[seq(query_name = "class", kind(symbol(
+    lsp_completion_items(
+        code_gen(
+            item(
+                label = self::name,
+                kind = auto_lsp::lsp_types::CompletionItemKind::CLASS,
+                snippet(placeholders(self::methods::name))
+            )
+        )
+    )
+)))]
+struct Class {
+    name: Name,
+    methods: Vec<Function>,
+}
+
+#[seq(query_name = "name", kind(symbol()))]
+struct Name {}
+
+visitor!(Module, Definition, Function, Class, Name);
+
+static SOURCE: &str = r#"def foo():
+    pass
+
+class Bar:
+    def baz(self):
+        pass
+"#;
+
+#[test]
+fn children() {
+    let workspace = create_workspace(SOURCE);
+    assert!(workspace.errors.is_empty());
+
+    let ast = workspace.ast.as_ref().unwrap().read();
+    let module = ast.downcast_ref::<Module>().unwrap();
+
+    let definitions = module.children().collect::<Vec<_>>();
+    assert_eq!(definitions.len(), 2);
+    assert!(definitions[0].read().is::<Definition>());
+
+    // Children of a choice are the children of its variant
+    let class = definitions[1].read();
+    let children = class.children().collect::<Vec<_>>();
+    assert_eq!(children.len(), 2);
+    assert!(children[0].read().is::<Name>());
+    assert!(children[1].read().is::<Function>());
+
+    // Symbols without fields have no children
+    assert_eq!(children[0].read().children().count(), 0);
+}
+
+struct Names<'a> {
+    source: &'a [u8],
+    names: Vec<String>,
+    definitions: usize,
+}
+
+impl Visitor for Names<'_> {
+    fn visit_definition(&mut self, node: &Definition) {
+        self.definitions += 1;
+        node.walk(self);
+    }
+
+    fn visit_name(&mut self, node: &Name) {
+        self.names
+            .push(node.get_text(self.source).unwrap().to_string());
+    }
+}
+
+#[test]
+fn visitor() {
+    let workspace = create_workspace(SOURCE);
+    assert!(workspace.errors.is_empty());
+
+    let ast = workspace.ast.as_ref().unwrap().read();
+    let module = ast.downcast_ref::<Module>().unwrap();
+
+    let mut names = Names {
+        source: workspace.document.document.text.as_bytes(),
+        names: vec![],
+        definitions: 0,
+    };
+    module.accept(&mut names);
+
+    assert_eq!(names.names, vec!["foo", "Bar", "baz"]);
+    // Methods are functions, not definitions
+    assert_eq!(names.definitions, 2);
+}
+
+/// Stops at classes, methods are not visited
+struct MarkFunctions;
+
+impl VisitorMut for MarkFunctions {
+    fn visit_function(&mut self, node: &mut Function) {
+        node.set_partial(true);
+        node.walk_mut(self);
+    }
+
+    fn visit_class(&mut self, _node: &mut Class) {}
+}
+
+#[test]
+fn visitor_mut() {
+    let workspace = create_workspace(SOURCE);
+    assert!(workspace.errors.is_empty());
+
+    let mut ast = workspace.ast.as_ref().unwrap().write();
+    let module = ast.downcast_mut::<Module>().unwrap();
+    module.accept_mut(&mut MarkFunctions);
+
+    let Definition::Function(foo) = &*module.definitions[0].read() else {
+        panic!("Expected a function");
+    };
+    assert!(foo.is_partial());
+
+    let Definition::Class(bar) = &*module.definitions[1].read() else {
+        panic!("Expected a class");
+    };
+    assert!(!bar.methods[0].read().is_partial());
+}
+
+#[test]
+fn snippet_from_child_field() {
+    let workspace = create_workspace(SOURCE);
+    assert!(workspace.errors.is_empty());
+
+    let ast = workspace.ast.as_ref().unwrap().read();
+    let module = ast.downcast_ref::<Module>().unwrap();
+    let Definition::Class(bar) = &*module.definitions[1].read() else {
+        panic!("Expected a class");
+    };
+
+    let mut items = vec![];
+    bar.build_completion_items(&mut items, &workspace.document);
+    assert_eq!(items[0].insert_text.as_deref(), Some("Bar(${1:baz})"));
+}